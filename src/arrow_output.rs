@@ -0,0 +1,119 @@
+use anyhow::Result;
+use arrow_array::{ArrayRef, BooleanArray, Float64Array, Int64Array, RecordBatch, StringArray};
+use arrow_ipc::writer::StreamWriter;
+use arrow_schema::{Field, Schema, SchemaRef};
+use csv::StringRecord;
+use std::io::Write;
+use std::sync::Arc;
+
+use crate::convert::{OutputColumn, RowWriter};
+use crate::types::{DataType, Value, is_null, parse_value};
+
+/// Rows per record batch
+const BATCH_SIZE: usize = 8192;
+
+/// Writes rows as an Arrow IPC stream, typed by the inferred schema. Values
+/// that don't parse as the column's type become null.
+pub struct ArrowRowWriter {
+    writer: StreamWriter<Box<dyn Write>>,
+    schema: SchemaRef,
+    types: Vec<DataType>,
+    buffered: Vec<StringRecord>,
+}
+
+impl ArrowRowWriter {
+    pub fn new(output: Box<dyn Write>, columns: &[OutputColumn]) -> Result<Self> {
+        let fields: Vec<Field> = columns
+            .iter()
+            .map(|c| Field::new(&c.name, arrow_type(c.data_type), true))
+            .collect();
+        let schema = Arc::new(Schema::new(fields));
+
+        Ok(Self {
+            writer: StreamWriter::try_new(output, &schema)?,
+            schema,
+            types: columns.iter().map(|c| c.data_type).collect(),
+            buffered: Vec::with_capacity(BATCH_SIZE),
+        })
+    }
+
+    fn flush_batch(&mut self) -> Result<()> {
+        if self.buffered.is_empty() {
+            return Ok(());
+        }
+
+        let columns: Vec<ArrayRef> = self
+            .types
+            .iter()
+            .enumerate()
+            .map(|(i, dtype)| build_array(&self.buffered, i, *dtype))
+            .collect();
+        let batch = RecordBatch::try_new(self.schema.clone(), columns)?;
+        self.writer.write(&batch)?;
+        self.buffered.clear();
+        Ok(())
+    }
+}
+
+impl RowWriter for ArrowRowWriter {
+    fn write_record(&mut self, record: &StringRecord) -> Result<()> {
+        self.buffered.push(record.clone());
+        if self.buffered.len() >= BATCH_SIZE {
+            self.flush_batch()?;
+        }
+        Ok(())
+    }
+
+    fn finish(mut self: Box<Self>) -> Result<()> {
+        self.flush_batch()?;
+        self.writer.finish()?;
+        self.writer.get_mut().flush()?;
+        Ok(())
+    }
+}
+
+fn arrow_type(dtype: DataType) -> arrow_schema::DataType {
+    match dtype {
+        DataType::Integer => arrow_schema::DataType::Int64,
+        DataType::Float => arrow_schema::DataType::Float64,
+        DataType::Boolean => arrow_schema::DataType::Boolean,
+        DataType::String => arrow_schema::DataType::Utf8,
+    }
+}
+
+fn build_array(records: &[StringRecord], index: usize, dtype: DataType) -> ArrayRef {
+    let cells = records
+        .iter()
+        .map(move |r| r.get(index).filter(|v| !is_null(v)).map(str::trim));
+
+    match dtype {
+        DataType::Integer => Arc::new(
+            cells
+                .map(|v| match v.map(parse_value) {
+                    Some((_, Value::Integer(i))) => Some(i), // Also 1e3
+                    _ => None,
+                })
+                .collect::<Int64Array>(),
+        ),
+        DataType::Float => Arc::new(
+            cells
+                .map(|v| v.and_then(|v| v.parse::<f64>().ok()))
+                .collect::<Float64Array>(),
+        ),
+        DataType::Boolean => Arc::new(
+            cells
+                .map(|v| match v.map(str::to_lowercase).as_deref() {
+                    Some("true") => Some(true),
+                    Some("false") => Some(false),
+                    _ => None,
+                })
+                .collect::<BooleanArray>(),
+        ),
+        DataType::String => Arc::new(
+            records
+                .iter()
+                .map(|r| r.get(index).filter(|v| !is_null(v)))
+                .collect::<StringArray>(),
+        ),
+    }
+}
@@ -1,16 +1,42 @@
 use anyhow::Result;
-use clap::{Parser, Subcommand};
+use clap::error::ErrorKind;
+use clap::{CommandFactory, Parser, Subcommand};
 use csv::StringRecord;
+use regex::Regex;
+use std::collections::HashMap;
+use std::ops::Range;
 
-use crate::error::{ColumnSuggestion, CsvpeekError, find_similar_column};
+use crate::csv_reader::{DEFAULT_DETECT_BYTES, DEFAULT_INPUT_BUFFER_SIZE, DEFAULT_MAX_FIELD_BYTES};
+use crate::error::{ColumnSuggestion, CsvpeekError, find_similar_column, find_similar_name};
+use crate::output::DEFAULT_MAX_CELL_WIDTH;
+use crate::types::DataType;
 
 const MAIN_HELP: &str = r#"
 EXAMPLES:
     csvp data.csv                    Show summary statistics (default)
     csvp data.csv -c "name,age"      Analyze specific columns
     csvp data.csv -w "age > 30"      Filter rows before analysis
+    csvp data.csv -g region -c sales Per-group statistics
     csvp schema data.csv             Show schema information
+    csvp profile data.csv            Schema and statistics in one pass
+    csvp pivot data.csv --rows region -c status
+                                     Cross-tabulate two columns
+    csvp freq data.csv -c age --bin-width 10
+                                     Count values, numbers in buckets
+    csvp diff old.csv new.csv        Compare schemas of two files
+    csvp count data.csv --fast-count Count rows without parsing
+    csvp convert data.csv --to tsv -o out.tsv
+                                     Write a normalized copy
     csvp data.csv -d ";" -e sjis     Semicolon-delimited, Shift_JIS
+    csvp data.csv --byte-start 1000000 --byte-end 2000000
+                                     Analyze one shard of a large file
+    csvp big.csv -c price -w "qty > 5" --check
+                                     Validate columns and filter, read nothing
+
+DEFAULT COMMAND:
+    Without a subcommand csvp runs summary. Set CSVP_DEFAULT_COMMAND to
+    schema, profile or count for a lighter default; a subcommand given on
+    the command line always wins.
 
 OUTPUT FORMATS:
     -f table    Pretty table (default)
@@ -33,30 +59,142 @@ pub struct Cli {
     #[command(subcommand)]
     pub command: Option<Commands>,
 
-    /// CSV file path
+    /// CSV file path, or an http(s) URL with the `http` feature
     #[arg(global = true)]
     pub file: Option<String>,
 
-    /// Columns to analyze (names, indices, or ranges like 0..5)
+    /// Columns to analyze (names, indices, ranges like 0..5, or wildcards like metric_*)
     #[arg(long, short = 'c', global = true)]
     pub cols: Option<String>,
 
+    /// Also select the columns listed in this file, one per line or comma-separated
+    #[arg(long, global = true, value_name = "PATH")]
+    pub cols_file: Option<String>,
+
+    /// Also select every column whose name matches this regex (e.g. "^metric_")
+    #[arg(long, global = true, value_name = "REGEX")]
+    pub cols_match: Option<String>,
+
+    /// Output -c columns in file order rather than the order given
+    #[arg(long, global = true, default_value = "false")]
+    pub preserve_order: bool,
+
     /// Filter expression (e.g., "age > 30", "status == \"active\"")
     #[arg(long = "where", short = 'w', global = true)]
     pub where_clause: Option<String>,
 
+    /// What --where comparisons give on null cells: none (unknown, as in SQL) or ne (only != matches)
+    #[arg(long, global = true, value_name = "POLICY", default_value = "none")]
+    pub null_matches: String,
+
+    /// Error when --where reads a column a short row doesn't have (default: read it as null)
+    #[arg(long, global = true, default_value = "false")]
+    pub strict_rows: bool,
+
+    /// Only rows whose --date-col is on or after this date (YYYY-MM-DD)
+    #[arg(long, global = true, value_name = "DATE", requires = "date_col")]
+    pub since: Option<String>,
+
+    /// Only rows whose --date-col is on or before this date (YYYY-MM-DD)
+    #[arg(long, global = true, value_name = "DATE", requires = "date_col")]
+    pub until: Option<String>,
+
+    /// Date column for --since/--until
+    #[arg(long, global = true, value_name = "COLUMN")]
+    pub date_col: Option<String>,
+
+    /// Compute statistics separately for each distinct value of this column (summary only)
+    #[arg(long, short = 'g', global = true)]
+    pub group_by: Option<String>,
+
+    /// Most distinct groups --group-by keeps
+    #[arg(long, global = true, value_name = "N", default_value_t = 10_000)]
+    pub max_groups: usize,
+
+    /// Past --max-groups: error, or top (keep the largest groups, pool the rest into (other))
+    #[arg(long, global = true, value_name = "MODE", default_value = "error")]
+    pub group_overflow: String,
+
+    /// Add a weighted_mean of numeric columns, weighted by this column
+    #[arg(long, global = true, value_name = "COLUMN")]
+    pub weight_col: Option<String>,
+
+    /// Validate options against the header and exit without reading rows
+    #[arg(long, global = true, default_value = "false")]
+    pub check: bool,
+
+    /// Fail unless the header has every one of these columns (e.g. "id,amount,date")
+    #[arg(long, global = true, value_name = "COLUMNS")]
+    pub require_columns: Option<String>,
+
+    /// Show the first and last N raw rows above the table (summary, schema)
+    #[arg(long, global = true, value_name = "N")]
+    pub preview: Option<usize>,
+
+    /// Rename columns in the output (e.g., "col0=date,col1=amount"); -c, -w
+    /// and other options accept either name
+    #[arg(long, global = true)]
+    pub rename: Option<String>,
+
+    /// Force column types, bypassing inference (e.g., "zip=string,flag=boolean")
+    #[arg(long, global = true)]
+    pub types: Option<String>,
+
     /// Output format (table, json, ndjson, csv)
     #[arg(long, short = 'f', global = true)]
     pub format: Option<String>,
 
-    /// Field delimiter character
+    /// Field delimiter (a character, a name like tab/pipe, an escape like \x1f,
+    /// or a multi-character separator like :: without quoting)
     #[arg(long, short = 'd', global = true, default_value = ",")]
     pub delimiter: String,
 
+    /// Record terminator character (default: \n or \r\n)
+    #[arg(long, global = true)]
+    pub terminator: Option<String>,
+
+    /// Escape character for quotes inside quoted fields, e.g. \ for \"
+    #[arg(long, global = true, value_name = "CHAR")]
+    pub escape: Option<String>,
+
+    /// Abort when a single field exceeds this many bytes
+    #[arg(long, global = true, default_value_t = DEFAULT_MAX_FIELD_BYTES)]
+    pub max_field_bytes: usize,
+
+    /// Size of the read and parse buffers when streaming UTF-8 input
+    #[arg(long, global = true, value_name = "BYTES", default_value_t = DEFAULT_INPUT_BUFFER_SIZE)]
+    pub input_buffer_size: usize,
+
+    /// Skip malformed rows with a warning instead of stopping at the first
+    #[arg(long, global = true, default_value = "false")]
+    pub skip_errors: bool,
+
+    /// Keep whitespace around header names (trimmed by default)
+    #[arg(long, global = true, default_value = "false")]
+    pub no_trim_headers: bool,
+
+    /// Only read records starting at or after this byte offset
+    #[arg(long, global = true, value_name = "OFFSET")]
+    pub byte_start: Option<u64>,
+
+    /// Only read records starting before this byte offset
+    #[arg(long, global = true, value_name = "OFFSET")]
+    pub byte_end: Option<u64>,
+
     /// CSV has no header row (columns will be named col0, col1, ...)
     #[arg(long, global = true, default_value = "false")]
     pub no_header: bool,
 
+    /// Column names for a file without a header row, e.g. "id,name,age"
+    /// (implies --no-header)
+    #[arg(long, global = true, value_name = "NAMES")]
+    pub header: Option<String>,
+
+    /// Read fixed-width lines, cutting fields at these character ranges, e.g.
+    /// "0-10,10-20,20-" (end exclusive; implies --no-header)
+    #[arg(long, global = true, value_name = "RANGES")]
+    pub fixed_width: Option<String>,
+
     /// Output file path (default: stdout)
     #[arg(long, short = 'o', global = true)]
     pub output: Option<String>,
@@ -65,20 +203,194 @@ pub struct Cli {
     #[arg(long, short = 'q', global = true, default_value = "false")]
     pub quiet: bool,
 
+    /// Decimal places for mean/median/std/sum (default: 2 in tables, 6 in CSV)
+    #[arg(long, global = true, value_name = "N")]
+    pub round: Option<usize>,
+
+    /// Shorten large numbers in tables to 1.23G, 45.6M, 12.3K (CSV/JSON stay exact)
+    #[arg(
+        long,
+        global = true,
+        alias = "abbreviate-numbers",
+        default_value = "false"
+    )]
+    pub abbreviate: bool,
+
+    /// Table width in characters (default: terminal width, or 120 when not
+    /// writing to a terminal)
+    #[arg(long, global = true, value_name = "N")]
+    pub width: Option<u16>,
+
+    /// Cut longer values in tables and top values with an ellipsis (0 = never)
+    #[arg(long, global = true, value_name = "N", default_value_t = DEFAULT_MAX_CELL_WIDTH)]
+    pub max_cell_width: usize,
+
+    /// Locale for numbers in table output (e.g., en-US, de-DE)
+    #[arg(long, global = true)]
+    pub locale: Option<String>,
+
+    /// Quote top values in table output (auto, always, never)
+    #[arg(long, global = true, default_value = "auto")]
+    pub quote_output: String,
+
+    /// Error output format on stderr (text, json)
+    #[arg(long, global = true, value_name = "FMT", default_value = "text")]
+    pub error_format: String,
+
     /// Color output control (auto, always, never)
     #[arg(long, global = true, default_value = "auto")]
     pub color: String,
 
+    /// Type colors in tables: default, colorblind, or mono (bold/underline)
+    #[arg(long, global = true, value_name = "SCHEME", default_value = "default")]
+    pub color_scheme: String,
+
+    /// Skip unique value counting (faster, less memory on wide files)
+    #[arg(long, global = true, default_value = "false")]
+    pub no_unique: bool,
+
+    /// Skip median/p25/p75 (avoids keeping every numeric value in memory)
+    #[arg(long, global = true, default_value = "false")]
+    pub no_percentiles: bool,
+
+    /// Keep every value for exact median/p25/p75 and outliers, instead of estimating past 10,000 values
+    #[arg(long, global = true, default_value = "false")]
+    pub exact_percentiles: bool,
+
+    /// Print one tab-separated line per column: name, type, null_rate, unique (no header)
+    #[arg(long, global = true, default_value = "false")]
+    pub quickstats: bool,
+
+    /// Add a footer with totals across columns (cells, null rate, types) to the summary table
+    #[arg(long, global = true, default_value = "false")]
+    pub totals: bool,
+
+    /// Show each statistic's change from a summary saved earlier with -f json
+    #[arg(long, global = true, value_name = "FILE")]
+    pub compare_to: Option<String>,
+
+    /// Keep reading rows appended to the file and redraw the summary as they arrive
+    #[arg(long, global = true, default_value = "false")]
+    pub follow: bool,
+
+    /// Seconds between checks for appended rows with --follow
+    #[arg(long, global = true, value_name = "SECS", default_value_t = 1.0)]
+    pub follow_interval: f64,
+
+    /// Add an outliers column (values outside 1.5*IQR) to the summary table
+    #[arg(long, global = true, default_value = "false")]
+    pub show_outliers: bool,
+
+    /// Add a decimals column (most fractional digits seen) to the summary table
+    #[arg(long, global = true, default_value = "false")]
+    pub precision_detect: bool,
+
+    /// Flag columns that are entirely null, constant or unique
+    #[arg(long, global = true, default_value = "false")]
+    pub flag_issues: bool,
+
+    /// Warn about integer columns with values past 2^53, where mean, std and percentiles are approximate
+    #[arg(long, global = true, default_value = "false")]
+    pub precision_loss_warning: bool,
+
+    /// Count nulls as one distinct value, with a (null) bucket in top values
+    #[arg(long, global = true, default_value = "false")]
+    pub count_null_as_unique: bool,
+
+    /// Only list top values seen at least N times
+    #[arg(long, global = true, value_name = "N", default_value_t = 1)]
+    pub top_min_count: usize,
+
+    /// Show each top value's share of the column in tables (always in JSON)
+    #[arg(long, global = true, default_value = "false")]
+    pub top_pct: bool,
+
+    /// Min/max of string columns: lexical (byte order) or length (shortest/longest)
+    #[arg(long, global = true, value_name = "MODE", default_value = "lexical")]
+    pub string_extremes: String,
+
+    /// inf/NaN in numeric columns: ignore (leave out of stats), error, or count (as null)
+    #[arg(long, global = true, value_name = "POLICY", default_value = "ignore")]
+    pub nan_policy: String,
+
+    /// Only compute and show these statistics (e.g. "count,null_rate,mean,p95"; pNN is any percentile)
+    #[arg(long, global = true, value_name = "LIST")]
+    pub stats: Option<String>,
+
+    /// Type integer columns with values like 01234 as string
+    #[arg(long, global = true, default_value = "false")]
+    pub preserve_leading_zeros: bool,
+
+    /// Type whole numbers in scientific notation (1e3) as float, not integer
+    #[arg(long, global = true, default_value = "false")]
+    pub sci_as_float: bool,
+
     /// Character encoding (auto-detect if not specified)
     /// Supported: utf-8, shift_jis, euc-jp, gbk, big5, latin1, etc.
     #[arg(long, short = 'e', global = true)]
     pub encoding: Option<String>,
+
+    /// Detect the encoding from the first N bytes (0 for the whole file); a BOM is always checked
+    #[arg(long, global = true, value_name = "N", default_value_t = DEFAULT_DETECT_BYTES)]
+    pub detect_bytes: usize,
+
+    /// Count decoding errors under common encodings, show the detected one, and exit
+    #[arg(long, global = true, default_value = "false")]
+    pub encoding_report: bool,
+
+    /// Print time spent reading, parsing, computing and finalizing to stderr
+    #[arg(long, global = true, default_value = "false")]
+    pub timings: bool,
+
+    /// Print copy-pasteable examples for the subcommand, using FILE's columns when given, and exit
+    #[arg(long, global = true, default_value = "false")]
+    pub examples: bool,
 }
 
 impl Cli {
     pub fn parse_args() -> Self {
-        Self::parse()
+        // `csvp summry data.csv` would otherwise read "summry" as FILE.
+        // Only what clap took as FILE is checked; other words are option values.
+        let cli = Self::try_parse();
+        let file = match cli {
+            Ok(ref cli) if cli.command.is_none() => cli.file.clone(),
+            Ok(_) => None,
+            // An extra positional fails the parse; see what FILE would have been
+            Err(_) => Self::command()
+                .ignore_errors(true)
+                .try_get_matches()
+                .ok()
+                .filter(|m| m.subcommand().is_none())
+                .and_then(|m| m.get_one::<String>("file").cloned()),
+        };
+        let typo = file.and_then(|f| suggest_subcommand(&f).map(|s| (f, s)));
+
+        if let Some((arg, similar)) = typo {
+            Self::command()
+                .error(
+                    ErrorKind::InvalidSubcommand,
+                    format!(
+                        "unrecognized subcommand '{arg}'\n\n  tip: a similar subcommand exists: '{similar}'"
+                    ),
+                )
+                .exit();
+        }
+
+        cli.unwrap_or_else(|e| e.exit())
+    }
+}
+
+/// A near-miss of a subcommand name that isn't an existing file
+fn suggest_subcommand(arg: &str) -> Option<String> {
+    if std::path::Path::new(arg).exists() {
+        return None;
     }
+    let command = Cli::command();
+    let names: Vec<&str> = command.get_subcommands().map(|c| c.get_name()).collect();
+    if names.contains(&arg) {
+        return None;
+    }
+    find_similar_name(arg, &names).map(str::to_string)
 }
 
 #[derive(Subcommand, Debug)]
@@ -89,10 +401,45 @@ pub enum Commands {
     /// Display schema information (column names, types, null rates)
     Schema(SchemaArgs),
 
+    /// Cross-tabulate two columns into a pivot table
+    Pivot(PivotArgs),
+
+    /// Count each value of a column, binning numbers with --bin-width
+    Freq(FreqArgs),
+
+    /// Schema and summary statistics together, from a single pass
+    Profile(ProfileArgs),
+
+    /// Compare the schema and row count of two files
+    Diff(DiffArgs),
+
+    /// Count rows (matching -w if given)
+    Count(CountArgs),
+
+    /// Write the rows in another format (csv, tsv, json, ndjson, arrow)
+    Convert(ConvertArgs),
+
     /// Show detailed help for a specific topic
     Guide(GuideArgs),
 }
 
+impl Commands {
+    /// The subcommand as typed on the command line
+    pub fn name(&self) -> &'static str {
+        match self {
+            Commands::Summary(_) => "summary",
+            Commands::Schema(_) => "schema",
+            Commands::Pivot(_) => "pivot",
+            Commands::Freq(_) => "freq",
+            Commands::Profile(_) => "profile",
+            Commands::Diff(_) => "diff",
+            Commands::Count(_) => "count",
+            Commands::Convert(_) => "convert",
+            Commands::Guide(_) => "guide",
+        }
+    }
+}
+
 #[derive(Parser, Debug, Clone)]
 pub struct GuideArgs {
     /// Topic to display help for (filters, stats, columns, formats, encoding)
@@ -102,22 +449,54 @@ pub struct GuideArgs {
 
 const SUMMARY_HELP: &str = r#"
 STATISTICS COMPUTED:
-    Numeric columns: count, null%, unique, min, max, mean, median, std, p25, p75
-    String columns:  count, null%, unique, min_len, max_len, top values
+    Numeric columns: count, null%, unique, unique%, min, max, mean, median, std,
+                     p25, p75; geo_mean and harmonic_mean in CSV/JSON when
+                     every value is positive
+    String columns:  count, null%, unique, unique%, min, max, min_len, max_len,
+                     top values; min/max are the shortest and longest value
+                     with --string-extremes length
+    Boolean columns: true/false counts and true rate, below the table
+
+PREVIEW (--preview N):
+    Prints the first and last N raw rows (before -w) above the statistics, so
+    you can check you are reading the file you think you are. Table output
+    only; schema accepts it too.
+
+TYPE OVERRIDES (--types):
+    --types "zip=string,flag=boolean"
+                        Skip inference for these columns (names, indices or
+                        ranges); types: integer, float, boolean, string
 
 COLUMN SELECTION (-c):
     -c "name,age"       By column names
     -c "0,1,2"          By index (0-based)
     -c "0..5"           Range (exclusive end)
     -c "0..=5"          Range (inclusive end)
+    --cols-match "^m_"  Add columns whose name matches a regex
+    --preserve-order    Output the selection in file order, not the order
+                        given in -c
 
 FILTER EXPRESSIONS (-w):
     Comparison: age > 30, name == "Alice", price <= 100
     Logic:      age > 20 && age < 30, status == "A" || status == "B"
-    Functions:  contains(name, "test"), is_null(email), matches(id, "^A\\d+")
+    Functions:  contains(name, "test"), is_null(email), is_empty(note),
+                matches(id, "^A\\d+")
+
+GROUPING (-g):
+    -g region           One set of statistics per distinct region value
+                        (the group column itself is skipped unless in -c)
+    --max-groups 50 --group-overflow top
+                        Keep the 50 values with the most rows as groups and
+                        pool the rows of every other value into (other);
+                        the file is read twice, to count rows first
+
+WEIGHTED MEAN (--weight-col):
+    --weight-col qty    Adds w.mean = Σ(value * qty) / Σqty for numeric
+                        columns; rows with a non-numeric qty are skipped
 
 EXAMPLES:
     csvp data.csv -c "0..5" -w "status == \"active\""
+    csvp data.csv -g region -c "sales,units"
     csvp data.csv -w "price > 100 && is_not_null(discount)"
 
 Run 'csvp guide filters' for complete filter syntax reference.
@@ -131,8 +510,9 @@ const SCHEMA_HELP: &str = r#"
 SCHEMA INFORMATION:
     column      Column name from header (or col0, col1... if --no-header)
     type        Inferred type: Integer, Float, Boolean, or String
-    null%       Percentage of null/empty values
-    samples     First few unique non-null values
+    null%       Percentage of null/empty values (JSON/CSV also give
+                empty_count and null_literal_count: blank cells vs null, NA, N/A)
+    samples     First 5 unique non-null values
 
 TYPE INFERENCE:
     Integer     All non-null values are integers
@@ -140,15 +520,250 @@ TYPE INFERENCE:
     Boolean     All values are true/false (case-insensitive)
     String      Any other values
 
+TYPE OVERRIDES:
+    --types "zip=string"  Force a column's type instead of inferring it
+
+FILTERING:
+    -c "id,price"          Only infer these columns (names, indices, ranges)
+    -w "status == active"  Infer types from matching rows only
+
+EXPLAINING TYPES (--explain-types):
+    Adds a counts column with how many values parsed as each type, e.g.
+    integer:900 float:0 boolean:0 string:100 null:5 for a column typed
+    string because of 100 text values. JSON output gets a type_counts object.
+
+SAMPLING:
+    --sample-size N   Stop after N rows. The output notes "sampled: first N
+                      rows"; a value further down the file could still change
+                      a column's type.
+
+JSON SCHEMA (--as json-schema):
+    Writes a Draft 2020-12 JSON Schema for the row objects of
+    'csvp convert --to json' instead of the report: one property per column
+    typed integer, number, boolean or string, with ["type", "null"] for
+    columns that have nulls.
+
 EXAMPLES:
     csvp schema data.csv              Table format
+    csvp schema data.csv --sample-size 1000
+    csvp schema data.csv -w 'status == "active"'
     csvp schema data.csv -f json      JSON format for programmatic use
     csvp schema data.csv -f csv       CSV format for export
+    csvp schema data.csv --as json-schema -o data.schema.json
 "#;
 
 #[derive(Parser, Debug, Default, Clone)]
 #[command(after_long_help = SCHEMA_HELP)]
-pub struct SchemaArgs {}
+pub struct SchemaArgs {
+    /// Infer types from the first N rows only (faster, but later rows may differ)
+    #[arg(long, value_name = "N")]
+    pub sample_size: Option<u64>,
+
+    /// Write another kind of schema document instead (json-schema)
+    #[arg(long = "as", value_name = "KIND")]
+    pub as_kind: Option<String>,
+
+    /// Show how many values parsed as each type, to explain the inferred type
+    #[arg(long, default_value = "false")]
+    pub explain_types: bool,
+}
+
+const PIVOT_HELP: &str = r#"
+PIVOT TABLE:
+    --rows <COL>        Column whose distinct values become table rows
+    -c, --cols <COL>    Column whose distinct values become table columns
+    --value <COL>       Column to aggregate (required for sum and mean)
+    --agg <AGG>         Aggregation: count (default), sum, mean
+
+    Rows and columns are sorted by value. Combinations that never occur show
+    0 for count and - for sum/mean. Non-numeric values are ignored by sum/mean.
+
+EXAMPLES:
+    csvp pivot data.csv --rows region -c status
+    csvp pivot data.csv --rows region -c status --value sales --agg sum
+    csvp pivot data.csv --rows region -c year --value price --agg mean -f csv
+"#;
+
+#[derive(Parser, Debug, Clone)]
+#[command(after_long_help = PIVOT_HELP)]
+pub struct PivotArgs {
+    /// Column whose distinct values become the rows
+    #[arg(
+        long,
+        required_unless_present = "examples",
+        default_value = "",
+        hide_default_value = true
+    )]
+    pub rows: String,
+
+    /// Column whose values are aggregated in each cell
+    #[arg(long)]
+    pub value: Option<String>,
+
+    /// Aggregation function (count, sum, mean)
+    #[arg(long, default_value = "count")]
+    pub agg: String,
+}
+
+const FREQ_HELP: &str = r#"
+FREQUENCY TABLE:
+    -c, --cols <COL>      Column to count (exactly one)
+    --bin-width <WIDTH>   Count numbers per bucket [lo, lo + WIDTH) instead
+                          of per value
+
+    Values are listed most frequent first, ties by value. With --bin-width,
+    a column whose values are all numeric is binned and listed by bucket;
+    empty buckets are left out. Any other column is counted per value. Nulls
+    are counted last: (null) in tables, an empty value in CSV, null in JSON.
+    percent is the share of all rows counted (after -w).
+
+EXAMPLES:
+    csvp freq data.csv -c status
+    csvp freq data.csv -c age --bin-width 10
+    csvp freq data.csv -c age --bin-width 10 -f csv
+"#;
+
+#[derive(Parser, Debug, Clone)]
+#[command(after_long_help = FREQ_HELP)]
+pub struct FreqArgs {
+    /// Bucket width for numeric columns
+    #[arg(long, value_name = "WIDTH")]
+    pub bin_width: Option<f64>,
+}
+
+const DIFF_HELP: &str = r#"
+DIFF REPORT:
+    rows        Row count of each file and the delta
+    added       Columns only in FILE
+    removed     Columns only in BASE
+    renamed     A removed and an added column at the same position with the
+                same inferred type
+    types       Columns whose inferred type changed
+    null%       Null rate drift per column (with --data)
+
+EXIT STATUS:
+    0           No structural differences (row count and null rates may differ)
+    1           Columns were added, removed, renamed, or changed type
+
+EXAMPLES:
+    csvp diff yesterday.csv today.csv
+    csvp diff yesterday.csv today.csv --data
+    csvp diff base.csv new.csv -f json
+"#;
+
+const PROFILE_HELP: &str = r#"
+PROFILE:
+    Reads the file once and reports both the inferred schema (as `schema`
+    would) and the summary statistics (as the default command would). On
+    large files this halves the I/O of running the two separately.
+
+    -c and -w apply to both parts. --group-by is not supported.
+
+OUTPUT:
+    table       Schema table followed by the statistics table
+    json        One object: {"file", "rows", "matched", "schema", "summary"}
+    ndjson      The same object on a single line
+
+EXAMPLES:
+    csvp profile data.csv
+    csvp profile data.csv -c "price,quantity" -f json
+"#;
+
+#[derive(Parser, Debug, Default, Clone)]
+#[command(after_long_help = PROFILE_HELP)]
+pub struct ProfileArgs {}
+
+const COUNT_HELP: &str = r#"
+OUTPUT:
+    table       The row count alone, for scripts: 1000
+    json/csv    File name and row count
+
+FAST COUNT (--fast-count):
+    Counts record terminators in the decoded file instead of parsing CSV,
+    minus the header. This is several times faster but over-counts when
+    quoted fields contain newlines or the file has blank lines. With -w,
+    or with --strict, rows are parsed as usual.
+
+EXAMPLES:
+    csvp count data.csv
+    csvp count data.csv -w "status == \"active\""
+    csvp count huge.csv --fast-count
+"#;
+
+#[derive(Parser, Debug, Default, Clone)]
+#[command(after_long_help = COUNT_HELP)]
+pub struct CountArgs {
+    /// Count terminators instead of parsing (inaccurate with quoted newlines)
+    #[arg(long, default_value = "false")]
+    pub fast_count: bool,
+
+    /// Always parse rows, even with --fast-count
+    #[arg(long, default_value = "false")]
+    pub strict: bool,
+}
+
+const CONVERT_HELP: &str = r#"
+TARGETS (--to):
+    csv, tsv    Delimited text with a header row, quoted where needed
+    json        Array of row objects keyed by header: [{"id":1,...},...]
+    ndjson      One row object per line
+    arrow       Arrow IPC stream (build with --features arrow)
+
+    json, ndjson and arrow are typed: column types come from a full
+    inference pass over the file (see --types), null values (empty, null,
+    NA, N/A) become null, and values that don't fit the type become null.
+
+    Output goes to stdout unless -o is given. -c selects and orders
+    columns and -w filters rows, as for summary.
+
+ENCODING (--to-encoding):
+    Input is decoded as usual (-e, or auto-detected) and the output is
+    re-encoded, e.g. --to-encoding utf-8 to normalize a Shift_JIS file.
+    Characters the target can't represent are written as '?' with a
+    warning. Not available for arrow.
+
+EXAMPLES:
+    csvp convert data.csv --to tsv -o out.tsv
+    csvp convert legacy.csv -e shift_jis --to csv --to-encoding utf-8 -o clean.csv
+    csvp convert data.csv -d ";" --to json -o rows.json
+    csvp convert data.csv --to arrow -o data.arrows
+    csvp convert data.csv --to arrow -c "id,amount" -w "amount > 0" | consumer
+"#;
+
+#[derive(Parser, Debug, Clone)]
+#[command(after_long_help = CONVERT_HELP)]
+pub struct ConvertArgs {
+    /// Output format (csv, tsv, json, ndjson, arrow)
+    #[arg(
+        long,
+        value_name = "FORMAT",
+        required_unless_present = "examples",
+        default_value = "",
+        hide_default_value = true
+    )]
+    pub to: String,
+
+    /// Character encoding of the output (default: utf-8)
+    #[arg(long, value_name = "ENC")]
+    pub to_encoding: Option<String>,
+}
+
+#[derive(Parser, Debug, Clone)]
+#[command(after_long_help = DIFF_HELP)]
+pub struct DiffArgs {
+    /// Baseline CSV file to compare FILE against
+    #[arg(
+        value_name = "BASE",
+        required_unless_present = "examples",
+        default_value = "",
+        hide_default_value = true
+    )]
+    pub base: String,
+
+    /// Also report per-column null rate drift
+    #[arg(long, default_value = "false")]
+    pub data: bool,
+}
 
 pub fn parse_columns(cols_str: &str, headers: &StringRecord) -> Result<Vec<String>> {
     let header_vec: Vec<String> = headers.iter().map(|s| s.to_string()).collect();
@@ -183,6 +798,17 @@ pub fn parse_columns(cols_str: &str, headers: &StringRecord) -> Result<Vec<Strin
         // Check if column name exists
         if header_vec.iter().any(|h| h == col) {
             result.push(col.to_string());
+        } else if col.contains('*') {
+            // Glob such as metric_* or *_count, in file order
+            let matched: Vec<&String> = header_vec.iter().filter(|h| glob_match(col, h)).collect();
+            if matched.is_empty() {
+                return Err(CsvpeekError::NoColumnsMatch {
+                    pattern: col.to_string(),
+                    available: header_vec,
+                }
+                .into());
+            }
+            result.extend(matched.into_iter().cloned());
         } else {
             // Try to find a suggestion
             let suggestion = find_similar_column(col, &header_vec);
@@ -197,6 +823,182 @@ pub fn parse_columns(cols_str: &str, headers: &StringRecord) -> Result<Vec<Strin
     Ok(result)
 }
 
+/// Whether `text` matches `pattern`, where `*` stands for any run of
+/// characters (including none) and everything else is literal
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let (p, t): (Vec<char>, Vec<char>) = (pattern.chars().collect(), text.chars().collect());
+    let (mut pi, mut ti) = (0, 0);
+    let mut backtrack = None; // Last * seen, and where its match ended
+    while ti < t.len() {
+        if pi < p.len() && p[pi] == '*' {
+            backtrack = Some((pi, ti));
+            pi += 1;
+        } else if pi < p.len() && p[pi] == t[ti] {
+            pi += 1;
+            ti += 1;
+        } else if let Some((star, end)) = backtrack {
+            // Let the last * swallow one more character
+            pi = star + 1;
+            ti = end + 1;
+            backtrack = Some((star, end + 1));
+        } else {
+            return false;
+        }
+    }
+    p[pi..].iter().all(|&c| c == '*')
+}
+
+/// Names of the columns matching the --cols-match regex, in file order
+pub fn match_columns(pattern: &str, headers: &StringRecord) -> Result<Vec<String>> {
+    let regex = Regex::new(pattern)
+        .map_err(|e| anyhow::anyhow!("Invalid --cols-match pattern '{pattern}': {e}"))?;
+    let matched: Vec<String> = headers
+        .iter()
+        .filter(|h| regex.is_match(h))
+        .map(str::to_string)
+        .collect();
+    if matched.is_empty() {
+        return Err(anyhow::anyhow!(
+            "--cols-match '{pattern}' matched no columns"
+        ));
+    }
+    Ok(matched)
+}
+
+/// Parse `col=type` pairs from --types into a map keyed by column name
+pub fn parse_type_overrides(
+    types_str: &str,
+    headers: &StringRecord,
+) -> Result<HashMap<String, DataType>> {
+    let mut result = HashMap::new();
+
+    for pair in types_str.split(',') {
+        let pair = pair.trim();
+        if pair.is_empty() {
+            continue;
+        }
+
+        let (col, type_name) = pair
+            .rsplit_once('=')
+            .ok_or_else(|| anyhow::anyhow!("Invalid type override '{pair}': expected col=type"))?;
+        let dtype = DataType::from_str(type_name)?;
+
+        for name in parse_columns(col.trim(), headers)? {
+            result.insert(name, dtype);
+        }
+    }
+
+    Ok(result)
+}
+
+/// Environment variable naming the command run when none is given
+pub const DEFAULT_COMMAND_VAR: &str = "CSVP_DEFAULT_COMMAND";
+
+/// The command to run when none is given on the command line: summary, or
+/// the one named by CSVP_DEFAULT_COMMAND. Only commands that need no
+/// arguments of their own can be the default.
+pub fn default_command() -> Result<Commands> {
+    let name = std::env::var(DEFAULT_COMMAND_VAR).unwrap_or_default();
+    match name.trim().to_lowercase().as_str() {
+        "" | "summary" => Ok(Commands::Summary(SummaryArgs::default())),
+        "schema" => Ok(Commands::Schema(SchemaArgs::default())),
+        "profile" => Ok(Commands::Profile(ProfileArgs::default())),
+        "count" => Ok(Commands::Count(CountArgs::default())),
+        _ => Err(anyhow::anyhow!(
+            "Invalid {DEFAULT_COMMAND_VAR} '{name}'. Supported: summary, schema, profile, count"
+        )),
+    }
+}
+
+/// Parse the comma-separated column names given with --header
+pub fn parse_header_list(names: &str) -> Result<StringRecord> {
+    let header: StringRecord = names.split(',').map(str::trim).collect();
+    if header.iter().any(str::is_empty) {
+        return Err(anyhow::anyhow!(
+            "Invalid --header '{names}': column names must not be empty"
+        ));
+    }
+    Ok(header)
+}
+
+/// Parse --fixed-width ranges like "0-10,10-20,20-": start inclusive, end
+/// exclusive, and an open end running to the end of the line
+pub fn parse_fixed_width(spec: &str) -> Result<Vec<Range<usize>>> {
+    let invalid = || {
+        anyhow::anyhow!(
+            "Invalid --fixed-width '{spec}': expected start-end character ranges like 0-10,10-20,20- (end exclusive)"
+        )
+    };
+    spec.split(',')
+        .map(|range| {
+            let (start, end) = range.trim().split_once('-').ok_or_else(invalid)?;
+            let start: usize = start.trim().parse().map_err(|_| invalid())?;
+            let end = match end.trim() {
+                "" => usize::MAX,
+                end => end.parse().map_err(|_| invalid())?,
+            };
+            if end <= start {
+                return Err(anyhow::anyhow!(
+                    "Invalid --fixed-width range '{}': the end must be after the start",
+                    range.trim()
+                ));
+            }
+            Ok(start..end)
+        })
+        .collect()
+}
+
+/// Parse `old=new` pairs from --rename into a map from the original column
+/// name to its new name. Old names may also be indices.
+pub fn parse_renames(renames_str: &str, headers: &StringRecord) -> Result<HashMap<String, String>> {
+    let mut result = HashMap::new();
+
+    for pair in renames_str.split(',') {
+        let pair = pair.trim();
+        if pair.is_empty() {
+            continue;
+        }
+
+        let (col, new_name) = pair
+            .split_once('=')
+            .ok_or_else(|| anyhow::anyhow!("Invalid rename '{pair}': expected old=new"))?;
+        let new_name = new_name.trim();
+        if new_name.is_empty() {
+            return Err(anyhow::anyhow!(
+                "Invalid rename '{pair}': new name is empty"
+            ));
+        }
+        if result.values().any(|v| v == new_name) {
+            return Err(anyhow::anyhow!("Cannot rename two columns to '{new_name}'"));
+        }
+
+        match parse_columns(col.trim(), headers)?.as_slice() {
+            [name] => {
+                result.insert(name.clone(), new_name.to_string());
+            }
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "Invalid rename '{pair}': expected a single column"
+                ));
+            }
+        }
+    }
+
+    // Renaming onto a name that stays in use would make it ambiguous
+    for new_name in result.values() {
+        if headers
+            .iter()
+            .any(|h| h == new_name && !result.contains_key(h))
+        {
+            return Err(anyhow::anyhow!(
+                "Cannot rename to '{new_name}': a column with that name already exists"
+            ));
+        }
+    }
+
+    Ok(result)
+}
+
 fn parse_range(s: &str, headers: &[String]) -> Result<Option<Vec<String>>> {
     // Check for inclusive range (0..=5)
     if let Some((start_str, end_str)) = s.split_once("..=") {
@@ -0,0 +1,276 @@
+use anyhow::Result;
+use csv::{StringRecord, WriterBuilder};
+use encoding_rs::{EncoderResult, Encoding};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+
+use crate::types::{DataType, Value, is_null, parse_value};
+
+/// Output format of the convert subcommand
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConvertTarget {
+    Csv,
+    Tsv,
+    Json,
+    NdJson,
+    Arrow,
+}
+
+impl ConvertTarget {
+    pub fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "csv" => Ok(ConvertTarget::Csv),
+            "tsv" => Ok(ConvertTarget::Tsv),
+            "json" => Ok(ConvertTarget::Json),
+            "ndjson" | "jsonl" => Ok(ConvertTarget::NdJson),
+            "arrow" | "arrows" | "ipc" => Ok(ConvertTarget::Arrow),
+            _ => Err(anyhow::anyhow!(
+                "Unknown convert target: {s}. Supported: csv, tsv, json, ndjson, arrow"
+            )),
+        }
+    }
+
+    /// Whether the target needs column types, and so an inference pass
+    pub fn is_typed(&self) -> bool {
+        matches!(
+            self,
+            ConvertTarget::Json | ConvertTarget::NdJson | ConvertTarget::Arrow
+        )
+    }
+}
+
+/// A column of the converted output
+#[derive(Debug, Clone)]
+pub struct OutputColumn {
+    pub name: String,
+    pub data_type: DataType,
+}
+
+/// Streams converted rows to the output
+pub trait RowWriter {
+    fn write_record(&mut self, record: &StringRecord) -> Result<()>;
+
+    /// Flush buffered rows and write any trailer
+    fn finish(self: Box<Self>) -> Result<()>;
+}
+
+/// `columns` are the selected columns, in output order
+pub fn row_writer(
+    target: ConvertTarget,
+    output: Box<dyn Write>,
+    columns: &[OutputColumn],
+) -> Result<Box<dyn RowWriter>> {
+    match target {
+        ConvertTarget::Csv => Ok(Box::new(DelimitedWriter::new(output, b',', columns)?)),
+        ConvertTarget::Tsv => Ok(Box::new(DelimitedWriter::new(output, b'\t', columns)?)),
+        ConvertTarget::Json => Ok(Box::new(JsonWriter::new(output, columns, false))),
+        ConvertTarget::NdJson => Ok(Box::new(JsonWriter::new(output, columns, true))),
+        #[cfg(feature = "arrow")]
+        ConvertTarget::Arrow => Ok(Box::new(crate::arrow_output::ArrowRowWriter::new(
+            output, columns,
+        )?)),
+        #[cfg(not(feature = "arrow"))]
+        ConvertTarget::Arrow => Err(anyhow::anyhow!(
+            "Arrow output requires csvpeek built with `--features arrow`"
+        )),
+    }
+}
+
+pub fn open_output(path: Option<&str>) -> Result<Box<dyn Write>> {
+    match path {
+        Some(path) => Ok(Box::new(BufWriter::new(File::create(path)?))),
+        None => Ok(Box::new(BufWriter::new(io::stdout()))),
+    }
+}
+
+/// Transcodes the UTF-8 written to it into `encoding` (--to-encoding).
+/// Characters the encoding can't represent are written as `?`, with one
+/// warning when the writer is dropped.
+pub struct EncodingWriter {
+    inner: Box<dyn Write>,
+    encoding: &'static Encoding,
+    encoder: encoding_rs::Encoder,
+    pending: Vec<u8>, // Trailing bytes of a UTF-8 sequence split across writes
+    unmappable: u64,
+}
+
+impl EncodingWriter {
+    pub fn new(inner: Box<dyn Write>, encoding: &'static Encoding) -> Self {
+        Self {
+            inner,
+            encoding,
+            encoder: encoding.new_encoder(),
+            pending: Vec::new(),
+            unmappable: 0,
+        }
+    }
+
+    fn encode(&mut self, text: &str) -> Vec<u8> {
+        // encoding_rs only decodes UTF-16, so encode it by hand
+        if self.encoding == encoding_rs::UTF_16LE {
+            return text.encode_utf16().flat_map(u16::to_le_bytes).collect();
+        }
+        if self.encoding == encoding_rs::UTF_16BE {
+            return text.encode_utf16().flat_map(u16::to_be_bytes).collect();
+        }
+
+        let mut out = Vec::with_capacity(text.len());
+        let mut rest = text;
+        loop {
+            let needed = self
+                .encoder
+                .max_buffer_length_from_utf8_without_replacement(rest.len())
+                .unwrap_or(rest.len() * 4);
+            out.reserve(needed);
+            let (result, read) = self
+                .encoder
+                .encode_from_utf8_to_vec_without_replacement(rest, &mut out, false);
+            rest = &rest[read..];
+            match result {
+                EncoderResult::InputEmpty => return out,
+                EncoderResult::OutputFull => {}
+                EncoderResult::Unmappable(_) => {
+                    self.unmappable += 1;
+                    out.push(b'?');
+                }
+            }
+        }
+    }
+}
+
+impl Write for EncodingWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.pending.extend_from_slice(buf);
+        let valid = match std::str::from_utf8(&self.pending) {
+            Ok(s) => s.len(),
+            Err(e) => e.valid_up_to(),
+        };
+        let text = String::from_utf8_lossy(&self.pending[..valid]).into_owned();
+        self.pending.drain(..valid);
+
+        let bytes = self.encode(&text);
+        self.inner.write_all(&bytes)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl Drop for EncodingWriter {
+    fn drop(&mut self) {
+        if self.unmappable > 0 {
+            eprintln!(
+                "Warning: {} character(s) could not be encoded as '{}' and were written as '?'",
+                self.unmappable,
+                self.encoding.name()
+            );
+        }
+    }
+}
+
+/// CSV/TSV with a header row, quoting only where needed
+struct DelimitedWriter {
+    writer: csv::Writer<Box<dyn Write>>,
+}
+
+impl DelimitedWriter {
+    fn new(output: Box<dyn Write>, delimiter: u8, columns: &[OutputColumn]) -> Result<Self> {
+        let mut writer = WriterBuilder::new()
+            .delimiter(delimiter)
+            .flexible(true)
+            .from_writer(output);
+        writer.write_record(columns.iter().map(|c| &c.name))?;
+        Ok(Self { writer })
+    }
+}
+
+impl RowWriter for DelimitedWriter {
+    fn write_record(&mut self, record: &StringRecord) -> Result<()> {
+        self.writer.write_record(record)?;
+        Ok(())
+    }
+
+    fn finish(mut self: Box<Self>) -> Result<()> {
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+/// One object per row keyed by header, in column order. Values are typed by
+/// the column's inferred type; nulls and values that don't fit become null.
+struct JsonWriter {
+    output: Box<dyn Write>,
+    keys: Vec<String>, // Pre-serialized JSON keys
+    types: Vec<DataType>,
+    lines: bool, // ndjson instead of an array
+    rows: u64,
+}
+
+impl JsonWriter {
+    fn new(output: Box<dyn Write>, columns: &[OutputColumn], lines: bool) -> Self {
+        Self {
+            output,
+            keys: columns
+                .iter()
+                .map(|c| serde_json::Value::String(c.name.clone()).to_string())
+                .collect(),
+            types: columns.iter().map(|c| c.data_type).collect(),
+            lines,
+            rows: 0,
+        }
+    }
+}
+
+impl RowWriter for JsonWriter {
+    fn write_record(&mut self, record: &StringRecord) -> Result<()> {
+        let fields: Vec<String> = self
+            .keys
+            .iter()
+            .zip(&self.types)
+            .enumerate()
+            .map(|(i, (key, dtype))| {
+                format!("{key}:{}", json_value(record.get(i).unwrap_or(""), *dtype))
+            })
+            .collect();
+        let object = format!("{{{}}}", fields.join(","));
+
+        if self.lines {
+            writeln!(self.output, "{object}")?;
+        } else {
+            let sep = if self.rows == 0 { "[\n" } else { ",\n" };
+            write!(self.output, "{sep}  {object}")?;
+        }
+        self.rows += 1;
+        Ok(())
+    }
+
+    fn finish(mut self: Box<Self>) -> Result<()> {
+        if !self.lines {
+            let close = if self.rows == 0 { "[]\n" } else { "\n]\n" };
+            write!(self.output, "{close}")?;
+        }
+        self.output.flush()?;
+        Ok(())
+    }
+}
+
+fn json_value(cell: &str, dtype: DataType) -> serde_json::Value {
+    if is_null(cell) {
+        return serde_json::Value::Null;
+    }
+    if dtype == DataType::String {
+        return serde_json::Value::String(cell.to_string());
+    }
+
+    match parse_value(cell) {
+        (_, Value::Integer(i)) if dtype != DataType::Boolean => i.into(),
+        (DataType::Float, _) if dtype == DataType::Float => cell
+            .trim()
+            .parse::<f64>()
+            .map_or(serde_json::Value::Null, Into::into),
+        (_, Value::Boolean(b)) if dtype == DataType::Boolean => b.into(),
+        _ => serde_json::Value::Null,
+    }
+}
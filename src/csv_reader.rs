@@ -1,26 +1,70 @@
 use anyhow::Result;
 use chardetng::EncodingDetector;
-use csv::{Reader, ReaderBuilder, StringRecord};
+use csv::{Reader, ReaderBuilder, StringRecord, Terminator};
 use encoding_rs::Encoding;
+use std::cell::{Cell, RefCell};
+use std::collections::{HashSet, VecDeque};
 use std::fs::File;
-use std::io::{BufReader, Read};
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::ops::Range;
 use std::path::Path;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
 
-use crate::error::CsvpeekError;
+use crate::error::{CsvpeekError, Warning};
+use crate::types::{EncodingCandidate, EncodingReport};
+
+/// Default cap on a single field, so an unterminated quote fails fast
+pub const DEFAULT_MAX_FIELD_BYTES: usize = 16 * 1024 * 1024;
+
+/// Default size of the read and parse buffers when streaming
+pub const DEFAULT_INPUT_BUFFER_SIZE: usize = 64 * 1024;
+
+/// Default sample auto-detection looks at; encodings rarely change
+/// mid-file, and chardetng is slow on a whole large file
+pub const DEFAULT_DETECT_BYTES: usize = 64 * 1024;
 
 #[derive(Debug, Clone, Default)]
 pub struct CsvOptions {
     pub delimiter: u8,
+    pub multi_delimiter: Option<String>, // A separator like `::`; quoting is off
+    pub fixed_width: Option<Vec<Range<usize>>>, // Character ranges of each field
     pub no_header: bool,
     pub encoding: Option<String>, // None = auto-detect
+    pub terminator: Option<u8>,   // None = \n or \r\n
+    pub escape: Option<u8>,       // Escapes a quote inside a quoted field
+    pub max_field_bytes: usize,
+    pub skip_errors: bool, // Skip malformed records instead of failing
+    pub byte_start: Option<u64>,
+    pub byte_end: Option<u64>,
+    pub trim_headers: bool,
+    pub header_only: bool, // Read just enough of the file for the first record
+    pub header: Option<StringRecord>, // Given with --header; the file has none
+    pub show_progress: bool, // Download bar for large URL inputs
+    pub buffer_size: usize, // Read and parse buffers when streaming
+    pub detect_bytes: usize, // Sample for encoding detection; 0 for the whole file
 }
 
 impl CsvOptions {
     pub fn new() -> Self {
         Self {
             delimiter: b',',
+            multi_delimiter: None,
+            fixed_width: None,
             no_header: false,
             encoding: None,
+            terminator: None,
+            escape: None,
+            max_field_bytes: DEFAULT_MAX_FIELD_BYTES,
+            skip_errors: false,
+            byte_start: None,
+            byte_end: None,
+            trim_headers: true,
+            header_only: false,
+            header: None,
+            show_progress: false,
+            buffer_size: DEFAULT_INPUT_BUFFER_SIZE,
+            detect_bytes: DEFAULT_DETECT_BYTES,
         }
     }
 
@@ -29,6 +73,26 @@ impl CsvOptions {
         self
     }
 
+    /// Split fields on a multi-character separator. Each occurrence is
+    /// replaced with a control character the file doesn't contain, and quotes
+    /// are read as ordinary characters, so a quoted separator still splits.
+    pub fn with_multi_delimiter(mut self, separator: Option<String>) -> Self {
+        self.multi_delimiter = separator;
+        self
+    }
+
+    /// Cut each line into fields at these character ranges (end exclusive;
+    /// usize::MAX runs to the end of the line) and trim them, instead of
+    /// splitting on a delimiter. Quotes are ordinary characters, and every
+    /// line is data, as with --no-header.
+    pub fn with_fixed_width(mut self, ranges: Option<Vec<Range<usize>>>) -> Self {
+        if ranges.is_some() {
+            self.no_header = true;
+        }
+        self.fixed_width = ranges;
+        self
+    }
+
     pub fn with_no_header(mut self, no_header: bool) -> Self {
         self.no_header = no_header;
         self
@@ -38,55 +102,301 @@ impl CsvOptions {
         self.encoding = encoding;
         self
     }
+
+    pub fn with_terminator(mut self, terminator: Option<u8>) -> Self {
+        self.terminator = terminator;
+        self
+    }
+
+    /// Read `\"` (with `\` as the escape) as a quote inside quoted fields,
+    /// as MySQL's SELECT ... INTO OUTFILE writes them. Doubled quotes still
+    /// work.
+    pub fn with_escape(mut self, escape: Option<u8>) -> Self {
+        self.escape = escape;
+        self
+    }
+
+    pub fn with_max_field_bytes(mut self, max_field_bytes: usize) -> Self {
+        self.max_field_bytes = max_field_bytes;
+        self
+    }
+
+    /// Warn about and skip records that fail to parse, or that have a field
+    /// over max_field_bytes, instead of failing on the first one
+    pub fn with_skip_errors(mut self, skip_errors: bool) -> Self {
+        self.skip_errors = skip_errors;
+        self
+    }
+
+    pub fn with_trim_headers(mut self, trim_headers: bool) -> Self {
+        self.trim_headers = trim_headers;
+        self
+    }
+
+    /// Stop reading after the first record (--check); the reader then only
+    /// yields the header, or the first row with --no-header
+    pub fn with_header_only(mut self, header_only: bool) -> Self {
+        self.header_only = header_only;
+        self
+    }
+
+    /// Use these column names instead of a header row (--header). Every
+    /// line of the file is then data, as with --no-header.
+    pub fn with_header(mut self, header: Option<StringRecord>) -> Self {
+        if header.is_some() {
+            self.no_header = true;
+        }
+        self.header = header;
+        self
+    }
+
+    pub fn with_progress(mut self, show_progress: bool) -> Self {
+        self.show_progress = show_progress;
+        self
+    }
+
+    pub fn with_byte_window(mut self, start: Option<u64>, end: Option<u64>) -> Self {
+        self.byte_start = start;
+        self.byte_end = end;
+        self
+    }
+
+    pub fn with_buffer_size(mut self, buffer_size: usize) -> Self {
+        self.buffer_size = buffer_size;
+        self
+    }
+
+    pub fn with_detect_bytes(mut self, detect_bytes: usize) -> Self {
+        self.detect_bytes = detect_bytes;
+        self
+    }
+
+    /// Whether the file can be decoded and parsed as it is read. A byte
+    /// window, a multi-character delimiter, fixed-width fields and --check
+    /// need the whole content.
+    fn can_stream(&self) -> bool {
+        !self.header_only
+            && self.multi_delimiter.is_none()
+            && self.fixed_width.is_none()
+            && self.byte_start.is_none()
+            && self.byte_end.is_none()
+    }
 }
 
 pub struct CsvReader {
-    reader: Reader<std::io::Cursor<String>>,
+    reader: Reader<FieldGuard>, // UTF-8, decoded as read or all at once
     headers: Option<StringRecord>,
     generated_headers: bool,
+    peeked: Option<Result<StringRecord>>, // First record, read to size generated headers
+    trim_headers: bool,
+    max_field_bytes: usize,
+    terminator: u8,
+    skip_errors: bool,
+    skipped: u64,                  // Malformed records dropped with skip_errors
+    read_time: Rc<Cell<Duration>>, // Shared with the TimedRead under the parser
+    cut_fields: CutFields,         // Shared with the FieldGuard
+    decoding: Decoding,
+}
+
+/// How the input was decoded, and how many characters in it couldn't be
+struct Decoding {
+    description: String,        // e.g. "as UTF-8, detected from ...", for the warning
+    undecodable: Rc<Cell<u64>>, // Replaced with U+FFFD; shared with a DecodingReader
 }
 
 impl CsvReader {
     pub fn from_path_with_options<P: AsRef<Path>>(path: P, options: CsvOptions) -> Result<Self> {
         let path = path.as_ref();
 
-        if !path.exists() {
-            return Err(CsvpeekError::FileNotFound(path.display().to_string()).into());
+        let (mut file, charset) = open_source(path, &options)?;
+        let declared = charset.and_then(|c| lookup_encoding(&c).ok().map(|e| (c, e)));
+        let explicit = match options.encoding {
+            Some(ref name) => Some((name.clone(), lookup_encoding(name)?)),
+            None => None,
+        };
+
+        // Stream UTF-8 and single-byte encodings straight into the parser;
+        // others are decoded from the whole file below
+        let mut head = Vec::new();
+        let mut detected = None;
+        if options.can_stream() {
+            let streamed = match explicit.as_ref().or(declared.as_ref()) {
+                Some((name, encoding)) if streams(encoding) => {
+                    Some((*encoding, format!("with encoding '{name}'")))
+                }
+                Some(_) => None,
+                None => {
+                    let limit = match options.detect_bytes {
+                        0 => u64::MAX,
+                        n => n.max(BOM_BYTES) as u64,
+                    };
+                    (&mut file).take(limit).read_to_end(&mut head)?;
+                    let detection = detect_encoding(&head, (head.len() as u64) < limit);
+                    if streams(detection.encoding) {
+                        Some((detection.encoding, detection.description))
+                    } else {
+                        detected = Some(detection); // Decoded with the rest below
+                        None
+                    }
+                }
+            };
+            if let Some((encoding, description)) = streamed {
+                let head = std::io::Cursor::new(head);
+                let undecodable = Rc::new(Cell::new(0));
+                let input = DecodingReader::new(
+                    Box::new(head.chain(file)),
+                    encoding,
+                    options.buffer_size,
+                    Rc::clone(&undecodable),
+                );
+                let decoding = Decoding {
+                    description,
+                    undecodable,
+                };
+                return Ok(Self::from_input(
+                    Box::new(input),
+                    options.delimiter,
+                    &options,
+                    decoding,
+                ));
+            }
         }
 
         // Read file content
-        let mut file = BufReader::new(File::open(path)?);
-        let mut bytes = Vec::new();
-        file.read_to_end(&mut bytes)?;
+        let mut bytes = head;
+        if options.header_only {
+            read_first_record(
+                &mut file,
+                options.terminator.unwrap_or(b'\n'),
+                options.escape,
+                &mut bytes,
+            )?;
+        } else {
+            file.read_to_end(&mut bytes)?;
+        }
+
+        if !options.header_only && (options.byte_start.is_some() || options.byte_end.is_some()) {
+            bytes = byte_window(&bytes, &options);
+        }
 
         // Determine encoding
-        let content = if let Some(ref enc_name) = options.encoding {
+        let (encoding, description) = if let Some((ref enc_name, encoding)) = explicit {
             // Explicit encoding specified
-            let encoding = lookup_encoding(enc_name)?;
-            let (decoded, _, had_errors) = encoding.decode(&bytes);
-            if had_errors {
-                eprintln!(
-                    "Warning: Some characters could not be decoded with encoding '{enc_name}'"
-                );
-            }
-            decoded.into_owned()
+            (encoding, format!("with encoding '{enc_name}'"))
+        } else if let Some((ref charset, encoding)) = declared {
+            // The charset the server declared; more reliable than detection
+            // for single-byte encodings
+            (encoding, format!("with encoding '{charset}'"))
         } else {
-            // Auto-detect encoding
-            detect_and_decode(&bytes)
+            // Auto-detect encoding from a sample; a BOM is always at the start
+            let detection =
+                detected.unwrap_or_else(|| detect_encoding_in(&bytes, options.detect_bytes));
+            (detection.encoding, detection.description)
+        };
+        let (content, undecodable) = decode_lossy(encoding, &bytes);
+        let decoding = Decoding {
+            description,
+            undecodable: Rc::new(Cell::new(undecodable)),
         };
 
-        let cursor = std::io::Cursor::new(content);
-        let reader = ReaderBuilder::new()
-            .has_headers(!options.no_header)
-            .delimiter(options.delimiter)
-            .flexible(true)
-            .from_reader(cursor);
+        // A BOM that survives decoding would end up in the first header name
+        let content = match content.strip_prefix('\u{FEFF}') {
+            Some(stripped) => stripped.to_string(),
+            None => content,
+        };
 
-        Ok(Self {
+        let (content, delimiter) = match (&options.fixed_width, &options.multi_delimiter) {
+            (Some(ranges), _) => {
+                slice_fixed_width(&content, ranges, options.terminator.unwrap_or(b'\n'))?
+            }
+            (None, Some(separator)) => replace_separator(content, separator)?,
+            (None, None) => (content, options.delimiter),
+        };
+
+        let input = Box::new(std::io::Cursor::new(content.into_bytes()));
+        Ok(Self::from_input(input, delimiter, &options, decoding))
+    }
+
+    /// Parse UTF-8 `input`, splitting fields on `delimiter`
+    fn from_input(
+        input: Box<dyn Read>,
+        delimiter: u8,
+        options: &CsvOptions,
+        decoding: Decoding,
+    ) -> Self {
+        let read_time = Rc::new(Cell::new(Duration::ZERO));
+        let input = TimedRead {
+            inner: input,
+            spent: Rc::clone(&read_time),
+        };
+        let cut_fields = CutFields::default();
+        let input = FieldGuard::new(Box::new(input), delimiter, options, Rc::clone(&cut_fields));
+        let reader = reader_builder(delimiter, options).from_reader(input);
+
+        Self {
             reader,
-            headers: None,
+            headers: options.header.as_ref().map(disambiguate_headers),
             generated_headers: options.no_header,
-        })
+            peeked: None,
+            trim_headers: options.trim_headers,
+            max_field_bytes: options.max_field_bytes,
+            terminator: options.terminator.unwrap_or(b'\n'),
+            skip_errors: options.skip_errors,
+            skipped: 0,
+            read_time,
+            cut_fields,
+            decoding,
+        }
+    }
+
+    /// Time the parser has spent waiting on reading and decoding input.
+    /// Input that isn't streamed was already read and decoded when the
+    /// reader was opened, so that time isn't included.
+    pub fn read_time(&self) -> Duration {
+        self.read_time.get()
+    }
+
+    /// Malformed records records() has dropped with skip_errors
+    pub fn skipped(&self) -> u64 {
+        self.skipped
+    }
+
+    /// Characters that couldn't be decoded and were replaced with U+FFFD,
+    /// so far, and how the input was decoded
+    pub fn undecodable(&self) -> (u64, &str) {
+        (self.decoding.undecodable.get(), &self.decoding.description)
+    }
+
+    /// Count records by counting terminators in the decoded content, without
+    /// parsing. Quoted fields containing newlines and blank lines are counted
+    /// as extra records. Call before reading any records.
+    pub fn fast_count(&mut self) -> Result<u64> {
+        let input = &mut self.reader.get_mut().inner; // Uncut, as nothing is parsed
+        let mut buffer = vec![0u8; DEFAULT_INPUT_BUFFER_SIZE];
+        let mut lines = 0u64;
+        let mut last = None;
+        loop {
+            let read = input.read(&mut buffer)?;
+            if read == 0 {
+                break;
+            }
+            lines += buffer[..read]
+                .iter()
+                .filter(|&&b| b == self.terminator)
+                .count() as u64;
+            last = Some(buffer[read - 1]);
+        }
+
+        match last {
+            None => return Ok(0),
+            Some(b) if b != self.terminator => lines += 1, // No trailing terminator
+            _ => {}
+        }
+        if self.generated_headers {
+            Ok(lines)
+        } else {
+            Ok(lines.saturating_sub(1))
+        }
     }
 
     pub fn headers(&mut self) -> Result<&StringRecord> {
@@ -94,63 +404,839 @@ impl CsvReader {
             if self.generated_headers {
                 // Generate headers like col0, col1, col2...
                 // We need to peek at first record to know column count
-                let first_record = self.reader.records().next();
-                if let Some(Ok(record)) = first_record {
+                let mut record = StringRecord::new();
+                let read = self.reader.read_record(&mut record);
+                if let (Err(_), Some(e)) = (&read, self.cut_field(&record)) {
+                    return Err(e.into());
+                }
+                if read.unwrap_or(false) {
                     let count = record.len();
                     let mut headers = StringRecord::new();
                     for i in 0..count {
                         headers.push_field(&format!("col{i}"));
                     }
                     self.headers = Some(headers);
+                    self.peeked = Some(match self.cut_field(&record) {
+                        Some(e) => Err(e.into()),
+                        None => Ok(record),
+                    });
                 } else {
                     self.headers = Some(StringRecord::new());
                 }
             } else {
-                self.headers = Some(self.reader.headers()?.clone());
+                let headers = match self.reader.headers() {
+                    Ok(headers) => headers.clone(),
+                    Err(e) => {
+                        let cut = self.cut_field(&StringRecord::new());
+                        return Err(cut.map_or_else(|| e.into(), Into::into));
+                    }
+                };
+                if let Some(e) = self.cut_field(&headers) {
+                    return Err(e.into());
+                }
+                let headers = if self.trim_headers {
+                    headers.iter().map(str::trim).collect()
+                } else {
+                    headers.clone()
+                };
+                self.headers = Some(disambiguate_headers(&headers));
             }
         }
         Ok(self.headers.as_ref().unwrap())
     }
 
-    pub fn records(&mut self) -> impl Iterator<Item = Result<StringRecord, csv::Error>> + '_ {
-        self.reader.records()
+    pub fn records(&mut self) -> impl Iterator<Item = Result<StringRecord>> + '_ {
+        let mut peeked = self.peeked.take();
+        std::iter::from_fn(move || {
+            loop {
+                let result = match peeked.take() {
+                    Some(result) => result,
+                    None => self.next_record().transpose()?,
+                };
+                match result {
+                    Err(e) if self.skip_errors => {
+                        Warning::SkippedRow(e.to_string()).report();
+                        self.skipped += 1;
+                    }
+                    result => return Some(result),
+                }
+            }
+        })
+    }
+
+    fn next_record(&mut self) -> Result<Option<StringRecord>> {
+        let mut record = StringRecord::new();
+        match self.reader.read_record(&mut record) {
+            Ok(true) => {}
+            Ok(false) => return Ok(None),
+            Err(e) => return Err(self.cut_field(&record).map_or_else(|| e.into(), Into::into)),
+        }
+        match self.cut_field(&record) {
+            Some(e) => Err(e.into()),
+            None => Ok(Some(record)),
+        }
+    }
+
+    /// The error for a field the FieldGuard cut short in `record`, the one
+    /// just parsed, or the one whose read it stopped
+    fn cut_field(&self, record: &StringRecord) -> Option<CsvpeekError> {
+        let end = match self.reader.is_done() {
+            true => u64::MAX, // Stopped mid-record
+            false => self.reader.position().byte(),
+        };
+        let mut cut_fields = self.cut_fields.borrow_mut();
+        let mut first = None;
+        while let Some(&(at, column)) = cut_fields.front()
+            && at < end
+        {
+            cut_fields.pop_front();
+            first.get_or_insert(column);
+        }
+        first.map(|column| CsvpeekError::FieldTooLarge {
+            line: record.position().map_or(1, |p| p.line()),
+            column,
+            max: self.max_field_bytes,
+        })
+    }
+}
+
+/// Whether `encoding` can be decoded chunk by chunk as the file is read.
+/// Every byte of a single-byte encoding maps on its own, and UTF-8 carries
+/// a character cut at a chunk boundary over to the next chunk; multibyte
+/// legacy encodings keep the whole-file decode.
+fn streams(encoding: &'static Encoding) -> bool {
+    encoding == encoding_rs::UTF_8 || encoding.is_single_byte()
+}
+
+/// The encoding auto-detection picked from the start of a file
+struct Detection {
+    encoding: &'static Encoding,
+    confident: bool,
+    description: String, // How it was picked, for the decode warning
+}
+
+/// The csv parser settings for `options`, splitting fields on `delimiter`
+fn reader_builder(delimiter: u8, options: &CsvOptions) -> ReaderBuilder {
+    let mut builder = ReaderBuilder::new();
+    builder
+        .has_headers(!options.no_header)
+        .delimiter(delimiter)
+        .quoting(options.multi_delimiter.is_none() && options.fixed_width.is_none())
+        .escape(options.escape)
+        .flexible(true)
+        .buffer_capacity(options.buffer_size);
+    if let Some(terminator) = options.terminator {
+        builder.terminator(Terminator::Any(terminator));
+    }
+    builder
+}
+
+/// Reads the records appended to a local file since the last read
+/// (--follow). Only whole records are taken; a partly written last record,
+/// including one inside a quoted field with a newline, waits for the next
+/// read.
+pub struct FileTail {
+    path: std::path::PathBuf,
+    offset: u64,
+    encoding: Option<&'static Encoding>, // Detected on the first read unless given
+    skip_header: bool,                   // The header row hasn't been read yet
+    options: CsvOptions,
+}
+
+impl FileTail {
+    /// Start at the beginning of the file, skipping its header row
+    pub fn new<P: AsRef<Path>>(path: P, options: CsvOptions) -> Result<Self> {
+        let path = path.as_ref();
+        if is_url(&path.to_string_lossy()) {
+            return Err(anyhow::anyhow!("--follow needs a local file, not a URL"));
+        }
+        if !path.exists() {
+            return Err(CsvpeekError::FileNotFound(path.display().to_string()).into());
+        }
+        if options.multi_delimiter.is_some() {
+            return Err(anyhow::anyhow!(
+                "--follow does not support multi-character delimiters"
+            ));
+        }
+        if options.fixed_width.is_some() {
+            return Err(anyhow::anyhow!("--follow does not support --fixed-width"));
+        }
+        let encoding = match options.encoding {
+            Some(ref name) => Some(follow_encoding(lookup_encoding(name)?)?),
+            None => None,
+        };
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            offset: 0,
+            encoding,
+            skip_header: !options.no_header,
+            options,
+        })
+    }
+
+    /// Records that are complete since the last read, oldest first
+    pub fn read_new(&mut self) -> Result<Vec<StringRecord>> {
+        let mut file = File::open(&self.path)?;
+        if file.metadata()?.len() < self.offset {
+            return Err(anyhow::anyhow!(
+                "{} was truncated while following it",
+                self.path.display()
+            ));
+        }
+        file.seek(SeekFrom::Start(self.offset))?;
+        let mut bytes = Vec::new();
+        file.read_to_end(&mut bytes)?;
+
+        let terminator = self.options.terminator.unwrap_or(b'\n');
+        let Some(end) = last_record_end(&bytes, terminator, self.options.escape) else {
+            return Ok(Vec::new());
+        };
+        bytes.truncate(end + 1);
+        self.offset += bytes.len() as u64;
+
+        let encoding = match self.encoding {
+            Some(encoding) => encoding,
+            None => {
+                let detected = detect_encoding_in(&bytes, self.options.detect_bytes).encoding;
+                *self.encoding.insert(follow_encoding(detected)?)
+            }
+        };
+        let (content, undecodable) = decode_lossy(encoding, &bytes);
+        if undecodable > 0 {
+            Warning::Undecodable {
+                count: undecodable,
+                description: format!("as {}", encoding.name()),
+            }
+            .report();
+        }
+
+        let mut builder = reader_builder(self.options.delimiter, &self.options);
+        builder.has_headers(self.skip_header);
+        self.skip_header = false;
+        let mut reader = builder.from_reader(content.as_bytes());
+        let mut records = Vec::new();
+        for result in reader.records() {
+            records.push(result?);
+        }
+        Ok(records)
+    }
+}
+
+/// Index of the last terminator in `bytes` outside quotes, where a record
+/// ends; `bytes` starts at the beginning of a record. A terminator inside
+/// a quoted field still being written is part of that field.
+fn last_record_end(bytes: &[u8], terminator: u8, escape: Option<u8>) -> Option<usize> {
+    let mut in_quotes = false;
+    let mut escaped = false;
+    let mut end = None;
+    for (i, &b) in bytes.iter().enumerate() {
+        match b {
+            _ if escaped => escaped = false,
+            b if in_quotes && Some(b) == escape => escaped = true,
+            b'"' => in_quotes = !in_quotes,
+            b if b == terminator && !in_quotes => end = Some(i),
+            _ => {}
+        }
     }
+    end
 }
 
-/// Detect encoding and decode bytes to UTF-8 string
-fn detect_and_decode(bytes: &[u8]) -> String {
-    // Check for BOM first
-    if bytes.starts_with(&[0xEF, 0xBB, 0xBF]) {
-        // UTF-8 BOM
-        return String::from_utf8_lossy(&bytes[3..]).into_owned();
+/// Appended bytes are split at the terminator before decoding, which cuts
+/// UTF-16 characters in half
+fn follow_encoding(encoding: &'static Encoding) -> Result<&'static Encoding> {
+    if encoding == encoding_rs::UTF_16LE || encoding == encoding_rs::UTF_16BE {
+        return Err(anyhow::anyhow!("--follow does not support UTF-16 input"));
     }
-    if bytes.starts_with(&[0xFF, 0xFE]) {
-        // UTF-16 LE BOM
-        let (decoded, _, _) = encoding_rs::UTF_16LE.decode(&bytes[2..]);
-        return decoded.into_owned();
+    Ok(encoding)
+}
+
+/// Longest BOM (UTF-8), which is looked for however small the sample
+const BOM_BYTES: usize = 3;
+
+/// Pick an encoding for all of `bytes` from its first `detect_bytes`
+/// bytes, or all of it when 0
+fn detect_encoding_in(bytes: &[u8], detect_bytes: usize) -> Detection {
+    match detect_bytes.max(BOM_BYTES) {
+        n if detect_bytes > 0 && n < bytes.len() => detect_encoding(&bytes[..n], false),
+        _ => detect_encoding(bytes, true),
     }
-    if bytes.starts_with(&[0xFE, 0xFF]) {
-        // UTF-16 BE BOM
-        let (decoded, _, _) = encoding_rs::UTF_16BE.decode(&bytes[2..]);
-        return decoded.into_owned();
+}
+
+/// Pick an encoding for a file from `sample`, its first bytes: a BOM, then
+/// valid UTF-8, then chardetng's guess. `complete` says the sample is the
+/// whole file; otherwise a character cut off at its end is allowed.
+fn detect_encoding(sample: &[u8], complete: bool) -> Detection {
+    if let Some((encoding, _)) = Encoding::for_bom(sample) {
+        return Detection {
+            encoding,
+            confident: true,
+            description: format!("as {}, the encoding its BOM declares", encoding.name()),
+        };
     }
 
-    // Try UTF-8 first (most common)
-    if let Ok(s) = std::str::from_utf8(bytes) {
-        return s.to_string();
+    let utf8 = match std::str::from_utf8(sample) {
+        Ok(_) => true,
+        Err(e) => !complete && e.error_len().is_none(),
+    };
+    if utf8 {
+        return Detection {
+            encoding: encoding_rs::UTF_8,
+            confident: true,
+            description: format!(
+                "as UTF-8, detected from the first {} bytes; set the encoding with -e (see --encoding-report)",
+                sample.len()
+            ),
+        };
     }
 
-    // Use chardetng for detection
     let mut detector = EncodingDetector::new();
-    detector.feed(bytes, true);
-    let encoding = detector.guess(None, true);
+    detector.feed(sample, complete);
+    let (encoding, confident) = detector.guess_assess(None, true);
+    Detection {
+        encoding,
+        confident,
+        description: format!(
+            "as detected encoding {}; set one with -e (see --encoding-report)",
+            encoding.name()
+        ),
+    }
+}
+
+/// Decodes another reader into UTF-8 as it is read, replacing invalid
+/// sequences with U+FFFD and counting them in `malformed`. A BOM overrides
+/// the encoding, as with a whole-file decode.
+struct DecodingReader {
+    inner: Box<dyn Read>,
+    decoder: encoding_rs::Decoder,
+    input: Vec<u8>,
+    input_pos: usize,
+    input_end: usize,
+    output: Vec<u8>,
+    output_pos: usize,
+    eof: bool,
+    done: bool,
+    malformed: Rc<Cell<u64>>, // Shared with the CsvReader, which reports it
+}
+
+/// Where the FieldGuard cut fields: the offset in the parser's input of the
+/// byte past max_field_bytes, and the field's column
+type CutFields = Rc<RefCell<VecDeque<(u64, usize)>>>;
+
+/// Cuts each field off after max_field_bytes as the parser reads it, so a
+/// runaway field (usually an unterminated quote) is never buffered whole.
+/// Follows just enough of the CSV syntax to see where fields end, and
+/// notes every cut in `cut_fields` for CsvReader to report. Unless the
+/// record is to be skipped, reading stops with an error at the cut.
+struct FieldGuard {
+    inner: Box<dyn Read>,
+    max: usize,
+    delimiter: u8,
+    terminator: Option<u8>, // None = \n or \r
+    quoting: bool,
+    escape: Option<u8>,
+    raw: Vec<u8>,
+    raw_pos: usize,
+    raw_end: usize,
+    pending: VecDeque<u8>, // Closes a cut quoted field before its delimiter
+    emitted: u64,          // Bytes handed to the parser so far
+    column: usize,
+    field_len: usize,
+    field_start: bool,
+    quoted: bool, // The current field opened with a quote
+    in_quotes: bool,
+    quote_pending: bool, // A quote inside quotes: closing, or the first of ""
+    escaped: bool,
+    cutting: bool,     // Dropping the rest of an oversized field
+    stop_at_cut: bool, // Fail the read instead, without --skip-errors
+    stopped: bool,     // Cut with stop_at_cut; every later read fails
+    cut_fields: CutFields,
+}
+
+/// What a byte is to the CSV syntax FieldGuard follows
+#[derive(PartialEq)]
+enum FieldByte {
+    Content,
+    Syntax, // Quotes and escapes
+    FieldEnd,
+    RecordEnd,
+}
+
+impl FieldGuard {
+    fn new(
+        inner: Box<dyn Read>,
+        delimiter: u8,
+        options: &CsvOptions,
+        cut_fields: CutFields,
+    ) -> Self {
+        Self {
+            inner,
+            max: options.max_field_bytes,
+            delimiter,
+            terminator: options.terminator,
+            quoting: options.multi_delimiter.is_none() && options.fixed_width.is_none(),
+            escape: options.escape,
+            raw: vec![0; options.buffer_size],
+            raw_pos: 0,
+            raw_end: 0,
+            pending: VecDeque::new(),
+            emitted: 0,
+            column: 0,
+            field_len: 0,
+            field_start: true,
+            quoted: false,
+            in_quotes: false,
+            quote_pending: false,
+            escaped: false,
+            cutting: false,
+            stop_at_cut: !options.skip_errors,
+            stopped: false,
+            cut_fields,
+        }
+    }
 
-    let (decoded, _, _) = encoding.decode(bytes);
-    decoded.into_owned()
+    fn classify(&mut self, b: u8) -> FieldByte {
+        if self.escaped {
+            self.escaped = false;
+            return FieldByte::Content;
+        }
+        if self.in_quotes {
+            if !self.quote_pending {
+                if Some(b) == self.escape {
+                    self.escaped = true;
+                    return FieldByte::Syntax;
+                }
+                if b == b'"' {
+                    self.quote_pending = true;
+                    return FieldByte::Syntax;
+                }
+                return FieldByte::Content;
+            }
+            self.quote_pending = false;
+            if b == b'"' {
+                return FieldByte::Content; // A doubled quote
+            }
+            self.in_quotes = false;
+        }
+
+        let field_start = std::mem::replace(&mut self.field_start, false);
+        if b == self.delimiter {
+            FieldByte::FieldEnd
+        } else if self.terminator.map_or(b == b'\n' || b == b'\r', |t| b == t) {
+            FieldByte::RecordEnd
+        } else if b == b'"' && self.quoting && field_start {
+            self.in_quotes = true;
+            self.quoted = true;
+            FieldByte::Syntax
+        } else {
+            FieldByte::Content
+        }
+    }
+}
+
+impl Read for FieldGuard {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let mut written = 0;
+        while written < buf.len() && !self.stopped {
+            if let Some(b) = self.pending.pop_front() {
+                buf[written] = b;
+                written += 1;
+                continue;
+            }
+            if self.raw_pos == self.raw_end {
+                if written > 0 {
+                    break;
+                }
+                self.raw_end = self.inner.read(&mut self.raw)?;
+                self.raw_pos = 0;
+                if self.raw_end == 0 {
+                    break;
+                }
+            }
+            let b = self.raw[self.raw_pos];
+            self.raw_pos += 1;
+
+            match self.classify(b) {
+                kind @ (FieldByte::FieldEnd | FieldByte::RecordEnd) => {
+                    if self.cutting && self.quoted {
+                        // The closing quote was dropped with the rest
+                        self.pending.push_back(b'"');
+                    }
+                    self.pending.push_back(b);
+                    self.cutting = false;
+                    self.field_len = 0;
+                    self.field_start = true;
+                    self.quoted = false;
+                    self.column = match kind {
+                        FieldByte::FieldEnd => self.column + 1,
+                        _ => 0,
+                    };
+                }
+                _ if self.cutting => {}
+                FieldByte::Syntax => {
+                    buf[written] = b;
+                    written += 1;
+                }
+                FieldByte::Content => {
+                    self.field_len += 1;
+                    if self.field_len > self.max {
+                        // Keep this byte, so the parser's field is over the
+                        // limit too, and drop the rest of the field
+                        self.cutting = true;
+                        let at = self.emitted + written as u64;
+                        self.cut_fields.borrow_mut().push_back((at, self.column));
+                        if self.stop_at_cut {
+                            // Records before this one still parse
+                            self.stopped = true;
+                            continue;
+                        }
+                    }
+                    buf[written] = b;
+                    written += 1;
+                }
+            }
+        }
+        self.emitted += written as u64;
+        // Never an empty read, which the parser would take for the end
+        if self.stopped && written == 0 {
+            return Err(std::io::Error::other("field over max_field_bytes"));
+        }
+        Ok(written)
+    }
+}
+
+/// Adds the time spent in each read of `inner` to `spent` (--timings)
+struct TimedRead {
+    inner: Box<dyn Read>,
+    spent: Rc<Cell<Duration>>,
+}
+
+impl Read for TimedRead {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let start = Instant::now();
+        let read = self.inner.read(buf);
+        self.spent.set(self.spent.get() + start.elapsed());
+        read
+    }
+}
+
+impl DecodingReader {
+    fn new(
+        inner: Box<dyn Read>,
+        encoding: &'static Encoding,
+        buffer_size: usize,
+        malformed: Rc<Cell<u64>>,
+    ) -> Self {
+        Self {
+            inner,
+            decoder: encoding.new_decoder(),
+            input: vec![0; buffer_size],
+            input_pos: 0,
+            input_end: 0,
+            output: Vec::with_capacity(buffer_size * 3 + 16), // Room for any expansion
+            output_pos: 0,
+            eof: false,
+            done: false,
+            malformed,
+        }
+    }
+}
+
+impl Read for DecodingReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        loop {
+            if self.output_pos < self.output.len() {
+                let n = buf.len().min(self.output.len() - self.output_pos);
+                buf[..n].copy_from_slice(&self.output[self.output_pos..self.output_pos + n]);
+                self.output_pos += n;
+                return Ok(n);
+            }
+            if self.done {
+                return Ok(0);
+            }
+
+            if self.input_pos == self.input_end && !self.eof {
+                self.input_end = self.inner.read(&mut self.input)?;
+                self.input_pos = 0;
+                self.eof = self.input_end == 0;
+            }
+
+            let capacity = self.output.capacity();
+            self.output.resize(capacity, 0);
+            self.output_pos = 0;
+            let (result, read, written) = self.decoder.decode_to_utf8_without_replacement(
+                &self.input[self.input_pos..self.input_end],
+                &mut self.output,
+                self.eof,
+            );
+            self.input_pos += read;
+            self.output.truncate(written);
+            match result {
+                encoding_rs::DecoderResult::Malformed(_, _) => {
+                    self.malformed.set(self.malformed.get() + 1);
+                    self.output.extend_from_slice("\u{FFFD}".as_bytes());
+                }
+                encoding_rs::DecoderResult::InputEmpty => self.done = self.eof,
+                encoding_rs::DecoderResult::OutputFull => {}
+            }
+        }
+    }
+}
+
+/// Control characters a multi-character delimiter can be swapped for, in
+/// order of preference: the file must not already contain the one used
+const SEPARATOR_STAND_INS: [char; 4] = ['\u{1F}', '\u{1E}', '\u{1D}', '\u{1C}'];
+
+/// Replace every `separator` in `content` with a single-byte stand-in the
+/// csv parser can split on, returning the new content and that byte
+fn replace_separator(content: String, separator: &str) -> Result<(String, u8)> {
+    let stand_in = stand_in_for(&content, &format!("split on '{separator}'"))?;
+    Ok((
+        content.replace(separator, stand_in.encode_utf8(&mut [0; 4])),
+        stand_in as u8,
+    ))
+}
+
+/// The first of SEPARATOR_STAND_INS that `content` doesn't contain
+fn stand_in_for(content: &str, action: &str) -> Result<char> {
+    SEPARATOR_STAND_INS
+        .into_iter()
+        .find(|&c| !content.contains(c))
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "Cannot {action}: the file contains every control character (\\x1c-\\x1f) it could be replaced with"
+            )
+        })
+}
+
+/// Cut each line of `content` into trimmed fields at `ranges`, counted in
+/// characters, and join them with a stand-in byte the csv parser can split
+/// on. A line too short for a range gives an empty field; blank lines are
+/// dropped, as the csv parser would.
+fn slice_fixed_width(
+    content: &str,
+    ranges: &[Range<usize>],
+    terminator: u8,
+) -> Result<(String, u8)> {
+    let stand_in = stand_in_for(content, "split fixed-width fields")?;
+    let mut sliced = String::with_capacity(content.len() + content.len() / 8);
+    let mut offsets = Vec::new();
+
+    for line in content.split(terminator as char) {
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        if line.trim().is_empty() {
+            continue;
+        }
+        // Byte offset of each character, and of the end of the line
+        offsets.clear();
+        offsets.extend(line.char_indices().map(|(i, _)| i));
+        offsets.push(line.len());
+        let at = |position: usize| offsets.get(position).copied().unwrap_or(line.len());
+
+        for (i, range) in ranges.iter().enumerate() {
+            if i > 0 {
+                sliced.push(stand_in);
+            }
+            let start = at(range.start);
+            sliced.push_str(line[start..at(range.end).max(start)].trim());
+        }
+        sliced.push(terminator as char);
+    }
+    Ok((sliced, stand_in as u8))
+}
+
+/// Read chunks until `bytes` holds a terminator outside quotes, i.e. the
+/// whole first record, or the file ends
+fn read_first_record(
+    file: &mut impl Read,
+    terminator: u8,
+    escape: Option<u8>,
+    bytes: &mut Vec<u8>,
+) -> Result<()> {
+    const CHUNK: usize = 64 * 1024;
+    let mut in_quotes = false;
+    let mut escaped = false;
+    let mut scanned = 0;
+
+    loop {
+        let read = file.take(CHUNK as u64).read_to_end(bytes)?;
+        for &b in &bytes[scanned..] {
+            match b {
+                _ if escaped => escaped = false,
+                b if in_quotes && Some(b) == escape => escaped = true,
+                b'"' => in_quotes = !in_quotes,
+                b if b == terminator && !in_quotes => return Ok(()),
+                _ => {}
+            }
+        }
+        scanned = bytes.len();
+        if read == 0 {
+            return Ok(());
+        }
+    }
+}
+
+/// Whether FILE is an http(s) URL rather than a path
+pub fn is_url(path: &str) -> bool {
+    let lower = path.to_ascii_lowercase();
+    lower.starts_with("http://") || lower.starts_with("https://")
+}
+
+/// Open a local file, or fetch a URL with the `http` feature. Also returns
+/// the charset a server declared in its Content-Type.
+#[cfg_attr(not(feature = "http"), allow(unused_variables))]
+fn open_source(path: &Path, options: &CsvOptions) -> Result<(Box<dyn Read>, Option<String>)> {
+    let location = path.to_string_lossy();
+    if is_url(&location) {
+        #[cfg(feature = "http")]
+        return crate::http_input::open(&location, options.show_progress);
+        #[cfg(not(feature = "http"))]
+        return Err(anyhow::anyhow!(
+            "Reading from a URL requires csvpeek built with `--features http`"
+        ));
+    }
+
+    if !path.exists() {
+        return Err(CsvpeekError::FileNotFound(path.display().to_string()).into());
+    }
+    Ok((Box::new(BufReader::new(File::open(path)?)), None))
+}
+
+/// Decode, returning the number of U+FFFD replacements made for invalid
+/// bytes. A BOM overrides `encoding`, as with any decode.
+fn decode_lossy(encoding: &'static Encoding, bytes: &[u8]) -> (String, u64) {
+    let (decoded, used, had_errors) = encoding.decode(bytes);
+    let mut count = 0;
+    if had_errors {
+        // Counted on the input, so U+FFFD already in the file isn't included
+        let bom_len = Encoding::for_bom(bytes).map_or(0, |(_, len)| len);
+        count = malformed_sequences(used, &bytes[bom_len..]);
+    }
+    (decoded.into_owned(), count)
+}
+
+/// Rename repeated header names to `name_2`, `name_3`, ... so every column
+/// can be selected and none is shadowed by an earlier one
+fn disambiguate_headers(headers: &StringRecord) -> StringRecord {
+    let mut seen: HashSet<String> = HashSet::new();
+    let mut result = StringRecord::new();
+
+    for name in headers {
+        let mut unique = name.to_string();
+        let mut n = 2;
+        while seen.contains(&unique) || (unique != name && headers.iter().any(|h| h == unique)) {
+            unique = format!("{name}_{n}");
+            n += 1;
+        }
+        seen.insert(unique.clone());
+        result.push_field(&unique);
+    }
+
+    result
+}
+
+/// Cut `bytes` down to the records that start inside
+/// `[byte_start, byte_end)`. Both ends snap forward to the next record
+/// boundary, so adjacent windows cover every record exactly once. Boundaries
+/// are found by scanning for the terminator byte, which means a quoted
+/// newline or a UTF-16 file can split a record. Unless `no_header` is set
+/// the header line from offset 0 is kept in front of the window.
+fn byte_window(bytes: &[u8], options: &CsvOptions) -> Vec<u8> {
+    let terminator = options.terminator.unwrap_or(b'\n');
+    let snap = |offset: u64| -> usize {
+        let offset = usize::try_from(offset).unwrap_or(usize::MAX);
+        if offset == 0 || offset >= bytes.len() || bytes[offset - 1] == terminator {
+            return offset.min(bytes.len());
+        }
+        bytes[offset..]
+            .iter()
+            .position(|&b| b == terminator)
+            .map_or(bytes.len(), |i| offset + i + 1)
+    };
+
+    let header_end = if options.no_header { 0 } else { snap(1) };
+    let start = snap(options.byte_start.unwrap_or(0)).max(header_end);
+    let end = snap(options.byte_end.unwrap_or(u64::MAX)).max(start);
+
+    let mut window = bytes[..header_end].to_vec();
+    window.extend_from_slice(&bytes[start..end]);
+    window
+}
+
+/// Encodings tried by --encoding-report, besides the detected one. UTF-16
+/// is left out: without a BOM, ASCII decodes as UTF-16 without errors.
+const REPORT_ENCODINGS: [&Encoding; 12] = [
+    encoding_rs::UTF_8,
+    encoding_rs::SHIFT_JIS,
+    encoding_rs::EUC_JP,
+    encoding_rs::ISO_2022_JP,
+    encoding_rs::GBK,
+    encoding_rs::BIG5,
+    encoding_rs::EUC_KR,
+    encoding_rs::WINDOWS_1252,
+    encoding_rs::ISO_8859_2,
+    encoding_rs::WINDOWS_1251,
+    encoding_rs::KOI8_R,
+    encoding_rs::ISO_8859_15,
+];
+
+/// Decode the whole file as each common encoding and count the errors,
+/// alongside what auto-detection would pick
+pub fn encoding_report<P: AsRef<Path>>(path: P, options: &CsvOptions) -> Result<EncodingReport> {
+    let path = path.as_ref();
+    let (mut file, _) = open_source(path, options)?;
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes)?;
+
+    // Picked the way reading the file does, from the same sample
+    let bom = Encoding::for_bom(&bytes).map(|(e, _)| e);
+    let Detection {
+        encoding: detected,
+        confident,
+        ..
+    } = detect_encoding_in(&bytes, options.detect_bytes);
+
+    let mut encodings = REPORT_ENCODINGS.to_vec();
+    if !encodings.contains(&detected) {
+        encodings.push(detected);
+    }
+    let mut candidates: Vec<EncodingCandidate> = encodings
+        .into_iter()
+        .map(|e| EncodingCandidate {
+            encoding: e.name().to_string(),
+            errors: malformed_sequences(e, &bytes),
+        })
+        .collect();
+    // The detected encoding leads its tie; the rest keep the list order
+    candidates.sort_by_key(|c| (c.errors, c.encoding != detected.name()));
+
+    Ok(EncodingReport {
+        file: path.display().to_string(),
+        bom: bom.map(|e| e.name().to_string()),
+        detected: detected.name().to_string(),
+        confident,
+        candidates,
+    })
+}
+
+fn malformed_sequences(encoding: &'static Encoding, bytes: &[u8]) -> u64 {
+    let mut decoder = encoding.new_decoder_without_bom_handling();
+    let mut buffer = [0u8; 8192];
+    let mut rest = bytes;
+    let mut errors = 0;
+    loop {
+        let (result, read, _) = decoder.decode_to_utf8_without_replacement(rest, &mut buffer, true);
+        rest = &rest[read..];
+        match result {
+            encoding_rs::DecoderResult::InputEmpty => return errors,
+            encoding_rs::DecoderResult::OutputFull => {}
+            encoding_rs::DecoderResult::Malformed(_, _) => errors += 1,
+        }
+    }
 }
 
 /// Look up encoding by name
-fn lookup_encoding(name: &str) -> Result<&'static Encoding> {
+pub fn lookup_encoding(name: &str) -> Result<&'static Encoding> {
     let normalized = name.to_lowercase().replace(['-', '_'], "");
 
     let encoding = match normalized.as_str() {
@@ -0,0 +1,83 @@
+use crate::types::{ColumnSchema, NullRateChange, SchemaDiff, TypeChange};
+
+/// Null rate changes smaller than this (in percentage points) are not reported
+const NULL_RATE_TOLERANCE: f64 = 0.1;
+
+pub struct SchemaSnapshot<'a> {
+    pub file: &'a str,
+    pub rows: u64,
+    pub schema: &'a [ColumnSchema],
+}
+
+/// Compare two inferred schemas.
+///
+/// A column removed from `base` and a column added to `current` at the same
+/// position with the same type are reported as a rename rather than as an
+/// add/remove pair.
+pub fn diff_schemas(base: &SchemaSnapshot, current: &SchemaSnapshot, data: bool) -> SchemaDiff {
+    let find = |schema: &[ColumnSchema], name: &str| schema.iter().position(|c| c.name == name);
+
+    let mut removed: Vec<usize> = (0..base.schema.len())
+        .filter(|&i| find(current.schema, &base.schema[i].name).is_none())
+        .collect();
+    let mut added: Vec<usize> = (0..current.schema.len())
+        .filter(|&i| find(base.schema, &current.schema[i].name).is_none())
+        .collect();
+
+    let mut renamed = Vec::new();
+    removed.retain(|&i| {
+        let pos = added.iter().position(|&j| {
+            j == i && current.schema[j].inferred_type == base.schema[i].inferred_type
+        });
+        match pos {
+            Some(pos) => {
+                let j = added.remove(pos);
+                renamed.push((base.schema[i].name.clone(), current.schema[j].name.clone()));
+                false
+            }
+            None => true,
+        }
+    });
+
+    let mut type_changes = Vec::new();
+    let mut null_rate_changes = Vec::new();
+    for old in base.schema {
+        let Some(j) = find(current.schema, &old.name) else {
+            continue;
+        };
+        let new = &current.schema[j];
+        if old.inferred_type != new.inferred_type {
+            type_changes.push(TypeChange {
+                column: old.name.clone(),
+                from: old.inferred_type,
+                to: new.inferred_type,
+            });
+        }
+        if (new.null_rate - old.null_rate).abs() >= NULL_RATE_TOLERANCE {
+            null_rate_changes.push(NullRateChange {
+                column: old.name.clone(),
+                from: old.null_rate,
+                to: new.null_rate,
+            });
+        }
+    }
+
+    SchemaDiff {
+        base_file: base.file.to_string(),
+        file: current.file.to_string(),
+        base_rows: base.rows,
+        rows: current.rows,
+        row_delta: current.rows as i64 - base.rows as i64,
+        added: added
+            .into_iter()
+            .map(|j| current.schema[j].name.clone())
+            .collect(),
+        removed: removed
+            .into_iter()
+            .map(|i| base.schema[i].name.clone())
+            .collect(),
+        renamed,
+        type_changes,
+        null_rate_changes: data.then_some(null_rate_changes),
+    }
+}
@@ -1,5 +1,27 @@
+use anyhow::Result;
+use serde_json::{Value, json};
+use std::sync::OnceLock;
 use thiserror::Error;
 
+/// How errors are written to stderr (--error-format)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ErrorFormat {
+    Text,
+    Json,
+}
+
+impl ErrorFormat {
+    pub fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "text" => Ok(ErrorFormat::Text),
+            "json" => Ok(ErrorFormat::Json),
+            _ => Err(anyhow::anyhow!(
+                "Unknown error format: {s}. Supported: text, json"
+            )),
+        }
+    }
+}
+
 #[derive(Error, Debug)]
 pub enum CsvpeekError {
     #[error("File not found: {0}")]
@@ -15,9 +37,43 @@ pub enum CsvpeekError {
     #[error("Column index out of range: {index} (max: {max})")]
     ColumnIndexOutOfRange { index: usize, max: usize },
 
+    #[error(
+        "Too many groups in column '{column}' (max: {max}); group by a lower-cardinality column, raise --max-groups, or keep the largest with --group-overflow top"
+    )]
+    TooManyGroups { column: String, max: usize },
+
     #[error("Invalid filter expression: {0}")]
     InvalidFilter(String),
 
+    #[error(
+        "Field too large at line {line}, column {column}: over {max} bytes; possibly an unterminated quote, raise with --max-field-bytes"
+    )]
+    FieldTooLarge {
+        line: u64,
+        column: usize,
+        max: usize,
+    },
+
+    #[error(
+        "Row at line {line} has {fields} fields but the filter reads column '{column}' (field {needed}); the header has {expected}"
+    )]
+    ShortRow {
+        line: u64,
+        fields: usize,
+        expected: usize,
+        column: String,
+        needed: usize,
+    },
+
+    #[error("No columns match '{pattern}' (available: {})", .available.join(", "))]
+    NoColumnsMatch {
+        pattern: String,
+        available: Vec<String>,
+    },
+
+    #[error("Missing required columns: {}", describe_missing(.0))]
+    MissingColumns(Vec<MissingColumn>),
+
     #[error("CSV error: {0}")]
     CsvError(#[from] csv::Error),
 
@@ -25,6 +81,163 @@ pub enum CsvpeekError {
     IoError(#[from] std::io::Error),
 }
 
+impl CsvpeekError {
+    /// Structured form for `--error-format json`
+    pub fn to_json(&self) -> Value {
+        let message = self.to_string();
+        match self {
+            CsvpeekError::FileNotFound(path) => {
+                json!({"error": "FileNotFound", "path": path, "message": message})
+            }
+            CsvpeekError::ColumnNotFound { name, suggestion } => json!({
+                "error": "ColumnNotFound",
+                "column": name,
+                "suggestion": suggestion.as_ref().map(|s| &s.suggested),
+                "message": message,
+            }),
+            CsvpeekError::ColumnIndexOutOfRange { index, max } => json!({
+                "error": "ColumnIndexOutOfRange",
+                "index": index,
+                "max": max,
+                "message": message,
+            }),
+            CsvpeekError::TooManyGroups { column, max } => json!({
+                "error": "TooManyGroups",
+                "column": column,
+                "max": max,
+                "message": message,
+            }),
+            CsvpeekError::InvalidFilter(detail) => {
+                json!({"error": "InvalidFilter", "detail": detail, "message": message})
+            }
+            CsvpeekError::FieldTooLarge { line, column, max } => json!({
+                "error": "FieldTooLarge",
+                "line": line,
+                "column": column,
+                "max": max,
+                "message": message,
+            }),
+            CsvpeekError::ShortRow {
+                line,
+                fields,
+                expected,
+                column,
+                needed,
+            } => json!({
+                "error": "ShortRow",
+                "line": line,
+                "fields": fields,
+                "expected": expected,
+                "column": column,
+                "needed": needed,
+                "message": message,
+            }),
+            CsvpeekError::NoColumnsMatch { pattern, available } => json!({
+                "error": "NoColumnsMatch",
+                "pattern": pattern,
+                "available": available,
+                "message": message,
+            }),
+            CsvpeekError::MissingColumns(missing) => json!({
+                "error": "MissingColumns",
+                "columns": missing.iter().map(|m| &m.name).collect::<Vec<_>>(),
+                "suggestions": missing.iter().map(|m| &m.suggestion).collect::<Vec<_>>(),
+                "message": message,
+            }),
+            CsvpeekError::CsvError(_) => json!({"error": "CsvError", "message": message}),
+            CsvpeekError::IoError(_) => json!({"error": "IoError", "message": message}),
+        }
+    }
+}
+
+/// The --error-format for the whole run, which warnings follow too
+static ERROR_FORMAT: OnceLock<ErrorFormat> = OnceLock::new();
+
+pub fn set_error_format(format: ErrorFormat) {
+    ERROR_FORMAT.get_or_init(|| format);
+}
+
+/// Something wrong with the input that doesn't stop the run
+#[derive(Error, Debug)]
+pub enum Warning {
+    #[error("skipping malformed row: {0}")]
+    SkippedRow(String),
+
+    #[error("skipped {} malformed {}", .0, plural(*.0, "row", "rows"))]
+    SkippedRows(u64),
+
+    #[error("{count} character(s) could not be decoded {description}")]
+    Undecodable { count: u64, description: String },
+
+    #[error("{count} row(s) excluded because '{column}' isn't a date (YYYY-MM-DD)")]
+    NotADate { count: u64, column: String },
+}
+
+impl Warning {
+    /// Structured form for `--error-format json`
+    pub fn to_json(&self) -> Value {
+        let message = self.to_string();
+        match self {
+            Warning::SkippedRow(error) => {
+                json!({"warning": "SkippedRow", "error": error, "message": message})
+            }
+            Warning::SkippedRows(count) => {
+                json!({"warning": "SkippedRows", "count": count, "message": message})
+            }
+            Warning::Undecodable { count, .. } => {
+                json!({"warning": "Undecodable", "count": count, "message": message})
+            }
+            Warning::NotADate { count, column } => json!({
+                "warning": "NotADate",
+                "count": count,
+                "column": column,
+                "message": message,
+            }),
+        }
+    }
+
+    /// Print on stderr in the --error-format
+    pub fn report(&self) {
+        match ERROR_FORMAT.get() {
+            Some(ErrorFormat::Json) => eprintln!("{}", self.to_json()),
+            _ => eprintln!("Warning: {self}"),
+        }
+    }
+}
+
+fn plural<'a>(count: u64, one: &'a str, many: &'a str) -> &'a str {
+    if count == 1 { one } else { many }
+}
+
+/// Render any error as JSON, keeping structured fields for `CsvpeekError`
+pub fn error_to_json(err: &anyhow::Error) -> Value {
+    if let Some(e) = err.downcast_ref::<CsvpeekError>() {
+        return e.to_json();
+    }
+    if let Some(e) = err.downcast_ref::<csv::Error>() {
+        return json!({"error": "CsvError", "message": format!("CSV error: {e}")});
+    }
+    json!({"error": "Error", "message": err.to_string()})
+}
+
+/// A --require-columns name the header doesn't have
+#[derive(Debug)]
+pub struct MissingColumn {
+    pub name: String,
+    pub suggestion: Option<String>,
+}
+
+fn describe_missing(missing: &[MissingColumn]) -> String {
+    missing
+        .iter()
+        .map(|m| match m.suggestion {
+            Some(ref s) => format!("{} (did you mean \"{s}\"?)", m.name),
+            None => m.name.clone(),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 #[derive(Debug)]
 pub struct ColumnSuggestion {
     pub suggested: String,
@@ -59,6 +272,18 @@ pub fn find_similar_column(name: &str, headers: &[String]) -> Option<String> {
         .cloned()
 }
 
+/// Closest known name within an edit distance of 2, for typo hints on
+/// subcommands and option values
+pub fn find_similar_name<'a>(name: &str, candidates: &[&'a str]) -> Option<&'a str> {
+    let name_lower = name.to_lowercase();
+    candidates
+        .iter()
+        .map(|c| (*c, levenshtein_distance(&name_lower, c)))
+        .filter(|(_, d)| *d <= 2)
+        .min_by_key(|(_, d)| *d)
+        .map(|(c, _)| c)
+}
+
 fn levenshtein_distance(a: &str, b: &str) -> usize {
     let a_chars: Vec<char> = a.chars().collect();
     let b_chars: Vec<char> = b.chars().collect();
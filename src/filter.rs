@@ -1,14 +1,95 @@
+use std::cell::Cell;
+
 use anyhow::Result;
 use csv::StringRecord;
 use regex::Regex;
 
-use crate::error::CsvpeekError;
-use crate::types::is_null;
+use crate::error::{ColumnSuggestion, CsvpeekError, Warning, find_similar_column};
+use crate::types::{Date, is_null, parse_date};
 
 #[derive(Debug, Clone)]
 pub struct Filter {
-    expr: Expr,
+    expr: Option<Expr>, // None when only --since/--until filter
     column_indices: std::collections::HashMap<String, usize>,
+    null_matches: NullMatches,
+    strict_rows: Option<usize>, // Header width, when short rows are errors
+    date_range: Option<DateRange>,
+}
+
+/// --since/--until on --date-col: both bounds are inclusive. Null cells
+/// are left out quietly; cells that aren't dates are left out and counted.
+#[derive(Debug, Clone)]
+pub struct DateRange {
+    column: String,
+    index: usize,
+    since: Option<Date>,
+    until: Option<Date>,
+    unparseable: Cell<u64>,
+}
+
+impl DateRange {
+    pub fn new(
+        column: &str,
+        index: usize,
+        since: Option<&str>,
+        until: Option<&str>,
+    ) -> Result<Self> {
+        let bound = |flag: &str, value: Option<&str>| -> Result<Option<Date>> {
+            value
+                .map(|v| {
+                    parse_date(v).ok_or_else(|| {
+                        CsvpeekError::InvalidFilter(format!(
+                            "{flag} {v} is not a date (expected YYYY-MM-DD)"
+                        ))
+                        .into()
+                    })
+                })
+                .transpose()
+        };
+        Ok(Self {
+            column: column.to_string(),
+            index,
+            since: bound("--since", since)?,
+            until: bound("--until", until)?,
+            unparseable: Cell::new(0),
+        })
+    }
+
+    fn contains(&self, record: &StringRecord) -> bool {
+        let cell = record.get(self.index).unwrap_or("");
+        if is_null(cell) {
+            return false;
+        }
+        match parse_date(cell) {
+            Some(date) => {
+                self.since.is_none_or(|since| date >= since)
+                    && self.until.is_none_or(|until| date <= until)
+            }
+            None => {
+                self.unparseable.set(self.unparseable.get() + 1);
+                false
+            }
+        }
+    }
+}
+
+/// What comparisons and value functions give on a null cell (--null-matches)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NullMatches {
+    None, // Unknown, as in SQL: neither the test nor its negation matches
+    Ne,   // Null differs from every value: != matches, everything else doesn't
+}
+
+impl NullMatches {
+    pub fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "none" => Ok(NullMatches::None),
+            "ne" => Ok(NullMatches::Ne),
+            _ => Err(anyhow::anyhow!(
+                "Unknown null matching policy: {s}. Supported: none, ne"
+            )),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -18,10 +99,12 @@ enum Expr {
     Not(Box<Expr>),
     Compare(String, CompareOp, Value),
     Contains(String, String),
+    IContains(String, String),
     Matches(String, Regex),
     In(String, Vec<String>),
     IsNull(String),
     IsNotNull(String),
+    IsEmpty(String),
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -41,61 +124,137 @@ enum Value {
 }
 
 impl Filter {
+    #[allow(dead_code)] // The commands parse with --rename aliases
     pub fn parse(expr_str: &str, headers: &StringRecord) -> Result<Self> {
-        let column_indices: std::collections::HashMap<String, usize> = headers
+        Self::parse_with_aliases(expr_str, headers, &std::collections::HashMap::new())
+    }
+
+    /// `aliases` maps extra names to header names (the original names of
+    /// columns renamed by --rename), so the expression may use either
+    pub fn parse_with_aliases(
+        expr_str: &str,
+        headers: &StringRecord,
+        aliases: &std::collections::HashMap<String, String>,
+    ) -> Result<Self> {
+        let mut column_indices: std::collections::HashMap<String, usize> = headers
             .iter()
             .enumerate()
             .map(|(i, h)| (h.to_string(), i))
             .collect();
 
-        let expr = parse_expr(expr_str, &column_indices)?;
+        let mut names: Vec<String> = headers.iter().map(|h| h.to_string()).collect();
+        for (alias, name) in aliases {
+            if !column_indices.contains_key(alias)
+                && let Some(&idx) = column_indices.get(name)
+            {
+                column_indices.insert(alias.clone(), idx);
+                names.push(alias.clone());
+            }
+        }
+        let expr = parse_expr(expr_str, &names)?;
 
         Ok(Self {
-            expr,
+            expr: Some(expr),
             column_indices,
+            null_matches: NullMatches::None,
+            strict_rows: None,
+            date_range: None,
+        })
+    }
+
+    /// Only --since/--until, without a --where expression
+    pub fn from_date_range(range: DateRange) -> Self {
+        Self {
+            expr: None,
+            column_indices: std::collections::HashMap::new(),
+            null_matches: NullMatches::None,
+            strict_rows: None,
+            date_range: Some(range),
+        }
+    }
+
+    pub fn with_date_range(mut self, range: Option<DateRange>) -> Self {
+        self.date_range = range;
+        self
+    }
+
+    /// The rows left out so far because their --date-col isn't a date
+    pub fn date_warning(&self) -> Option<Warning> {
+        let range = self.date_range.as_ref()?;
+        let count = range.unparseable.get();
+        (count > 0).then(|| Warning::NotADate {
+            count,
+            column: range.column.clone(),
         })
     }
 
+    pub fn with_null_matches(mut self, null_matches: NullMatches) -> Self {
+        self.null_matches = null_matches;
+        self
+    }
+
+    /// Error on rows too short to have a column the filter reads, instead
+    /// of reading the missing field as null (--strict-rows)
+    pub fn with_strict_rows(mut self, strict: bool, headers: &StringRecord) -> Self {
+        self.strict_rows = strict.then_some(headers.len());
+        self
+    }
+
+    /// Rows match only when the expression is true, not when it is unknown
     pub fn matches(&self, record: &StringRecord, _headers: &StringRecord) -> Result<bool> {
-        eval_expr(&self.expr, record, &self.column_indices)
+        if let Some(ref range) = self.date_range
+            && !range.contains(record)
+        {
+            return Ok(false);
+        }
+        let Some(ref expr) = self.expr else {
+            return Ok(true);
+        };
+        let eval = Eval {
+            record,
+            columns: &self.column_indices,
+            null_matches: self.null_matches,
+            strict_rows: self.strict_rows,
+        };
+        Ok(eval.expr(expr)? == Some(true))
     }
 }
 
-fn parse_expr(s: &str, columns: &std::collections::HashMap<String, usize>) -> Result<Expr> {
+fn parse_expr(s: &str, headers: &[String]) -> Result<Expr> {
     let s = s.trim();
 
     // Handle OR (lowest precedence)
     if let Some(pos) = find_operator(s, "||") {
-        let left = parse_expr(&s[..pos], columns)?;
-        let right = parse_expr(&s[pos + 2..], columns)?;
+        let left = parse_expr(&s[..pos], headers)?;
+        let right = parse_expr(&s[pos + 2..], headers)?;
         return Ok(Expr::Or(Box::new(left), Box::new(right)));
     }
 
     // Handle AND
     if let Some(pos) = find_operator(s, "&&") {
-        let left = parse_expr(&s[..pos], columns)?;
-        let right = parse_expr(&s[pos + 2..], columns)?;
+        let left = parse_expr(&s[..pos], headers)?;
+        let right = parse_expr(&s[pos + 2..], headers)?;
         return Ok(Expr::And(Box::new(left), Box::new(right)));
     }
 
     // Handle NOT
     if s.starts_with('!') && !s.starts_with("!=") {
-        let inner = parse_expr(&s[1..], columns)?;
+        let inner = parse_expr(&s[1..], headers)?;
         return Ok(Expr::Not(Box::new(inner)));
     }
 
     // Handle parentheses
     if s.starts_with('(') && s.ends_with(')') {
-        return parse_expr(&s[1..s.len() - 1], columns);
+        return parse_expr(&s[1..s.len() - 1], headers);
     }
 
     // Handle function calls
-    if let Some(func_expr) = parse_function(s, columns)? {
+    if let Some(func_expr) = parse_function(s, headers)? {
         return Ok(func_expr);
     }
 
     // Handle comparisons
-    parse_comparison(s, columns)
+    parse_comparison(s, headers)
 }
 
 fn find_operator(s: &str, op: &str) -> Option<usize> {
@@ -110,14 +269,12 @@ fn find_operator(s: &str, op: &str) -> Option<usize> {
         }
 
         match c {
-            '\\' => escape = true,
+            '\\' if in_string => escape = true,
             '"' => in_string = !in_string,
             '(' if !in_string => depth += 1,
             ')' if !in_string => depth -= 1,
-            _ if !in_string && depth == 0 => {
-                if s[i..].starts_with(op) {
-                    return Some(i);
-                }
+            _ if !in_string && depth == 0 && s[i..].starts_with(op) => {
+                return Some(i);
             }
             _ => {}
         }
@@ -126,48 +283,70 @@ fn find_operator(s: &str, op: &str) -> Option<usize> {
     None
 }
 
-fn parse_function(
-    s: &str,
-    columns: &std::collections::HashMap<String, usize>,
-) -> Result<Option<Expr>> {
+fn parse_function(s: &str, headers: &[String]) -> Result<Option<Expr>> {
     let s = s.trim();
 
     // contains(col, "value")
     if s.starts_with("contains(") && s.ends_with(')') {
         let inner = &s[9..s.len() - 1];
         let (col, val) = parse_func_args(inner)?;
-        validate_column(&col, columns)?;
+        validate_column(&col, headers)?;
         return Ok(Some(Expr::Contains(col, val)));
     }
 
-    // in(col, ["a", "b", "c"])
+    // icontains(col, "value")
+    if s.starts_with("icontains(") && s.ends_with(')') {
+        let inner = &s[10..s.len() - 1];
+        let (col, val) = parse_func_args(inner)?;
+        validate_column(&col, headers)?;
+        return Ok(Some(Expr::IContains(col, val.to_lowercase())));
+    }
+
+    // in(col, ["a", "b", "c"]) or in(col, a|b|c)
     if s.starts_with("in(") && s.ends_with(')') {
         let inner = &s[3..s.len() - 1];
-        let (col, vals_str) = parse_func_args(inner)?;
-        validate_column(&col, columns)?;
-        let vals = parse_array(&vals_str)?;
+        let (col, vals_str) = inner
+            .split_once(',')
+            .ok_or_else(|| CsvpeekError::InvalidFilter("Invalid function arguments".to_string()))?;
+        let col = col.trim().to_string();
+        validate_column(&col, headers)?;
+        let vals_str = vals_str.trim();
+        let vals = if vals_str.starts_with('[') {
+            parse_array(vals_str)?
+        } else if vals_str.starts_with('"') {
+            vec![unquote(vals_str)?]
+        } else {
+            parse_pipe_list(vals_str)
+        };
         return Ok(Some(Expr::In(col, vals)));
     }
 
     // is_null(col)
     if s.starts_with("is_null(") && s.ends_with(')') {
         let col = s[8..s.len() - 1].trim().to_string();
-        validate_column(&col, columns)?;
+        validate_column(&col, headers)?;
         return Ok(Some(Expr::IsNull(col)));
     }
 
     // is_not_null(col)
     if s.starts_with("is_not_null(") && s.ends_with(')') {
         let col = s[12..s.len() - 1].trim().to_string();
-        validate_column(&col, columns)?;
+        validate_column(&col, headers)?;
         return Ok(Some(Expr::IsNotNull(col)));
     }
 
+    // is_empty(col)
+    if s.starts_with("is_empty(") && s.ends_with(')') {
+        let col = s[9..s.len() - 1].trim().to_string();
+        validate_column(&col, headers)?;
+        return Ok(Some(Expr::IsEmpty(col)));
+    }
+
     // matches(col, "regex_pattern")
     if s.starts_with("matches(") && s.ends_with(')') {
         let inner = &s[8..s.len() - 1];
         let (col, pattern) = parse_func_args(inner)?;
-        validate_column(&col, columns)?;
+        validate_column(&col, headers)?;
         let regex = Regex::new(&pattern).map_err(|e| {
             CsvpeekError::InvalidFilter(format!("Invalid regex pattern '{pattern}': {e}"))
         })?;
@@ -195,12 +374,7 @@ fn parse_func_args(s: &str) -> Result<(String, String)> {
             ']' | ')' if !in_string => depth -= 1,
             ',' if !in_string && depth == 0 => {
                 let col = s[..i].trim().to_string();
-                let val = s[i + 1..].trim();
-                let val = if val.starts_with('"') && val.ends_with('"') {
-                    val[1..val.len() - 1].to_string()
-                } else {
-                    val.to_string()
-                };
+                let val = unquote(&s[i + 1..])?;
                 return Ok((col, val));
             }
             _ => {}
@@ -210,6 +384,49 @@ fn parse_func_args(s: &str) -> Result<(String, String)> {
     Err(CsvpeekError::InvalidFilter("Invalid function arguments".to_string()).into())
 }
 
+/// Strip the surrounding quotes from a string literal argument.
+///
+/// The literal is scanned character by character so that commas, brackets and
+/// escaped quotes inside it are kept intact. `\"` becomes `"`; every other
+/// backslash sequence is preserved as written so regex escapes survive.
+fn unquote(s: &str) -> Result<String> {
+    let s = s.trim();
+    let Some(body) = s.strip_prefix('"') else {
+        return Ok(s.to_string());
+    };
+
+    let mut out = String::new();
+    let mut escape = false;
+
+    for (i, c) in body.char_indices() {
+        if escape {
+            if c != '"' {
+                out.push('\\');
+            }
+            out.push(c);
+            escape = false;
+            continue;
+        }
+
+        match c {
+            '\\' => escape = true,
+            '"' => {
+                let rest = body[i + 1..].trim();
+                if !rest.is_empty() {
+                    return Err(CsvpeekError::InvalidFilter(format!(
+                        "Unexpected text after string literal: {rest}"
+                    ))
+                    .into());
+                }
+                return Ok(out);
+            }
+            _ => out.push(c),
+        }
+    }
+
+    Err(CsvpeekError::InvalidFilter(format!("Unterminated string literal: {s}")).into())
+}
+
 fn parse_array(s: &str) -> Result<Vec<String>> {
     let s = s.trim();
     if !s.starts_with('[') || !s.ends_with(']') {
@@ -234,7 +451,17 @@ fn parse_array(s: &str) -> Result<Vec<String>> {
     Ok(result)
 }
 
-fn parse_comparison(s: &str, columns: &std::collections::HashMap<String, usize>) -> Result<Expr> {
+/// Unquoted `a|b|c`, the shell-friendly form of an in() list. Each value
+/// is trimmed; empty ones are skipped.
+fn parse_pipe_list(s: &str) -> Vec<String> {
+    s.split('|')
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn parse_comparison(s: &str, headers: &[String]) -> Result<Expr> {
     let ops = [
         ("==", CompareOp::Eq),
         ("!=", CompareOp::Ne),
@@ -245,14 +472,14 @@ fn parse_comparison(s: &str, columns: &std::collections::HashMap<String, usize>)
     ];
 
     for (op_str, op) in ops.iter() {
-        if let Some(pos) = s.find(op_str) {
+        if let Some(pos) = find_operator(s, op_str) {
             let col = s[..pos].trim().to_string();
             let val_str = s[pos + op_str.len()..].trim();
 
-            validate_column(&col, columns)?;
+            validate_column(&col, headers)?;
 
-            let value = if val_str.starts_with('"') && val_str.ends_with('"') {
-                Value::String(val_str[1..val_str.len() - 1].to_string())
+            let value = if val_str.starts_with('"') {
+                Value::String(unquote(val_str)?)
             } else if let Ok(n) = val_str.parse::<f64>() {
                 Value::Number(n)
             } else {
@@ -266,89 +493,130 @@ fn parse_comparison(s: &str, columns: &std::collections::HashMap<String, usize>)
     Err(CsvpeekError::InvalidFilter(format!("Cannot parse expression: {s}")).into())
 }
 
-fn validate_column(col: &str, columns: &std::collections::HashMap<String, usize>) -> Result<()> {
-    if !columns.contains_key(col) {
+fn validate_column(col: &str, headers: &[String]) -> Result<()> {
+    if !headers.iter().any(|h| h == col) {
         return Err(CsvpeekError::ColumnNotFound {
             name: col.to_string(),
-            suggestion: None,
+            suggestion: find_similar_column(col, headers)
+                .map(|suggested| ColumnSuggestion { suggested }),
         }
         .into());
     }
     Ok(())
 }
 
-fn eval_expr(
-    expr: &Expr,
-    record: &StringRecord,
-    columns: &std::collections::HashMap<String, usize>,
-) -> Result<bool> {
-    match expr {
-        Expr::And(left, right) => {
-            Ok(eval_expr(left, record, columns)? && eval_expr(right, record, columns)?)
-        }
-        Expr::Or(left, right) => {
-            Ok(eval_expr(left, record, columns)? || eval_expr(right, record, columns)?)
-        }
-        Expr::Not(inner) => Ok(!eval_expr(inner, record, columns)?),
-        Expr::Compare(col, op, val) => {
-            let idx = columns.get(col).copied().unwrap_or(0);
-            let cell = record.get(idx).unwrap_or("");
-            eval_compare(cell, op, val)
-        }
-        Expr::Contains(col, substr) => {
-            let idx = columns.get(col).copied().unwrap_or(0);
-            let cell = record.get(idx).unwrap_or("");
-            Ok(cell.contains(substr.as_str()))
-        }
-        Expr::Matches(col, regex) => {
-            let idx = columns.get(col).copied().unwrap_or(0);
-            let cell = record.get(idx).unwrap_or("");
-            Ok(regex.is_match(cell))
-        }
-        Expr::In(col, vals) => {
-            let idx = columns.get(col).copied().unwrap_or(0);
-            let cell = record.get(idx).unwrap_or("");
-            Ok(vals.iter().any(|v| v == cell))
-        }
-        Expr::IsNull(col) => {
-            let idx = columns.get(col).copied().unwrap_or(0);
-            let cell = record.get(idx).unwrap_or("");
-            Ok(is_null(cell))
+/// Evaluates an expression against one record in three-valued logic: None
+/// is unknown, from a comparison or value function on a null cell.
+struct Eval<'a> {
+    record: &'a StringRecord,
+    columns: &'a std::collections::HashMap<String, usize>,
+    null_matches: NullMatches,
+    strict_rows: Option<usize>,
+}
+
+impl Eval<'_> {
+    /// A field missing from a short row reads as null, or is an error
+    /// with --strict-rows
+    fn cell(&self, col: &str) -> Result<&str> {
+        let idx = self.columns.get(col).copied().unwrap_or(0);
+        match (self.record.get(idx), self.strict_rows) {
+            (Some(cell), _) => Ok(cell),
+            (None, None) => Ok(""),
+            (None, Some(expected)) => Err(CsvpeekError::ShortRow {
+                line: self.record.position().map_or(0, |p| p.line()),
+                fields: self.record.len(),
+                expected,
+                column: col.to_string(),
+                needed: idx + 1,
+            }
+            .into()),
         }
-        Expr::IsNotNull(col) => {
-            let idx = columns.get(col).copied().unwrap_or(0);
-            let cell = record.get(idx).unwrap_or("");
-            Ok(!is_null(cell))
+    }
+
+    /// Run `test` on the cell, or apply the null policy when it is null;
+    /// `ne` is whether the test is a != comparison
+    fn value_test(
+        &self,
+        col: &str,
+        ne: bool,
+        test: impl FnOnce(&str) -> Option<bool>,
+    ) -> Result<Option<bool>> {
+        let cell = self.cell(col)?;
+        if !is_null(cell) {
+            return Ok(test(cell));
         }
+        Ok(match self.null_matches {
+            NullMatches::None => None,
+            NullMatches::Ne => Some(ne),
+        })
+    }
+
+    fn expr(&self, expr: &Expr) -> Result<Option<bool>> {
+        Ok(match expr {
+            // false && unknown is false, true || unknown is true
+            Expr::And(left, right) => match self.expr(left)? {
+                Some(false) => Some(false),
+                l => match (l, self.expr(right)?) {
+                    (_, Some(false)) => Some(false),
+                    (Some(true), Some(true)) => Some(true),
+                    _ => None,
+                },
+            },
+            Expr::Or(left, right) => match self.expr(left)? {
+                Some(true) => Some(true),
+                l => match (l, self.expr(right)?) {
+                    (_, Some(true)) => Some(true),
+                    (Some(false), Some(false)) => Some(false),
+                    _ => None,
+                },
+            },
+            Expr::Not(inner) => self.expr(inner)?.map(|v| !v),
+            Expr::Compare(col, op, val) => {
+                let ne = matches!(op, CompareOp::Ne);
+                self.value_test(col, ne, |cell| eval_compare(cell, op, val))?
+            }
+            Expr::Contains(col, substr) => {
+                self.value_test(col, false, |cell| Some(cell.contains(substr.as_str())))?
+            }
+            Expr::IContains(col, substr) => self.value_test(col, false, |cell| {
+                Some(cell.to_lowercase().contains(substr.as_str()))
+            })?,
+            Expr::Matches(col, regex) => {
+                self.value_test(col, false, |cell| Some(regex.is_match(cell)))?
+            }
+            Expr::In(col, vals) => {
+                self.value_test(col, false, |cell| Some(vals.iter().any(|v| v == cell)))?
+            }
+            Expr::IsNull(col) => Some(is_null(self.cell(col)?)),
+            Expr::IsNotNull(col) => Some(!is_null(self.cell(col)?)),
+            Expr::IsEmpty(col) => Some(self.cell(col)?.trim().is_empty()),
+        })
     }
 }
 
-fn eval_compare(cell: &str, op: &CompareOp, val: &Value) -> Result<bool> {
-    match val {
+/// None (unknown) when a number is compared with a cell that isn't one
+fn eval_compare(cell: &str, op: &CompareOp, val: &Value) -> Option<bool> {
+    Some(match val {
         Value::Number(n) => {
-            if let Ok(cell_num) = cell.trim().parse::<f64>() {
-                Ok(match op {
-                    CompareOp::Eq => (cell_num - n).abs() < f64::EPSILON,
-                    CompareOp::Ne => (cell_num - n).abs() >= f64::EPSILON,
-                    CompareOp::Lt => cell_num < *n,
-                    CompareOp::Le => cell_num <= *n,
-                    CompareOp::Gt => cell_num > *n,
-                    CompareOp::Ge => cell_num >= *n,
-                })
-            } else {
-                // Type mismatch - row is filtered out
-                Ok(false)
+            let cell_num = cell.trim().parse::<f64>().ok()?;
+            match op {
+                CompareOp::Eq => (cell_num - n).abs() < f64::EPSILON,
+                CompareOp::Ne => (cell_num - n).abs() >= f64::EPSILON,
+                CompareOp::Lt => cell_num < *n,
+                CompareOp::Le => cell_num <= *n,
+                CompareOp::Gt => cell_num > *n,
+                CompareOp::Ge => cell_num >= *n,
             }
         }
-        Value::String(s) => Ok(match op {
+        Value::String(s) => match op {
             CompareOp::Eq => cell == s,
             CompareOp::Ne => cell != s,
             CompareOp::Lt => cell < s.as_str(),
             CompareOp::Le => cell <= s.as_str(),
             CompareOp::Gt => cell > s.as_str(),
             CompareOp::Ge => cell >= s.as_str(),
-        }),
-    }
+        },
+    })
 }
 
 #[cfg(test)]
@@ -371,6 +639,38 @@ mod tests {
         record
     }
 
+    #[test]
+    fn test_filter_uses_full_header_indices() {
+        let headers = make_headers(&["id", "name", "age"]);
+        let filter = Filter::parse("name == \"Bob\" && age > 20", &headers).unwrap();
+
+        assert!(
+            filter
+                .matches(&make_record(&["1", "Bob", "30"]), &headers)
+                .unwrap()
+        );
+        assert!(
+            !filter
+                .matches(&make_record(&["2", "Bob", "10"]), &headers)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_filter_aliases() {
+        let headers = make_headers(&["id", "amount"]);
+        let aliases = std::collections::HashMap::from([("col1".to_string(), "amount".to_string())]);
+        let filter =
+            Filter::parse_with_aliases("col1 > 5 && amount < 10", &headers, &aliases).unwrap();
+
+        assert!(filter.matches(&make_record(&["1", "7"]), &headers).unwrap());
+        assert!(
+            !filter
+                .matches(&make_record(&["1", "12"]), &headers)
+                .unwrap()
+        );
+    }
+
     #[test]
     fn test_filter_equals_string() {
         let headers = make_headers(&["name", "age"]);
@@ -479,6 +779,20 @@ mod tests {
         assert!(!filter.matches(&record2, &headers).unwrap());
     }
 
+    #[test]
+    fn test_filter_icontains() {
+        let headers = make_headers(&["name", "company"]);
+        let filter = Filter::parse("icontains(company, \"Corp\")", &headers).unwrap();
+
+        let record1 = make_record(&["Alice", "ACME CORP"]);
+        let record2 = make_record(&["Bob", "megacorp ltd"]);
+        let record3 = make_record(&["Charlie", "Initech"]);
+
+        assert!(filter.matches(&record1, &headers).unwrap());
+        assert!(filter.matches(&record2, &headers).unwrap());
+        assert!(!filter.matches(&record3, &headers).unwrap());
+    }
+
     #[test]
     fn test_filter_in() {
         let headers = make_headers(&["name", "age"]);
@@ -491,6 +805,50 @@ mod tests {
         assert!(!filter.matches(&record2, &headers).unwrap());
     }
 
+    #[test]
+    fn test_filter_in_pipe_list() {
+        let headers = make_headers(&["name", "age"]);
+        let filter = Filter::parse("in(name, Alice | Bob|Dave)", &headers).unwrap();
+
+        assert!(
+            filter
+                .matches(&make_record(&["Alice", "25"]), &headers)
+                .unwrap()
+        );
+        assert!(
+            filter
+                .matches(&make_record(&["Bob", "30"]), &headers)
+                .unwrap()
+        );
+        assert!(
+            !filter
+                .matches(&make_record(&["Charlie", "30"]), &headers)
+                .unwrap()
+        );
+
+        // A quoted value is one literal, pipes and all
+        let filter = Filter::parse("in(name, \"A|B\")", &headers).unwrap();
+        assert!(
+            filter
+                .matches(&make_record(&["A|B", "1"]), &headers)
+                .unwrap()
+        );
+        assert!(!filter.matches(&make_record(&["A", "1"]), &headers).unwrap());
+
+        // Combines with the logical operators like any other test
+        let filter = Filter::parse("in(age, 25|30) && !in(name, Bob)", &headers).unwrap();
+        assert!(
+            filter
+                .matches(&make_record(&["Alice", "25"]), &headers)
+                .unwrap()
+        );
+        assert!(
+            !filter
+                .matches(&make_record(&["Bob", "30"]), &headers)
+                .unwrap()
+        );
+    }
+
     #[test]
     fn test_filter_is_null() {
         let headers = make_headers(&["name", "age"]);
@@ -517,6 +875,101 @@ mod tests {
         assert!(filter.matches(&record2, &headers).unwrap());
     }
 
+    #[test]
+    fn test_filter_null_comparisons_are_unknown() {
+        let headers = make_headers(&["name", "age"]);
+        let null_row = make_record(&["Alice", ""]);
+        let na_row = make_record(&["Bob", "NA"]);
+        let matches = |expr: &str, record: &StringRecord| {
+            Filter::parse(expr, &headers)
+                .unwrap()
+                .matches(record, &headers)
+                .unwrap()
+        };
+
+        for expr in [
+            "age == 30",
+            "age != 30",
+            "age > 30",
+            "!(age > 30)",
+            "!(age == 30)",
+            "age != \"x\"",
+            "!contains(age, \"3\")",
+            "!in(age, [\"30\"])",
+        ] {
+            assert!(!matches(expr, &null_row), "{expr}");
+            assert!(!matches(expr, &na_row), "{expr}");
+        }
+        // Unknown only matters when it decides the result
+        assert!(matches("age > 30 || name == \"Alice\"", &null_row));
+        assert!(matches("!(age > 30 && name == \"Bob\")", &null_row));
+        assert!(!matches("!(age > 30 && name == \"Bob\")", &na_row));
+        assert!(matches("age != 30 || is_null(age)", &null_row));
+        assert!(matches("!is_not_null(age)", &null_row));
+    }
+
+    #[test]
+    fn test_filter_non_numeric_comparisons_are_unknown() {
+        let headers = make_headers(&["name", "age"]);
+        let text_row = make_record(&["Alice", "thirty"]);
+        let matches = |expr: &str| {
+            Filter::parse(expr, &headers)
+                .unwrap()
+                .with_null_matches(NullMatches::Ne)
+                .matches(&text_row, &headers)
+                .unwrap()
+        };
+
+        for expr in [
+            "age == 30",
+            "age != 30",
+            "age > 30",
+            "!(age > 30)",
+            "!(age == 30)",
+        ] {
+            assert!(!matches(expr), "{expr}");
+        }
+        assert!(matches("age != 30 || name == \"Alice\""));
+        assert!(matches("age != \"30\""));
+    }
+
+    #[test]
+    fn test_filter_null_matches_ne() {
+        let headers = make_headers(&["name", "age"]);
+        let null_row = make_record(&["Alice", ""]);
+        let matches = |expr: &str| {
+            Filter::parse(expr, &headers)
+                .unwrap()
+                .with_null_matches(NullMatches::Ne)
+                .matches(&null_row, &headers)
+                .unwrap()
+        };
+
+        assert!(matches("age != 30"));
+        assert!(matches("!(age == 30)"));
+        assert!(matches("!(age > 30)"));
+        assert!(!matches("age == 30"));
+        assert!(!matches("age < 30"));
+        assert!(!matches("contains(age, \"\")"));
+        assert!(NullMatches::from_str("sometimes").is_err());
+    }
+
+    #[test]
+    fn test_filter_is_empty() {
+        let headers = make_headers(&["name", "note"]);
+        let filter = Filter::parse("is_empty(note)", &headers).unwrap();
+
+        let record1 = make_record(&["Alice", ""]);
+        let record2 = make_record(&["Bob", "  "]);
+        let record3 = make_record(&["Charlie", "null"]);
+        let record4 = make_record(&["Diana", "NA"]);
+
+        assert!(filter.matches(&record1, &headers).unwrap());
+        assert!(filter.matches(&record2, &headers).unwrap());
+        assert!(!filter.matches(&record3, &headers).unwrap());
+        assert!(!filter.matches(&record4, &headers).unwrap());
+    }
+
     #[test]
     fn test_filter_matches_regex() {
         let headers = make_headers(&["name", "age"]);
@@ -529,6 +982,78 @@ mod tests {
         assert!(!filter.matches(&record2, &headers).unwrap());
     }
 
+    #[test]
+    fn test_filter_matches_regex_with_comma() {
+        let headers = make_headers(&["code", "n"]);
+        let filter = Filter::parse("matches(code, \"^[A-Z]{2,4}$\")", &headers).unwrap();
+
+        assert!(
+            filter
+                .matches(&make_record(&["AB", "1"]), &headers)
+                .unwrap()
+        );
+        assert!(
+            filter
+                .matches(&make_record(&["ABCD", "2"]), &headers)
+                .unwrap()
+        );
+        assert!(
+            !filter
+                .matches(&make_record(&["ABCDE", "3"]), &headers)
+                .unwrap()
+        );
+        assert!(
+            !filter
+                .matches(&make_record(&["ab", "4"]), &headers)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_filter_matches_inline_flags() {
+        let headers = make_headers(&["code", "n"]);
+        let filter = Filter::parse("matches(code, \"(?i)^[A-Z]{2,4}$\")", &headers).unwrap();
+
+        assert!(
+            filter
+                .matches(&make_record(&["ab", "1"]), &headers)
+                .unwrap()
+        );
+        assert!(
+            filter
+                .matches(&make_record(&["AbC", "2"]), &headers)
+                .unwrap()
+        );
+        assert!(
+            !filter
+                .matches(&make_record(&["abcde", "3"]), &headers)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_filter_escaped_quote_in_argument() {
+        let headers = make_headers(&["msg"]);
+        let filter = Filter::parse(r#"contains(msg, "say \"hi\", ok")"#, &headers).unwrap();
+
+        assert!(
+            filter
+                .matches(&make_record(&["they say \"hi\", ok"]), &headers)
+                .unwrap()
+        );
+        assert!(
+            !filter
+                .matches(&make_record(&["say hi, ok"]), &headers)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_filter_unterminated_string_argument() {
+        let headers = make_headers(&["code"]);
+        assert!(Filter::parse("matches(code, \"^A{2,4})", &headers).is_err());
+    }
+
     #[test]
     fn test_filter_parentheses() {
         let headers = make_headers(&["name", "age", "active"]);
@@ -543,6 +1068,39 @@ mod tests {
         assert!(filter.matches(&record3, &headers).unwrap());
     }
 
+    #[test]
+    fn test_filter_operator_inside_quoted_argument() {
+        let headers = make_headers(&["x", "note", "age"]);
+        let record1 = make_record(&["a||b", "x && y", "2"]);
+        let record2 = make_record(&["c", "z", "0"]);
+
+        let filter = Filter::parse("matches(x, \"a||b\") && age > 1", &headers).unwrap();
+        assert!(filter.matches(&record1, &headers).unwrap());
+        assert!(!filter.matches(&record2, &headers).unwrap());
+
+        let filter = Filter::parse("contains(note, \"x && y\")", &headers).unwrap();
+        assert!(filter.matches(&record1, &headers).unwrap());
+        assert!(!filter.matches(&record2, &headers).unwrap());
+
+        let filter = Filter::parse("contains(note, \"x && y\") || age == 0", &headers).unwrap();
+        assert!(filter.matches(&record1, &headers).unwrap());
+        assert!(filter.matches(&record2, &headers).unwrap());
+    }
+
+    #[test]
+    fn test_filter_comparison_operator_inside_string_value() {
+        let headers = make_headers(&["note"]);
+        let record1 = make_record(&["a == b"]);
+        let record2 = make_record(&["a"]);
+
+        let filter = Filter::parse("note != \"a == b\"", &headers).unwrap();
+        assert!(!filter.matches(&record1, &headers).unwrap());
+        assert!(filter.matches(&record2, &headers).unwrap());
+
+        let filter = Filter::parse("note == \"a == b\"", &headers).unwrap();
+        assert!(filter.matches(&record1, &headers).unwrap());
+    }
+
     #[test]
     fn test_filter_invalid_column() {
         let headers = make_headers(&["name", "age"]);
@@ -550,6 +1108,21 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_filter_invalid_column_suggestion() {
+        let headers = make_headers(&["name", "age"]);
+        for expr in ["nmae == \"Alice\"", "contains(nmae, \"A\")"] {
+            let err = Filter::parse(expr, &headers).unwrap_err();
+            match err.downcast_ref::<CsvpeekError>() {
+                Some(CsvpeekError::ColumnNotFound {
+                    suggestion: Some(s),
+                    ..
+                }) => assert_eq!(s.suggested, "name"),
+                other => panic!("expected a suggestion, got {other:?}"),
+            }
+        }
+    }
+
     #[test]
     fn test_filter_invalid_regex() {
         let headers = make_headers(&["name", "age"]);
@@ -0,0 +1,142 @@
+use anyhow::Result;
+use csv::StringRecord;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::error::{ColumnSuggestion, CsvpeekError, find_similar_column};
+use crate::types::{FreqRow, FreqTable, is_null};
+
+/// Counts the values of one column (freq subcommand). With a bin width,
+/// a column whose values are all numeric is counted per bucket instead.
+pub struct FreqBuilder {
+    column: String,
+    index: usize,
+    bin_width: Option<f64>,
+    counts: HashMap<String, u64>,
+    null_count: u64,
+    rows: u64,
+}
+
+impl FreqBuilder {
+    pub fn new(column: &str, bin_width: Option<f64>, headers: &StringRecord) -> Result<Self> {
+        if let Some(w) = bin_width
+            && !(w.is_finite() && w > 0.0)
+        {
+            return Err(anyhow::anyhow!(
+                "--bin-width must be a positive number, got {w}"
+            ));
+        }
+        let index = headers.iter().position(|h| h == column).ok_or_else(|| {
+            let names: Vec<String> = headers.iter().map(|s| s.to_string()).collect();
+            CsvpeekError::ColumnNotFound {
+                name: column.to_string(),
+                suggestion: find_similar_column(column, &names)
+                    .map(|s| ColumnSuggestion { suggested: s }),
+            }
+        })?;
+
+        Ok(Self {
+            column: column.to_string(),
+            index,
+            bin_width,
+            counts: HashMap::new(),
+            null_count: 0,
+            rows: 0,
+        })
+    }
+
+    pub fn add_record(&mut self, record: &StringRecord) {
+        self.rows += 1;
+        let value = record.get(self.index).unwrap_or("");
+        if is_null(value) {
+            self.null_count += 1;
+            return;
+        }
+        *self.counts.entry(value.trim().to_string()).or_insert(0) += 1;
+    }
+
+    pub fn finalize(self) -> Result<FreqTable> {
+        let numeric: Option<Vec<(f64, u64)>> = self
+            .counts
+            .iter()
+            .map(|(v, &c)| {
+                v.parse::<f64>()
+                    .ok()
+                    .filter(|n| n.is_finite())
+                    .map(|n| (n, c))
+            })
+            .collect();
+
+        let (mut rows, bin_width) = match (self.bin_width, numeric) {
+            (Some(width), Some(values)) => (bin(&values, width)?, Some(width)),
+            _ => {
+                let mut rows: Vec<FreqRow> = self
+                    .counts
+                    .into_iter()
+                    .map(|(value, count)| FreqRow {
+                        value: Some(value),
+                        count,
+                        percent: 0.0,
+                    })
+                    .collect();
+                // Most frequent first; ties by value so output is stable
+                rows.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.value.cmp(&b.value)));
+                (rows, None)
+            }
+        };
+
+        if self.null_count > 0 {
+            rows.push(FreqRow {
+                value: None,
+                count: self.null_count,
+                percent: 0.0,
+            });
+        }
+        for row in &mut rows {
+            row.percent = row.count as f64 / self.rows as f64 * 100.0;
+        }
+
+        Ok(FreqTable {
+            column: self.column,
+            bin_width,
+            rows: self.rows,
+            values: rows,
+        })
+    }
+}
+
+/// Count values per `[lo, lo + width)` bucket, in ascending order. Empty
+/// buckets between the smallest and largest value are left out.
+fn bin(values: &[(f64, u64)], width: f64) -> Result<Vec<FreqRow>> {
+    let too_many = |value: f64| {
+        anyhow::anyhow!("{value:e} is too far from 0 for --bin-width {width}; use a wider bin")
+    };
+    let mut buckets: BTreeMap<i64, u64> = BTreeMap::new();
+    for &(value, count) in values {
+        let index = (value / width).floor();
+        // i64::MAX as f64 rounds up to 2^63, so the upper test is strict
+        if !(index >= i64::MIN as f64 && index < i64::MAX as f64) {
+            return Err(too_many(value));
+        }
+        *buckets.entry(index as i64).or_insert(0) += count;
+    }
+    buckets
+        .into_iter()
+        .map(|(i, count)| {
+            let upper = i.checked_add(1).ok_or_else(|| too_many(i as f64 * width))?;
+            Ok(FreqRow {
+                value: Some(format!(
+                    "[{}, {})",
+                    bound(i as f64 * width),
+                    bound(upper as f64 * width)
+                )),
+                count,
+                percent: 0.0,
+            })
+        })
+        .collect()
+}
+
+/// A bucket bound without float noise like 0.30000000000000004
+fn bound(x: f64) -> f64 {
+    (x * 1e9).round() / 1e9
+}
@@ -0,0 +1,186 @@
+use anyhow::Result;
+use csv::StringRecord;
+use std::collections::{HashMap, HashSet};
+
+use crate::error::{ColumnSuggestion, CsvpeekError, find_similar_column};
+use crate::stats::{StatsCollector, StatsOptions};
+use crate::types::GroupStats;
+
+/// Default bound on distinct groups (--max-groups), to avoid one collector
+/// per row on near-unique columns
+pub const DEFAULT_MAX_GROUPS: usize = 10_000;
+
+/// The group that pools the rows of smaller groups with --group-overflow top
+pub const OTHER_GROUP: &str = "(other)";
+
+/// What happens when a column has more distinct values than --max-groups
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GroupOverflow {
+    Error, // Abort with TooManyGroups
+    Top,   // Keep the --max-groups largest groups, pool the rest into (other)
+}
+
+impl GroupOverflow {
+    pub fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "error" => Ok(GroupOverflow::Error),
+            "top" => Ok(GroupOverflow::Top),
+            _ => Err(anyhow::anyhow!(
+                "Unknown group overflow mode: {s}. Supported: error, top"
+            )),
+        }
+    }
+}
+
+pub struct GroupByCollector {
+    group_column: String,
+    group_index: usize,
+    target_columns: Vec<String>,
+    headers: StringRecord,
+    options: StatsOptions,
+    groups: Vec<GroupAccumulator>,
+    group_positions: HashMap<String, usize>,
+    max_groups: usize,
+    overflow: GroupOverflow,
+    kept: Option<HashSet<String>>, // With Top, the values that get their own group
+    other: Option<GroupAccumulator>, // Rows of every other value
+}
+
+struct GroupAccumulator {
+    key: String,
+    rows: u64,
+    collector: StatsCollector,
+}
+
+impl GroupByCollector {
+    pub fn new(
+        group_column: &str,
+        target_columns: &[String],
+        headers: &StringRecord,
+        options: StatsOptions,
+    ) -> Result<Self> {
+        let header_vec: Vec<String> = headers.iter().map(|s| s.to_string()).collect();
+        let group_index = header_vec
+            .iter()
+            .position(|h| h == group_column)
+            .ok_or_else(|| CsvpeekError::ColumnNotFound {
+                name: group_column.to_string(),
+                suggestion: find_similar_column(group_column, &header_vec)
+                    .map(|s| ColumnSuggestion { suggested: s }),
+            })?;
+
+        Ok(Self {
+            group_column: group_column.to_string(),
+            group_index,
+            target_columns: target_columns.to_vec(),
+            headers: headers.clone(),
+            options,
+            groups: Vec::new(),
+            group_positions: HashMap::new(),
+            max_groups: DEFAULT_MAX_GROUPS,
+            overflow: GroupOverflow::Error,
+            kept: None,
+            other: None,
+        })
+    }
+
+    pub fn with_max_groups(mut self, max_groups: usize, overflow: GroupOverflow) -> Self {
+        self.max_groups = max_groups;
+        self.overflow = overflow;
+        self
+    }
+
+    /// Rows per value, read ahead: with --group-overflow top only the
+    /// --max-groups largest get a group (ties to the smaller value)
+    pub fn with_group_sizes(mut self, sizes: HashMap<String, u64>) -> Self {
+        if self.overflow == GroupOverflow::Top && sizes.len() > self.max_groups {
+            let mut sizes: Vec<(String, u64)> = sizes.into_iter().collect();
+            sizes.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+            sizes.truncate(self.max_groups);
+            self.kept = Some(sizes.into_iter().map(|(key, _)| key).collect());
+        }
+        self
+    }
+
+    /// Whether rows should be counted for with_group_sizes first
+    pub fn needs_group_sizes(&self) -> bool {
+        self.overflow == GroupOverflow::Top
+    }
+
+    /// The trimmed --group-by value of a row
+    pub fn group_key<'r>(&self, record: &'r StringRecord) -> &'r str {
+        record.get(self.group_index).unwrap_or("").trim()
+    }
+
+    fn new_group(&self, key: &str) -> GroupAccumulator {
+        GroupAccumulator {
+            key: key.to_string(),
+            rows: 0,
+            collector: StatsCollector::new(
+                &self.target_columns,
+                &self.headers,
+                self.options.clone(),
+            ),
+        }
+    }
+
+    pub fn group_column(&self) -> &str {
+        &self.group_column
+    }
+
+    pub fn add_record(&mut self, record: &StringRecord) -> Result<()> {
+        let key = self.group_key(record);
+        let kept = self.kept.as_ref().is_none_or(|kept| kept.contains(key));
+
+        let group = match self.group_positions.get(key) {
+            Some(&pos) => &mut self.groups[pos],
+            None if kept && self.groups.len() < self.max_groups => {
+                let group = self.new_group(key);
+                self.group_positions
+                    .insert(key.to_string(), self.groups.len());
+                self.groups.push(group);
+                self.groups.last_mut().unwrap()
+            }
+            None => match self.overflow {
+                GroupOverflow::Error => {
+                    return Err(CsvpeekError::TooManyGroups {
+                        column: self.group_column.clone(),
+                        max: self.max_groups,
+                    }
+                    .into());
+                }
+                GroupOverflow::Top => {
+                    if self.other.is_none() {
+                        self.other = Some(self.new_group(OTHER_GROUP));
+                    }
+                    self.other.as_mut().unwrap()
+                }
+            },
+        };
+
+        group.rows += 1;
+        group.collector.add_record(record, &self.headers)
+    }
+
+    /// Approximate heap bytes held by every group's unique-value sets
+    pub fn unique_memory(&self) -> usize {
+        self.groups
+            .iter()
+            .chain(&self.other)
+            .map(|g| g.collector.unique_memory())
+            .sum()
+    }
+
+    /// Finalize every group, in order of first appearance, then (other)
+    pub fn finalize(&mut self) -> Vec<GroupStats> {
+        self.groups
+            .iter_mut()
+            .chain(&mut self.other)
+            .map(|g| GroupStats {
+                group: g.key.clone(),
+                rows: g.rows,
+                stats: g.collector.finalize(),
+            })
+            .collect()
+    }
+}
@@ -32,6 +32,10 @@ AVAILABLE TOPICS:
 EXAMPLES:
     csvp guide filters
     csvp guide stats
+
+    Any subcommand with --examples prints commands to copy, using the
+    column names of FILE when one is given:
+        csvp pivot data.csv --examples
 "#
     );
 }
@@ -60,12 +64,18 @@ FUNCTIONS:
     contains(column, "text")        String contains substring
         Example: contains(name, "test")
 
+    icontains(column, "text")       Case-insensitive substring match
+        Example: icontains(company, "corp")   matches "Corp", "CORP", ...
+
     matches(column, "regex")        Regular expression match
         Example: matches(email, "@example\\.com$")
         Example: matches(id, "^[A-Z]\\d{{4}}$")
 
     in(column, ["a", "b", "c"])     Value in list
         Example: in(status, ["active", "pending", "review"])
+    in(column, a|b|c)               The same without brackets or quotes;
+                                    each value is trimmed
+        Example: csvp data.csv -w 'in(status, active|pending|review)'
 
     is_null(column)                 Value is null/empty
         Example: is_null(email)
@@ -73,6 +83,52 @@ FUNCTIONS:
     is_not_null(column)             Value is not null/empty
         Example: is_not_null(phone)
 
+    is_empty(column)                Value is an empty (or blank) cell
+        Example: is_empty(comment)
+
+NULL VS EMPTY:
+    is_null() matches empty cells AND the literals null, NA, N/A (any case).
+    is_empty() matches only truly empty cells, so a cell containing the
+    text "null" is NOT empty. Use it when "null" is a meaningful value.
+
+COMPARING NULLS:
+    Comparisons and contains/icontains/matches/in on a null cell (empty,
+    null, NA, N/A) are unknown, as in SQL: the row matches neither
+    age == 30 nor age != 30, and ! keeps it unknown, so !(age > 30) skips
+    it too. && and || follow SQL: false && unknown is false, true ||
+    unknown is true. Name nulls explicitly to include them:
+        age != 30 || is_null(age)
+    --null-matches ne treats a null as different from every value instead:
+    != matches it, ==, <, >, <=, >= and the functions don't, and ! flips
+    as usual. A number compared with a cell that isn't one ("abc", "12kg")
+    is unknown too, whatever --null-matches says: age == 30 and age != 30
+    both skip the row.
+
+SHORT ROWS:
+    A row with fewer fields than the header has no value for the missing
+    columns; filters read them as null (so is_null() matches them).
+    --strict-rows makes that an error naming the line, to catch
+    misaligned data:
+        Row at line 7 has 3 fields but the filter reads column 'city'
+        (field 4); the header has 5
+
+DATE RANGES:
+    --since and --until keep the rows whose --date-col falls in a range,
+    both ends included, without writing a filter:
+        csvp data.csv --date-col created_at --since 2024-01-01 --until 2024-12-31
+    Dates are YYYY-MM-DD or YYYY/MM/DD and are compared as dates; a time
+    after T or a space is ignored, so 2024-12-31T23:59 is on the 31st.
+    Rows whose date is null are left out. Rows whose date can't be read
+    (15/06/2024, 2024-02-30) are left out too, with a warning giving how
+    many. Combined with -w, a row has to pass both.
+
+COLUMNS:
+    Filters always see every column of the file, whatever -c selects:
+        csvp data.csv -c age -w "name == \"Alice\""
+    The table header then reads rows: 5, matched: 2 (40.0%).
+    With --no-header use the generated names col0, col1, ..., or the
+    names given with --header "id,name,age"
+
 GROUPING:
     Use parentheses for complex expressions:
         (age > 25 && age < 35) || status == "VIP"
@@ -118,35 +174,149 @@ The summary command computes various statistics for each column based on its dat
 
 COMMON STATISTICS (all columns):
     count       Number of non-null values
-    null%       Percentage of null/empty values
-    unique      Number of unique values
+    null%       Percentage of null/empty values; CSV/JSON split null_count
+                into empty_count (blank cells) and null_literal_count
+                (null, NA, N/A)
+    unique      Number of unique values (skip with --no-unique). Nulls are
+                not counted unless --count-null-as-unique, which also adds a
+                (null) bucket to the top values (null in JSON)
+    unique%     Cardinality ratio, unique / count: near 100% suggests a
+                key column, near 0% a constant one
+    first, last The first and last non-null values in file order
+                (first_value and last_value in CSV/JSON; add to the table
+                with --stats). Next to min and max they show whether a
+                column is sorted, or the tail of the file differs
 
 NUMERIC COLUMNS (Integer, Float):
     min         Minimum value
     max         Maximum value
     mean        Arithmetic mean (average)
+    w.mean      Σ(value * weight) / Σweight with --weight-col <column>;
+                rows whose weight isn't a number don't count towards it
+                (weighted_mean in CSV/JSON)
+    geo_mean    exp(mean(ln x)), for ratios and growth rates
+    harmonic_mean
+                n / Σ(1/x), for rates such as speeds or prices per unit
+                (both CSV/JSON only, and only when every value is > 0)
     median      Middle value (50th percentile)
+    sum         Total (CSV/JSON only); exact for integer columns, which
+                are added up as 128-bit integers, so large IDs or amounts
+                don't lose digits past 2^53 (9,007,199,254,740,992)
     std         Standard deviation
     p25         25th percentile (first quartile)
     p75         75th percentile (third quartile)
+    outliers    Values outside [p25 - 1.5*IQR, p75 + 1.5*IQR], IQR = p75 - p25
+                (outlier_count in CSV/JSON; add to the table with
+                --show-outliers)
+
+    decimals    Most fractional digits written in a value, trailing zeros
+                included ("1.50" is 2, "1.5e-3" is 4); useful for picking
+                NUMERIC(p,s). max_decimals in CSV/JSON; add to the table
+                with --precision-detect
+
+    non-finite  inf, -inf and NaN values, which are left out of min, max,
+                mean, std and the percentiles (non_finite_count in
+                CSV/JSON; in the table only when there are some). With
+                --nan-policy count they are nulls instead, and
+                --nan-policy error stops at the first one. Only numeric
+                columns have them: in a string column (inferred or by
+                --types) "NaN" is just a word
+
+    median, p25 and p75 are exact for columns of up to 10,000 numbers.
+    Beyond that the values are dropped and the P² algorithm estimates
+    them in constant memory; estimates are usually within a fraction of a
+    percent on smooth distributions. --exact-percentiles keeps every value
+    instead, as do --show-outliers and --stats outliers, because counting
+    outliers needs them (outlier_count is null for estimated columns).
+    --no-percentiles skips all of these; min, max, mean and std are
+    always computed in a single streaming pass.
+
+    --stats picks the statistics to compute and show, e.g.
+    --stats "count,null_rate,mean,p95". Names: count, null_rate, unique,
+    min, max, first, last, mean, weighted_mean, geo_mean, harmonic_mean,
+    median, p25, p75, outliers, sum, std, min_len, max_len, decimals,
+    booleans, top_values, and pNN for any other percentile from p1 to p99.
+    pNN percentiles are exact, so they keep every value like outliers;
+    they get a table column each and a percentiles field in JSON (pairs
+    like ["p95", 97.5]) and CSV (p95:97.5|p99:99.0). Values are only kept
+    in memory when median, p25, p75, outliers or a pNN is picked (see
+    above), and value counts only for top_values. The others are left out
+    of the table and null in CSV/JSON (count and null_rate are always
+    written there).
+
+    --flag-issues adds notes for columns that are 100% null, constant
+    (one distinct value) or all unique: a Warnings section under the table
+    and a notes field in CSV/JSON.
+
+    --precision-loss-warning adds a note for integer columns holding
+    values past ±2^53. Their sum stays exact, but mean, std, min/max and
+    the percentiles are computed in floating point and come out rounded.
+
+    --quickstats prints one tab-separated line per column and nothing
+    else, for pipelines: name, type, null_rate (two decimals), unique
+    ("-" with --no-unique). With --group-by the group comes first. Only
+    types, nulls and distinct values are computed, so it is also faster:
+        csvp data.csv --quickstats | awk -F'\t' '$3 > 50 {{print $1}}'
+
+    --totals adds a Totals footer under the table: cells analyzed, the
+    overall null rate (nulls over all cells, so every column counts by
+    its size), how many columns are numeric, string and boolean, and the
+    column with the highest null rate. Table output without --group-by.
+
+    --compare-to FILE compares against a summary saved earlier with
+    -f json, matching columns by name. The table appends each change to
+    count, null%, unique, mean, median and std, e.g. 42.50 (+3.10) or
+    5.0% (+2.0pp); JSON and NDJSON add a delta object with the same
+    fields. Columns the baseline lacks show no change. Not available with
+    --group-by; CSV output is unchanged.
+        csvp data.csv -f json -o baseline.json
+        csvp data.csv --compare-to baseline.json
+
+    --follow keeps the summary live for an append-only file such as a log:
+    after the first summary csvp checks the file every --follow-interval
+    seconds (default 1), adds only the rows appended since, and redraws
+    (clearing the terminal first) until Ctrl-C. A row is only read once
+    its terminator is written. Works with -w and --group-by; the file
+    must be local and shrinking it is an error.
 
 STRING COLUMNS:
+    min, max    Smallest and largest value in byte order; with
+                --string-extremes length, the shortest and longest value
     min_len     Minimum string length
     max_len     Maximum string length
     top         Most frequent values (up to 5)
 
 BOOLEAN COLUMNS:
-    Treated as string columns showing true/false distribution
+    true_count  Number of true values
+    false_count Number of false values
+    true_rate   Percentage of non-null values that are true
+    Shown in a Booleans section under the table ("active: 60.0% true
+    (3 true, 2 false)"), and as fields in CSV/JSON
 
 DATA TYPE INFERENCE:
-    Integer     All non-null values parse as integers
+    Integer     All non-null values parse as 64-bit integers
+                (larger integers such as 20-digit IDs are typed String)
     Float       Values contain decimals or mix of int/float
     Boolean     All values are true/false (case-insensitive)
     String      Everything else
 
+    A number in scientific notation is typed by its value: 1e3 and 2.5E3
+    are integers like 1000 and 2500 (up to 2^53), 2.5e-1 is a float. A
+    column mixing 1000 and 1e3 is therefore integer. --sci-as-float types
+    every exponent form as float instead.
+
+    Integers written with a leading zero (01234) are usually codes. schema
+    notes such columns (leading_zeros in CSV/JSON); --preserve-leading-zeros
+    types them as String everywhere.
+
+    Override inference with --types "zip=string,flag=boolean", e.g. to keep
+    leading zeros in codes that look numeric.
+
 OUTPUT COLUMNS BY FORMAT:
-    table       column, type, count, null%, unique, min, max, mean, median, std
-    csv/json    All statistics including: p25, p75, sum, min_len, max_len
+    table       column, type, count, null%, unique, unique%, min, max, mean,
+                median, std
+    csv/json    All statistics including: cardinality_ratio (0.0-1.0), p25,
+                p75, sum, min_len, max_len
 
 EXAMPLES:
     # View all statistics for all columns
@@ -160,6 +330,9 @@ EXAMPLES:
 
     # Filter before computing statistics
     csvp data.csv -w "year == 2024" -c "revenue,profit"
+
+    # Statistics per distinct value of a column (up to 10000 groups)
+    csvp data.csv --group-by region -c "revenue,profit"
 "#
     );
 }
@@ -183,9 +356,34 @@ BY RANGE:
     -c "0..=5"              Columns 0,1,2,3,4,5 (inclusive end)
     -c "3..7"               Columns 3,4,5,6
 
+BY WILDCARD:
+    -c "metric_*"           Every column starting with metric_
+    -c "*_count"            Every column ending in _count
+    -c "*rate*"             Every column containing rate
+                            Only * is special (any run of characters);
+                            matches come in file order, and a wildcard
+                            that matches nothing is an error listing the
+                            available columns
+
 MIXED:
     -c "name,0,3..5"        Combine names, indices, and ranges
 
+FROM A FILE:
+    --cols-file cols.txt    Columns listed in a file, one per line or
+                            comma-separated, in the same syntax as -c;
+                            blank lines and # comments are skipped. Added
+                            to any -c selection.
+
+BY PATTERN:
+    --cols-match "^metric_" Every column whose name matches the regex, in
+                            file order; added to any -c selection. Matching
+                            no column is an error.
+
+ORDER:
+    Columns are output in the order given in -c. With --preserve-order
+    they follow the file instead, so -c "salary,id" and -c "id,salary"
+    give the same report.
+
 NOTES:
     - Column indices start at 0
     - Range syntax follows Rust conventions:
@@ -193,6 +391,12 @@ NOTES:
         0..=5 = 0,1,2,3,4,5 (end included)
     - If a column name looks like a number, it's tried as an index first
     - Unknown column names show suggestions for similar names
+    - Header names are trimmed, so " name " is matched as name
+      (--no-trim-headers keeps them verbatim)
+    - --rename "col0=date,col1=amount" renames columns in the output; both
+      the old and the new name work in -c, -w, -g and --types
+    - Repeated header names are renamed name_2, name_3, ... so each
+      column can be selected on its own
 
 EXAMPLES:
     # Analyze first 10 columns
@@ -210,6 +414,7 @@ EXAMPLES:
 ERROR HANDLING:
     - Invalid index: "Column index 99 is out of range (max: 10)"
     - Unknown name:  "Column 'nmae' not found. Did you mean: 'name'?"
+    - No match:      "No columns match 'metric_*' (available: id, name)"
 "#
     );
 }
@@ -226,12 +431,41 @@ TABLE (default):
 
     csvp data.csv -f table
 
-    Example output:
-    ┌──────────┬─────────┬───────┬───────┬────────┬─────┬─────┬───────┐
-    │ column   │ type    │ count │ null% │ unique │ min │ max │ mean  │
-    ├──────────┼─────────┼───────┼───────┼────────┼─────┼─────┼───────┤
-    │ age      │ Integer │ 1000  │ 0.0%  │ 80     │ 18  │ 95  │ 42.5  │
-    └──────────┴─────────┴───────┴───────┴────────┴─────┴─────┴───────┘
+    Example output (tables are drawn with plain ASCII, so they survive log
+    files, email and terminals without box-drawing glyphs):
+    +--------+---------+-------+-------+--------+-----+-----+-------+
+    | column | type    | count | null% | unique | min | max | mean  |
+    +===============================================================+
+    | age    | integer | 1000  | 0.0%  | 80     | 18  | 95  | 42.50 |
+    +--------+---------+-------+-------+--------+-----+-----+-------+
+
+    Top values are the 5 most frequent, listed as value(count); values
+    with the same count are in value order.
+    --top-min-count N leaves out values seen fewer than N times, in every
+    format. --top-pct adds each value's share of the non-null values, as
+    value(count, 12.3%); JSON always includes it as top_values_pct.
+    Values containing , ( ) " \ or newlines are quoted with backslash
+    escapes, e.g. "a,b"(2). Use --quote-output always|never to change
+    this.
+
+    Tables fit the terminal width. When output goes to a file or pipe they
+    are 120 characters wide, so logs are reproducible; --width N sets it.
+    Values longer than 40 characters are cut with an ellipsis in tables
+    and top values (--max-cell-width N, 0 to never cut); CSV and JSON
+    output keep the full text.
+
+    --locale de-DE (or en-US, fr-FR, ja-JP, ...) formats numbers in the
+    table with that locale's separators, e.g. 1.234,56. JSON and CSV
+    always use plain 1234.56.
+
+    --round N sets the decimal places for mean/median/std/sum in every
+    format (default: 2 in the table, 6 in CSV, unrounded in JSON). Whole
+    numbers in integer columns are then printed without decimals.
+
+    --abbreviate shortens numbers of 1000 and up in tables to three
+    significant digits and a suffix: 12.3K, 45.6M, 1.23G, then T, P and E.
+    It covers counts, min/max and the decimal statistics, and takes
+    precedence over --round for them. JSON and CSV keep exact values.
 
 JSON:
     Pretty-printed JSON array. Good for programmatic processing.
@@ -248,6 +482,10 @@ JSON:
       }}
     ]
 
+    Keys are always in the same order and ties are broken by value, so
+    two runs on the same file give byte-identical output, safe for
+    golden-file tests.
+
 NDJSON (Newline Delimited JSON):
     One JSON object per line. Best for streaming/large datasets.
 
@@ -266,6 +504,20 @@ CSV:
     - p25, p75 (percentiles)
     - sum (total)
     - min_len, max_len (string lengths)
+    - top_values (after max_len), encoded as value:count|value:count,
+      e.g. a:10|b:7|c:3. A \, | or : inside a value is escaped with a
+      backslash (\\, \|, \:); the field is then CSV-quoted as usual.
+      The (null) bucket has an empty value, e.g. a:10|:4.
+
+    Columns added in later versions are appended at the end of the row,
+    so a script reading fields by position keeps working.
+
+PARQUET (build with --features parquet):
+    One row per column, for summary and schema. Requires -o since Parquet
+    is not written to stdout. top_values is a map of value -> count, with
+    the (null) bucket under the empty value.
+
+    csvp data.csv -f parquet -o stats.parquet
 
 OUTPUT TO FILE:
     Use -o/--output to write to a file instead of stdout:
@@ -278,6 +530,14 @@ COLOR CONTROL:
     --color auto      Auto-detect terminal (default)
     --color always    Always use colors
     --color never     Never use colors
+
+    --color-scheme picks the colors of the type column:
+    default           integer blue, float green, boolean yellow,
+                      string magenta
+    colorblind        integer blue, float yellow, boolean cyan, string
+                      white; no red-green contrasts
+    mono              integer bold, float underlined, boolean both,
+                      string plain; no hue at all
 "#
     );
 }
@@ -290,8 +550,41 @@ By default, csvp auto-detects the file encoding. Use -e/--encoding to specify ma
 
 AUTO-DETECTION:
     1. Check for BOM (Byte Order Mark) - UTF-8, UTF-16 LE/BE
-    2. Try UTF-8 decoding
-    3. Use chardetng library for detection
+    2. Try UTF-8 decoding of the first 64 KiB
+    3. Use chardetng library on the same 64 KiB
+
+    The whole file is then decoded with the encoding picked. --detect-bytes N
+    changes the sample size (0 uses the whole file), for a file whose first
+    non-ASCII text comes late; the BOM is checked however small N is.
+
+    UTF-8 and single-byte encodings (latin1, cp1252, koi8-r, iso-8859-*),
+    whether detected or given with -e, are decoded and parsed as the file
+    is read, so memory stays flat however large the file; --input-buffer-size
+    sets the read buffer [default: 64 KiB]. Multibyte legacy encodings
+    (shift_jis, gbk, ...) and UTF-16 are decoded from the whole file in
+    memory, as is any input with --check, --byte-start/--byte-end,
+    --fixed-width or a multi-character delimiter.
+
+    For an http(s) URL (--features http), a charset declared in the
+    Content-Type header is used instead of detection; -e still overrides it,
+    and an unrecognized charset falls back to detection.
+
+    Bytes that are invalid in the encoding used become U+FFFD, and a
+    warning says how many:
+        Warning: 42 character(s) could not be decoded with encoding 'utf-8'
+    A handful usually means a few stray bytes; many means the encoding is
+    wrong, so retry with -e.
+
+ENCODING REPORT:
+    --encoding-report decodes the whole file as each common encoding,
+    prints how many invalid byte sequences each one hits (each would
+    become U+FFFD), marks the encoding auto-detection picks and exits:
+        csvp legacy.csv --encoding-report
+    Encodings with 0 errors are the candidates for -e. chardetng gives no
+    scores, only whether it is confident in its guess. Single-byte
+    encodings such as windows-1252 or koi8-r accept almost any bytes, so
+    0 errors there says little; multibyte ones (utf-8, shift_jis, euc-jp,
+    gbk, big5, euc-kr) are strict, and 0 errors is a good sign.
 
 SUPPORTED ENCODINGS:
 
@@ -338,11 +631,205 @@ EXAMPLES:
     # UTF-16 with BOM
     csvp data.csv -e utf-16le
 
+    # Transcode a Shift_JIS file to UTF-8
+    csvp convert data.csv -e shift_jis --to csv --to-encoding utf-8 -o out.csv
+
 TIPS:
     - Most modern files are UTF-8
     - Japanese files from Windows are often Shift_JIS
-    - If you see garbled text, try specifying the encoding explicitly
+    - If you see garbled text, try specifying the encoding explicitly;
+      --encoding-report shows which encodings decode the file cleanly
     - Export from Excel often uses cp1252 (Windows) or UTF-8 with BOM
 "#
     );
 }
+
+/// Names filled into the --examples commands: columns of FILE when one was
+/// given, otherwise placeholders
+pub struct ExampleNames {
+    pub file: String,
+    pub text: String,           // A column to group and count by
+    pub other: String,          // A second column to pivot against
+    pub number: Option<String>, // A numeric column, if there is one
+}
+
+impl Default for ExampleNames {
+    fn default() -> Self {
+        Self {
+            file: "data.csv".to_string(),
+            text: "region".to_string(),
+            other: "status".to_string(),
+            number: Some("price".to_string()),
+        }
+    }
+}
+
+/// Quote a file or column name for a POSIX shell when it needs it
+pub fn shell_quote(s: &str) -> String {
+    let plain = !s.is_empty()
+        && s.chars()
+            .all(|c| c.is_ascii_alphanumeric() || "_-./:".contains(c));
+    if plain {
+        s.to_string()
+    } else {
+        format!("'{}'", s.replace('\'', r"'\''"))
+    }
+}
+
+/// --examples: commands to copy for one subcommand
+pub fn print_examples(command: &str, names: &ExampleNames) {
+    let file = shell_quote(&names.file);
+    let text = shell_quote(&names.text);
+    let other = shell_quote(&names.other);
+    let number = names.number.as_deref().map(shell_quote);
+    // A -w expression for rows worth keeping; a name the shell would need
+    // quoted can't be written in one
+    let filter = match names.number.as_deref() {
+        Some(number) if shell_quote(number) == number => Some(format!("{number} > 0")),
+        _ if shell_quote(&names.text) == names.text => Some(format!("is_not_null({})", names.text)),
+        _ => None,
+    };
+    let mut examples: Vec<(&str, String)> = Vec::new();
+
+    match command {
+        "summary" => {
+            examples.push(("Statistics for every column", format!("csvp {file}")));
+            let cols = match &number {
+                Some(number) => format!("{text},{number}"),
+                None => text.clone(),
+            };
+            examples.push((
+                "Only some columns, as JSON",
+                format!("csvp {file} -c {cols} -f json"),
+            ));
+            if let Some(filter) = &filter {
+                examples.push((
+                    "Only rows matching a filter",
+                    format!("csvp {file} -w \"{filter}\""),
+                ));
+            }
+            match &number {
+                Some(number) => {
+                    examples.push((
+                        "Statistics per group",
+                        format!("csvp {file} -c {number} --group-by {text}"),
+                    ));
+                }
+                None => examples.push((
+                    "Statistics per group",
+                    format!("csvp {file} --group-by {text}"),
+                )),
+            }
+            examples.push((
+                "Save a baseline, then show drift from it later",
+                format!("csvp {file} -f json -o baseline.json\n    csvp {file} --compare-to baseline.json"),
+            ));
+        }
+        "schema" => {
+            examples.push(("Column types and null rates", format!("csvp schema {file}")));
+            examples.push((
+                "Infer from the first rows only",
+                format!("csvp schema {file} --sample-size 1000"),
+            ));
+            examples.push((
+                "Why each column got its type",
+                format!("csvp schema {file} --explain-types"),
+            ));
+            examples.push((
+                "As a JSON Schema document",
+                format!("csvp schema {file} --as json-schema"),
+            ));
+        }
+        "pivot" => {
+            examples.push((
+                "Count rows for each pair of values",
+                format!("csvp pivot {file} --rows {text} -c {other}"),
+            ));
+            if let Some(number) = &number {
+                examples.push((
+                    "Sum a numeric column instead",
+                    format!(
+                        "csvp pivot {file} --rows {text} -c {other} --value {number} --agg sum"
+                    ),
+                ));
+            }
+        }
+        "freq" => {
+            examples.push((
+                "How often each value occurs",
+                format!("csvp freq {file} -c {text}"),
+            ));
+            if let Some(number) = &number {
+                examples.push((
+                    "Numbers grouped into bins",
+                    format!("csvp freq {file} -c {number} --bin-width 10"),
+                ));
+            }
+        }
+        "profile" => {
+            examples.push((
+                "Schema and statistics in one pass",
+                format!("csvp profile {file}"),
+            ));
+            examples.push((
+                "Saved as JSON",
+                format!("csvp profile {file} -f json -o profile.json"),
+            ));
+        }
+        "diff" => {
+            examples.push((
+                "Schema and row count changes since an older copy",
+                format!("csvp diff old.csv {file}"),
+            ));
+            examples.push((
+                "Per-column null rate drift as well",
+                format!("csvp diff old.csv {file} --data"),
+            ));
+        }
+        "count" => {
+            examples.push(("Number of rows", format!("csvp count {file}")));
+            examples.push((
+                "Without parsing fields, for a large file",
+                format!("csvp count {file} --fast-count"),
+            ));
+            if let Some(filter) = &filter {
+                examples.push((
+                    "Rows matching a filter",
+                    format!("csvp count {file} -w \"{filter}\""),
+                ));
+            }
+        }
+        "convert" => {
+            examples.push((
+                "To JSON",
+                format!("csvp convert {file} --to json -o out.json"),
+            ));
+            examples.push((
+                "Some columns to NDJSON",
+                format!("csvp convert {file} --to ndjson -c {text}"),
+            ));
+            examples.push((
+                "Re-encoded for a legacy tool",
+                format!("csvp convert {file} --to csv --to-encoding cp1252 -o out.csv"),
+            ));
+        }
+        _ => {
+            examples.push(("Filter expression syntax", "csvp guide filters".to_string()));
+            examples.push((
+                "Statistics for each column type",
+                "csvp guide stats".to_string(),
+            ));
+            examples.push((
+                "Column selection by name, index, range or wildcard",
+                "csvp guide columns".to_string(),
+            ));
+        }
+    }
+
+    println!("EXAMPLES ({command}):");
+    for (what, command) in examples {
+        println!();
+        println!("    # {what}");
+        println!("    {command}");
+    }
+}
@@ -0,0 +1,52 @@
+use anyhow::Result;
+use indicatif::{ProgressBar, ProgressFinish, ProgressStyle};
+use std::io::Read;
+
+/// Only show a download bar for bodies larger than this
+const PROGRESS_MIN_BYTES: u64 = 1_000_000;
+
+/// Start an HTTP(S) GET, following redirects, and return the body with the
+/// charset from its Content-Type, if any. Non-2xx responses are errors. With
+/// `show_progress`, a download bar is drawn when the server sends a
+/// Content-Length.
+pub fn open(url: &str, show_progress: bool) -> Result<(Box<dyn Read>, Option<String>)> {
+    let response = ureq::get(url).call().map_err(|e| match e {
+        ureq::Error::StatusCode(status) => {
+            anyhow::anyhow!("HTTP {status} fetching {url}")
+        }
+        e => anyhow::anyhow!("Could not fetch {url}: {e}"),
+    })?;
+
+    let charset = response
+        .headers()
+        .get("content-type")
+        .and_then(|v| v.to_str().ok())
+        .and_then(charset_param);
+    let length = response.body().content_length();
+    let body = response.into_body().into_reader();
+
+    match length.filter(|&n| show_progress && n >= PROGRESS_MIN_BYTES) {
+        Some(n) => {
+            let bar = ProgressBar::new(n).with_finish(ProgressFinish::AndClear);
+            bar.set_style(
+                ProgressStyle::default_bar()
+                    .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {bytes}/{total_bytes} ({eta})")
+                    .unwrap()
+                    .progress_chars("#>-"),
+            );
+            Ok((Box::new(bar.wrap_read(body)), charset))
+        }
+        None => Ok((Box::new(body), charset)),
+    }
+}
+
+/// The charset parameter of a Content-Type, e.g. `text/csv; charset="Shift_JIS"`
+fn charset_param(content_type: &str) -> Option<String> {
+    content_type.split(';').skip(1).find_map(|param| {
+        let (key, value) = param.split_once('=')?;
+        key.trim()
+            .eq_ignore_ascii_case("charset")
+            .then(|| value.trim().trim_matches('"').to_string())
+            .filter(|v| !v.is_empty())
+    })
+}
@@ -1,28 +1,94 @@
+#[cfg(feature = "arrow")]
+mod arrow_output;
 mod cli;
+mod convert;
 mod csv_reader;
+mod diff;
 mod error;
 mod filter;
+mod freq;
+mod groupby;
 mod guide;
+#[cfg(feature = "http")]
+mod http_input;
 mod output;
+#[cfg(feature = "parquet")]
+mod parquet_output;
+mod pivot;
+mod preview;
 mod progress;
+mod quantile;
 mod schema;
 mod stats;
 mod types;
 
 use anyhow::Result;
 use cli::{Cli, Commands};
+use error::ErrorFormat;
 
 fn main() -> Result<()> {
     let cli = Cli::parse_args();
+    let error_format = ErrorFormat::from_str(&cli.error_format)?;
+    error::set_error_format(error_format);
 
-    match &cli.command {
-        Some(Commands::Summary(_)) | None => {
-            commands::run_summary(&cli)?;
+    match run(&cli) {
+        Err(e) if error_format == ErrorFormat::Json => {
+            eprintln!("{}", error::error_to_json(&e));
+            std::process::exit(1);
         }
-        Some(Commands::Schema(_)) => {
-            commands::run_schema(&cli)?;
+        result => result,
+    }
+}
+
+fn run(cli: &Cli) -> Result<()> {
+    let default;
+    let command = match &cli.command {
+        Some(command) => command,
+        None => {
+            default = cli::default_command()?;
+            &default
+        }
+    };
+    check_command_options(cli, command)?;
+
+    if cli.check && !matches!(command, Commands::Guide(_)) {
+        return commands::run_check(cli);
+    }
+    if cli.encoding_report && !matches!(command, Commands::Guide(_)) {
+        return commands::run_encoding_report(cli);
+    }
+    if cli.examples {
+        return commands::run_examples(cli, command);
+    }
+
+    match command {
+        Commands::Summary(_) => {
+            commands::run_summary(cli)?;
+        }
+        Commands::Schema(args) => {
+            commands::run_schema(cli, args)?;
+        }
+        Commands::Pivot(args) => {
+            commands::run_pivot(cli, args)?;
+        }
+        Commands::Freq(args) => {
+            commands::run_freq(cli, args)?;
         }
-        Some(Commands::Guide(args)) => {
+        Commands::Profile(_) => {
+            commands::run_profile(cli)?;
+        }
+        Commands::Diff(args) => {
+            if !commands::run_diff(cli, args)? {
+                std::process::exit(1);
+            }
+        }
+        Commands::Count(args) => {
+            commands::run_count(cli, args)?;
+        }
+        Commands::Convert(args) => {
+            commands::run_convert(cli, args)?;
+        }
+        Commands::Guide(args) => {
             guide::print_guide(args.topic.as_deref());
         }
     }
@@ -30,32 +96,586 @@ fn main() -> Result<()> {
     Ok(())
 }
 
+/// Reject global options the command doesn't use, before any of the modes
+/// (--check, --encoding-report, --examples) that return early
+fn check_command_options(cli: &Cli, command: &Commands) -> Result<()> {
+    if cli.group_by.is_some() && !matches!(command, Commands::Summary(_)) {
+        return Err(anyhow::anyhow!(
+            "--group-by is not supported by {}",
+            command.name()
+        ));
+    }
+    if cli.follow && !matches!(command, Commands::Summary(_)) {
+        return Err(anyhow::anyhow!(
+            "--follow is not supported by {}",
+            command.name()
+        ));
+    }
+    Ok(())
+}
+
 mod commands {
     use super::*;
-    use crate::csv_reader::{CsvOptions, CsvReader};
-    use crate::filter::Filter;
-    use crate::output::{ColorMode, OutputFormat, Renderer};
-    use crate::progress::ProgressTracker;
+    use crate::convert::{ConvertTarget, EncodingWriter, OutputColumn, open_output, row_writer};
+    use crate::csv_reader::{CsvOptions, CsvReader, FileTail, lookup_encoding};
+    use crate::diff::{SchemaSnapshot, diff_schemas};
+    use crate::error::{CsvpeekError, MissingColumn, Warning, find_similar_column};
+    use crate::filter::{DateRange, Filter, NullMatches};
+    use crate::freq::FreqBuilder;
+    use crate::groupby::{GroupByCollector, GroupOverflow};
+    use crate::guide::{self, ExampleNames};
+    use crate::output::{
+        ColorMode, ColorScheme, NumberLocale, OutputFormat, QuoteMode, Renderer, SchemaDialect,
+    };
+    use crate::pivot::{Aggregation, PivotBuilder};
+    use crate::preview::{Preview, RowPreview};
+    use crate::progress::{ProgressTracker, Timings};
     use crate::schema::SchemaInferrer;
-    use crate::stats::StatsCollector;
+    use crate::stats::{
+        NanPolicy, StatSelection, StatsCollector, StatsOptions, StringExtremes,
+        compare_to_baseline, summary_totals,
+    };
+    use crate::types::{ColumnSchema, ColumnStats, DataType, null_kind, parse_value};
+    use csv::StringRecord;
+    use std::collections::HashMap;
+    use std::io::{IsTerminal, Write};
+    use std::time::Instant;
 
-    fn build_csv_options(cli: &Cli) -> CsvOptions {
-        let delimiter = parse_delimiter(&cli.delimiter);
-        CsvOptions::new()
+    fn build_csv_options(cli: &Cli) -> Result<CsvOptions> {
+        let multi_delimiter =
+            is_multi_char_delimiter(&cli.delimiter).then(|| cli.delimiter.clone());
+        let delimiter = match multi_delimiter {
+            Some(_) => b',', // Unused; the separator is swapped for a stand-in
+            None => parse_delimiter(&cli.delimiter)?,
+        };
+        let terminator = cli
+            .terminator
+            .as_deref()
+            .map(parse_terminator)
+            .transpose()?;
+        let escape = cli.escape.as_deref().map(parse_escape).transpose()?;
+        if escape == Some(delimiter) && multi_delimiter.is_none() {
+            return Err(anyhow::anyhow!(
+                "--escape and --delimiter must be different characters"
+            ));
+        }
+        if let (Some(start), Some(end)) = (cli.byte_start, cli.byte_end)
+            && start >= end
+        {
+            return Err(anyhow::anyhow!(
+                "--byte-start ({start}) must be less than --byte-end ({end})"
+            ));
+        }
+        let fixed_width = cli
+            .fixed_width
+            .as_deref()
+            .map(cli::parse_fixed_width)
+            .transpose()?;
+        let header = cli
+            .header
+            .as_deref()
+            .map(cli::parse_header_list)
+            .transpose()?;
+        if let (Some(ranges), Some(names)) = (&fixed_width, &header)
+            && ranges.len() != names.len()
+        {
+            return Err(anyhow::anyhow!(
+                "--header names {} columns but --fixed-width has {} ranges",
+                names.len(),
+                ranges.len()
+            ));
+        }
+        if cli.input_buffer_size == 0 {
+            return Err(anyhow::anyhow!("--input-buffer-size must be at least 1"));
+        }
+        Ok(CsvOptions::new()
             .with_delimiter(delimiter)
+            .with_multi_delimiter(multi_delimiter)
             .with_no_header(cli.no_header)
+            .with_header(header)
+            .with_fixed_width(fixed_width)
+            .with_trim_headers(!cli.no_trim_headers)
+            .with_progress(!cli.quiet)
             .with_encoding(cli.encoding.clone())
+            .with_terminator(terminator)
+            .with_escape(escape)
+            .with_max_field_bytes(cli.max_field_bytes)
+            .with_skip_errors(cli.skip_errors)
+            .with_byte_window(cli.byte_start, cli.byte_end)
+            .with_buffer_size(cli.input_buffer_size)
+            .with_detect_bytes(cli.detect_bytes))
+    }
+
+    fn build_renderer(cli: &Cli) -> Result<Renderer> {
+        let format = cli.format.as_deref().unwrap_or("table");
+        let color_mode = ColorMode::from_str(&cli.color);
+        // colored turns itself off when stdout isn't a terminal
+        if color_mode == ColorMode::Always {
+            colored::control::set_override(true);
+        }
+        Ok(Renderer::new(OutputFormat::from_str(format)?)
+            .with_output(cli.output.clone())
+            .with_color(color_mode)
+            .with_color_scheme(ColorScheme::from_str(&cli.color_scheme)?)
+            .with_quote_mode(QuoteMode::from_str(&cli.quote_output)?)
+            .with_locale(
+                cli.locale
+                    .as_deref()
+                    .map(NumberLocale::from_str)
+                    .transpose()?,
+            )
+            .with_round(cli.round)
+            .with_abbreviate(cli.abbreviate)
+            .with_width(cli.width)
+            .with_max_cell_width(cli.max_cell_width)
+            .with_top_pct(cli.top_pct)
+            .with_stat_selection(build_stat_selection(cli)?)
+            .with_outliers(cli.show_outliers)
+            .with_decimals(cli.precision_detect))
+    }
+
+    fn build_stats_options(
+        cli: &Cli,
+        headers: &StringRecord,
+        renames: &HashMap<String, String>,
+    ) -> Result<StatsOptions> {
+        let selection = build_stat_selection(cli)?;
+        Ok(StatsOptions::new()
+            .with_unique(!cli.no_unique)
+            .with_percentiles(!cli.no_percentiles)
+            // Counting outliers and pNN percentiles needs every value
+            .with_exact_percentiles(
+                cli.exact_percentiles
+                    || cli.show_outliers
+                    || selection.picked("outliers")
+                    || !selection.percentiles().is_empty(),
+            )
+            .with_flag_issues(cli.flag_issues)
+            .with_precision_warning(cli.precision_loss_warning)
+            .with_preserve_leading_zeros(cli.preserve_leading_zeros)
+            .with_sci_as_float(cli.sci_as_float)
+            .with_null_as_unique(cli.count_null_as_unique)
+            .with_weight_column(build_weight_column(cli, headers, renames)?)
+            .with_top_min_count(cli.top_min_count)
+            .with_string_extremes(StringExtremes::from_str(&cli.string_extremes)?)
+            .with_nan_policy(NanPolicy::from_str(&cli.nan_policy)?)
+            .with_selection(selection)
+            .with_type_overrides(build_type_overrides(cli, headers, renames)?))
+    }
+
+    fn build_stat_selection(cli: &Cli) -> Result<StatSelection> {
+        // --quickstats only prints these, so skip the rest of the work
+        if cli.quickstats {
+            return StatSelection::parse("null_rate,unique");
+        }
+        cli.stats
+            .as_deref()
+            .map_or(Ok(StatSelection::default()), StatSelection::parse)
+    }
+
+    fn build_weight_column(
+        cli: &Cli,
+        headers: &StringRecord,
+        renames: &HashMap<String, String>,
+    ) -> Result<Option<String>> {
+        let Some(ref weight_col) = cli.weight_col else {
+            return Ok(None);
+        };
+        match select_columns(weight_col, headers, renames)?.as_slice() {
+            [column] => Ok(Some(column.clone())),
+            _ => Err(anyhow::anyhow!(
+                "--weight-col takes a single column, got '{weight_col}'"
+            )),
+        }
+    }
+
+    fn build_type_overrides(
+        cli: &Cli,
+        headers: &StringRecord,
+        renames: &HashMap<String, String>,
+    ) -> Result<HashMap<String, DataType>> {
+        let Some(ref types) = cli.types else {
+            return Ok(HashMap::new());
+        };
+        let types: Vec<String> = types
+            .split(',')
+            .map(|pair| match pair.rsplit_once('=') {
+                Some((col, dtype)) => {
+                    format!("{}={dtype}", resolve_name(col.trim(), headers, renames))
+                }
+                None => pair.to_string(),
+            })
+            .collect();
+        cli::parse_type_overrides(&types.join(","), headers)
+    }
+
+    fn build_filter(
+        cli: &Cli,
+        headers: &StringRecord,
+        renames: &HashMap<String, String>,
+    ) -> Result<Option<Filter>> {
+        let date_range = build_date_range(cli, headers, renames)?;
+        let Some(ref w) = cli.where_clause else {
+            return Ok(date_range.map(Filter::from_date_range));
+        };
+        Ok(Some(
+            Filter::parse_with_aliases(w, headers, renames)?
+                .with_null_matches(NullMatches::from_str(&cli.null_matches)?)
+                .with_strict_rows(cli.strict_rows, headers)
+                .with_date_range(date_range),
+        ))
     }
 
-    fn parse_delimiter(s: &str) -> u8 {
-        match s.to_lowercase().as_str() {
+    fn build_date_range(
+        cli: &Cli,
+        headers: &StringRecord,
+        renames: &HashMap<String, String>,
+    ) -> Result<Option<DateRange>> {
+        let Some(ref date_col) = cli.date_col else {
+            return Ok(None);
+        };
+        if cli.since.is_none() && cli.until.is_none() {
+            return Err(anyhow::anyhow!("--date-col needs --since or --until"));
+        }
+        match select_columns(date_col, headers, renames)?.as_slice() {
+            [column] => {
+                let index = headers.iter().position(|h| h == column).unwrap();
+                Ok(Some(DateRange::new(
+                    column,
+                    index,
+                    cli.since.as_deref(),
+                    cli.until.as_deref(),
+                )?))
+            }
+            _ => Err(anyhow::anyhow!(
+                "--date-col takes a single column, got '{date_col}'"
+            )),
+        }
+    }
+
+    /// --where, with --since/--until spelled out, for the filter: line
+    fn filter_description(cli: &Cli) -> Option<String> {
+        let date_col = cli.date_col.as_deref().unwrap_or_default();
+        let bounds = [
+            cli.since.as_ref().map(|d| format!("{date_col} >= {d}")),
+            cli.until.as_ref().map(|d| format!("{date_col} <= {d}")),
+        ];
+        let mut parts: Vec<String> = bounds.into_iter().flatten().collect();
+        match cli.where_clause {
+            Some(ref w) if parts.is_empty() => return Some(w.clone()),
+            Some(ref w) => parts.insert(0, format!("({w})")),
+            None => {}
+        }
+        (!parts.is_empty()).then(|| parts.join(" && "))
+    }
+
+    /// Warnings about the input, once its records have been read
+    fn report_input(reader: &CsvReader, filter: Option<&Filter>) {
+        if reader.skipped() > 0 {
+            Warning::SkippedRows(reader.skipped()).report();
+        }
+        let (undecodable, description) = reader.undecodable();
+        if undecodable > 0 {
+            Warning::Undecodable {
+                count: undecodable,
+                description: description.to_string(),
+            }
+            .report();
+        }
+        if let Some(warning) = filter.and_then(Filter::date_warning) {
+            warning.report();
+        }
+    }
+
+    /// Read the headers and apply --rename, returning the renamed headers and
+    /// a map from each renamed column's original name to its new one
+    fn read_headers(
+        cli: &Cli,
+        reader: &mut CsvReader,
+    ) -> Result<(StringRecord, HashMap<String, String>)> {
+        let headers = reader.headers()?.clone();
+        let renames = match cli.rename {
+            Some(ref renames) => cli::parse_renames(renames, &headers)?,
+            None => HashMap::new(),
+        };
+        let renamed: StringRecord = headers
+            .iter()
+            .map(|h| renames.get(h).map_or(h, String::as_str))
+            .collect();
+        if let Some(ref required) = cli.require_columns {
+            check_required_columns(required, &renamed, &renames)?;
+        }
+        Ok((renamed, renames))
+    }
+
+    /// --require-columns: fail listing every required column the header
+    /// lacks, by its current or original name
+    fn check_required_columns(
+        required: &str,
+        headers: &StringRecord,
+        renames: &HashMap<String, String>,
+    ) -> Result<()> {
+        let names: Vec<String> = headers.iter().map(str::to_string).collect();
+        let missing: Vec<MissingColumn> = required
+            .split(',')
+            .map(str::trim)
+            .filter(|n| !n.is_empty())
+            .filter(|n| !names.iter().any(|h| h == n) && !renames.contains_key(*n))
+            .map(|n| MissingColumn {
+                name: n.to_string(),
+                suggestion: find_similar_column(n, &names),
+            })
+            .collect();
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(CsvpeekError::MissingColumns(missing).into())
+        }
+    }
+
+    /// The current name of a column given by its new or its original name
+    fn resolve_name<'a>(
+        name: &'a str,
+        headers: &StringRecord,
+        renames: &'a HashMap<String, String>,
+    ) -> &'a str {
+        match renames.get(name) {
+            Some(new_name) if !headers.iter().any(|h| h == name) => new_name,
+            _ => name,
+        }
+    }
+
+    /// parse_columns, also accepting the original names of renamed columns
+    fn select_columns(
+        cols: &str,
+        headers: &StringRecord,
+        renames: &HashMap<String, String>,
+    ) -> Result<Vec<String>> {
+        let cols: Vec<&str> = cols
+            .split(',')
+            .map(|c| resolve_name(c.trim(), headers, renames))
+            .collect();
+        cli::parse_columns(&cols.join(","), headers)
+    }
+
+    /// The union of the -c, --cols-file and --cols-match selections, in file
+    /// order under --preserve-order; None when none is given
+    fn target_columns(
+        cli: &Cli,
+        headers: &StringRecord,
+        renames: &HashMap<String, String>,
+    ) -> Result<Option<Vec<String>>> {
+        if cli.cols.is_none() && cli.cols_file.is_none() && cli.cols_match.is_none() {
+            return Ok(None);
+        }
+
+        let mut columns = match cli.cols {
+            Some(ref cols) => select_columns(cols, headers, renames)?,
+            None => Vec::new(),
+        };
+        let mut extra = Vec::new();
+        if let Some(ref path) = cli.cols_file {
+            extra.extend(read_cols_file(path, headers, renames)?);
+        }
+        if let Some(ref pattern) = cli.cols_match {
+            extra.extend(cli::match_columns(pattern, headers)?);
+        }
+        for name in extra {
+            if !columns.contains(&name) {
+                columns.push(name);
+            }
+        }
+        if cli.preserve_order {
+            columns.sort_by_key(|c| headers.iter().position(|h| h == c));
+        }
+        Ok(Some(columns))
+    }
+
+    /// Columns listed in a --cols-file: one entry per line or comma-separated,
+    /// each a name, index or range as in -c. Blank lines and lines starting
+    /// with # are skipped.
+    fn read_cols_file(
+        path: &str,
+        headers: &StringRecord,
+        renames: &HashMap<String, String>,
+    ) -> Result<Vec<String>> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("Could not read --cols-file '{path}': {e}"))?;
+        let mut columns = Vec::new();
+        for line in text.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            for name in select_columns(line.trim_end_matches(','), headers, renames)? {
+                if !columns.contains(&name) {
+                    columns.push(name);
+                }
+            }
+        }
+        Ok(columns)
+    }
+
+    fn parse_delimiter(s: &str) -> Result<u8> {
+        let delimiter = match s.to_lowercase().as_str() {
             "tab" | "\\t" | "\t" => b'\t',
             "comma" | "," => b',',
             "semicolon" | ";" => b';',
             "pipe" | "|" => b'|',
             "space" | " " => b' ',
+            lower if lower.starts_with("\\x") || lower.starts_with("\\u{") => {
+                parse_escaped_byte(s)?
+            }
             _ => s.as_bytes().first().copied().unwrap_or(b','),
+        };
+        Ok(delimiter)
+    }
+
+    /// A literal separator like `::` or `|~|`, rather than a single character,
+    /// a name (tab) or an escape (\x1f)
+    fn is_multi_char_delimiter(s: &str) -> bool {
+        let lower = s.to_lowercase();
+        s.len() > 1
+            && !matches!(
+                lower.as_str(),
+                "tab" | "\\t" | "comma" | "semicolon" | "pipe" | "space"
+            )
+            && !lower.starts_with("\\x")
+            && !lower.starts_with("\\u{")
+    }
+
+    fn parse_terminator(s: &str) -> Result<u8> {
+        match s {
+            "\\n" => Ok(b'\n'),
+            "\\r" => Ok(b'\r'),
+            _ if s.starts_with("\\x") || s.starts_with("\\u{") => parse_escaped_byte(s),
+            _ if s.len() == 1 => Ok(s.as_bytes()[0]),
+            _ => Err(anyhow::anyhow!(
+                "Invalid terminator '{s}': must be a single ASCII character"
+            )),
+        }
+    }
+
+    fn parse_escape(s: &str) -> Result<u8> {
+        match s {
+            "\"" => Err(anyhow::anyhow!(
+                "Invalid escape '\"': doubled quotes (\"\") are always read as a quote"
+            )),
+            _ if s.starts_with("\\x") || s.starts_with("\\u{") => parse_escaped_byte(s),
+            _ if s.len() == 1 => Ok(s.as_bytes()[0]),
+            _ => Err(anyhow::anyhow!(
+                "Invalid escape '{s}': must be a single ASCII character"
+            )),
+        }
+    }
+
+    /// Parse `\xNN` or `\u{NNNN}` into a single ASCII byte
+    fn parse_escaped_byte(s: &str) -> Result<u8> {
+        let lower = s.to_lowercase();
+        let hex = match lower.strip_prefix("\\x") {
+            Some(hex) => hex,
+            None => lower
+                .strip_prefix("\\u{")
+                .and_then(|h| h.strip_suffix('}'))
+                .ok_or_else(|| anyhow::anyhow!("Invalid escape '{s}': expected \\u{{NN}}"))?,
+        };
+        let code = u32::from_str_radix(hex, 16)
+            .map_err(|_| anyhow::anyhow!("Invalid escape '{s}': '{hex}' is not hexadecimal"))?;
+        if code > 0x7F {
+            return Err(anyhow::anyhow!(
+                "Invalid escape '{s}': U+{code:04X} is not a single-byte ASCII character"
+            ));
+        }
+        Ok(code as u8)
+    }
+
+    /// --check: validate options against the header without reading any rows
+    pub fn run_check(cli: &Cli) -> Result<()> {
+        let file_path = cli
+            .file
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("FILE is required"))?;
+
+        build_renderer(cli)?;
+        let options = build_csv_options(cli)?.with_header_only(true);
+        let mut reader = CsvReader::from_path_with_options(file_path, options)?;
+        let (headers, renames) = read_headers(cli, &mut reader)?;
+
+        if let Some(columns) = target_columns(cli, &headers, &renames)? {
+            println!("columns: {}", columns.join(", "));
+        }
+        build_filter(cli, &headers, &renames)?;
+        let stats_options = build_stats_options(cli, &headers, &renames)?;
+        if let Some(ref group_col) = cli.group_by {
+            select_columns(group_col, &headers, &renames)?;
+            let group_col = resolve_name(group_col, &headers, &renames);
+            build_group_by(cli, group_col, &[], &headers, stats_options)?;
+        }
+
+        report_input(&reader, None);
+        println!("OK");
+        Ok(())
+    }
+
+    /// --encoding-report: how the file decodes as each common encoding
+    pub fn run_encoding_report(cli: &Cli) -> Result<()> {
+        let file_path = cli
+            .file
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("FILE is required"))?;
+
+        let report = csv_reader::encoding_report(file_path, &build_csv_options(cli)?)?;
+        build_renderer(cli)?.render_encoding_report(&report)
+    }
+
+    /// --examples: commands for this subcommand, naming real columns of
+    /// FILE when one is given
+    pub fn run_examples(cli: &Cli, command: &Commands) -> Result<()> {
+        const SAMPLE_ROWS: usize = 100;
+
+        // `csvp diff data.csv --examples` takes the one file as BASE
+        let file = match command {
+            Commands::Diff(args) if cli.file.is_none() && !args.base.is_empty() => Some(&args.base),
+            _ => cli.file.as_ref(),
+        };
+
+        let mut names = ExampleNames::default();
+        if let Some(file_path) = file {
+            let mut reader = CsvReader::from_path_with_options(file_path, build_csv_options(cli)?)?;
+            let (headers, _) = read_headers(cli, &mut reader)?;
+            if headers.is_empty() {
+                anyhow::bail!("{file_path} has no columns");
+            }
+            let mut numeric = vec![true; headers.len()];
+            let mut seen = vec![false; headers.len()];
+            for record in reader.records().take(SAMPLE_ROWS) {
+                for (i, value) in record?.iter().enumerate().take(headers.len()) {
+                    if null_kind(value).is_some() {
+                        continue;
+                    }
+                    seen[i] = true;
+                    if !matches!(parse_value(value).0, DataType::Integer | DataType::Float) {
+                        numeric[i] = false;
+                    }
+                }
+            }
+
+            let (numbers, texts): (Vec<usize>, Vec<usize>) =
+                (0..headers.len()).partition(|&i| seen[i] && numeric[i]);
+            let name = |i: usize| headers[i].to_string();
+            names.file = file_path.clone();
+            names.number = numbers.first().map(|&i| name(i));
+            // Without a text column, group and count by whichever column comes first
+            let text = texts.first().copied().unwrap_or(0);
+            names.text = name(text);
+            names.other = texts
+                .iter()
+                .chain(&numbers)
+                .find(|&&i| i != text)
+                .map_or_else(|| names.text.clone(), |&i| name(i));
         }
+
+        guide::print_examples(command.name(), &names);
+        Ok(())
     }
 
     pub fn run_summary(cli: &Cli) -> Result<()> {
@@ -64,26 +684,424 @@ mod commands {
             .as_ref()
             .ok_or_else(|| anyhow::anyhow!("FILE is required"))?;
 
-        let options = build_csv_options(cli);
+        let opened = Instant::now();
+        let options = build_csv_options(cli)?;
         let mut reader = CsvReader::from_path_with_options(file_path, options)?;
-        let headers = reader.headers()?.clone();
+        let (headers, renames) = read_headers(cli, &mut reader)?;
+        let mut timings = Timings {
+            open: opened.elapsed(),
+            ..Timings::default()
+        };
+
+        let group_by = cli
+            .group_by
+            .as_deref()
+            .map(|g| resolve_name(g, &headers, &renames));
 
         // Determine columns to process
-        let target_cols = if let Some(ref cols) = cli.cols {
-            cli::parse_columns(cols, &headers)?
-        } else {
-            headers.iter().map(|s| s.to_string()).collect()
-        };
+        let target_cols = target_columns(cli, &headers, &renames)?.unwrap_or_else(|| {
+            headers
+                .iter()
+                .filter(|h| group_by != Some(*h))
+                .map(|s| s.to_string())
+                .collect()
+        });
 
-        // Build filter if specified
-        let filter = if let Some(ref where_clause) = cli.where_clause {
-            Some(Filter::parse(where_clause, &headers)?)
-        } else {
-            None
+        let filter = build_filter(cli, &headers, &renames)?;
+        let baseline = match cli.compare_to {
+            Some(_) if group_by.is_some() => {
+                return Err(anyhow::anyhow!("--compare-to does not support --group-by"));
+            }
+            Some(ref path) => Some(read_baseline(path)?),
+            None => None,
         };
 
         // Collect statistics with progress tracking
-        let mut collector = StatsCollector::new(&target_cols, &headers);
+        let stats_options = build_stats_options(cli, &headers, &renames)?;
+        let collector = StatsCollector::new(&target_cols, &headers, stats_options.clone());
+        let grouped = match group_by {
+            Some(group_col) => {
+                let mut grouped =
+                    build_group_by(cli, group_col, &target_cols, &headers, stats_options)?;
+                if grouped.needs_group_sizes() {
+                    let sizes = group_sizes(cli, file_path, &grouped)?;
+                    grouped = grouped.with_group_sizes(sizes);
+                }
+                Some(grouped)
+            }
+            None => None,
+        };
+        let mut state = SummaryState {
+            collector,
+            grouped,
+            total_rows: 0,
+            matched_rows: 0,
+            preview: cli.preview.map(|n| RowPreview::new(n, &headers)),
+        };
+        if cli.follow {
+            return follow_summary(
+                cli,
+                file_path,
+                &headers,
+                filter.as_ref(),
+                baseline.as_deref(),
+                state,
+            );
+        }
+
+        let mut progress = ProgressTracker::new(file_path, cli.quiet);
+        let read_before = reader.read_time();
+        let mut records = reader.records();
+        loop {
+            let start = cli.timings.then(Instant::now);
+            let Some(result) = records.next() else {
+                break;
+            };
+            let record = result?;
+            let parsed = cli.timings.then(Instant::now);
+            progress.update(&record);
+            state.add_record(&record, filter.as_ref(), &headers)?;
+            if let (Some(start), Some(parsed)) = (start, parsed) {
+                timings.parse += parsed - start;
+                timings.stats += parsed.elapsed();
+            }
+        }
+        drop(records);
+        progress.finish();
+        report_input(&reader, filter.as_ref());
+
+        if !cli.timings {
+            return render_summary_state(cli, file_path, baseline.as_deref(), &mut state);
+        }
+        // The parser's time includes waiting on reads
+        timings.read = reader.read_time() - read_before;
+        timings.parse = timings.parse.saturating_sub(timings.read);
+        timings.rows = state.total_rows;
+        timings.unique_bytes = state.unique_memory();
+        let finalizing = Instant::now();
+        render_summary_state(cli, file_path, baseline.as_deref(), &mut state)?;
+        timings.finalize = finalizing.elapsed();
+        timings.print();
+        Ok(())
+    }
+
+    /// The --group-by collector, with --max-groups and --group-overflow
+    fn build_group_by(
+        cli: &Cli,
+        group_col: &str,
+        target_cols: &[String],
+        headers: &StringRecord,
+        options: StatsOptions,
+    ) -> Result<GroupByCollector> {
+        let overflow = GroupOverflow::from_str(&cli.group_overflow)?;
+        if overflow == GroupOverflow::Top && cli.follow {
+            return Err(anyhow::anyhow!(
+                "--group-overflow top reads the file twice and doesn't work with --follow"
+            ));
+        }
+        Ok(
+            GroupByCollector::new(group_col, target_cols, headers, options)?
+                .with_max_groups(cli.max_groups, overflow),
+        )
+    }
+
+    /// Rows per --group-by value among those the filter keeps, from a first
+    /// read of the file for --group-overflow top. Its warnings are left to
+    /// the read that follows.
+    fn group_sizes(
+        cli: &Cli,
+        file_path: &str,
+        grouped: &GroupByCollector,
+    ) -> Result<HashMap<String, u64>> {
+        let options = build_csv_options(cli)?.with_skip_errors(false);
+        let mut reader = CsvReader::from_path_with_options(file_path, options)?;
+        let (headers, renames) = read_headers(cli, &mut reader)?;
+        let filter = build_filter(cli, &headers, &renames)?;
+
+        let mut sizes = HashMap::new();
+        for result in reader.records() {
+            let record = match result {
+                Ok(record) => record,
+                Err(_) if cli.skip_errors => continue,
+                Err(e) => return Err(e),
+            };
+            if let Some(ref f) = filter
+                && !f.matches(&record, &headers)?
+            {
+                continue;
+            }
+            *sizes
+                .entry(grouped.group_key(&record).to_string())
+                .or_insert(0) += 1;
+        }
+        Ok(sizes)
+    }
+
+    /// A summary saved with -f json, for --compare-to
+    fn read_baseline(path: &str) -> Result<Vec<ColumnStats>> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("Could not read --compare-to '{path}': {e}"))?;
+        serde_json::from_str(&text).map_err(|e| {
+            anyhow::anyhow!("--compare-to '{path}' is not a summary saved with -f json: {e}")
+        })
+    }
+
+    /// The rows summary has read so far, and statistics over those matching
+    /// the filter
+    struct SummaryState {
+        collector: StatsCollector,
+        grouped: Option<GroupByCollector>,
+        total_rows: u64,
+        matched_rows: u64,
+        preview: Option<RowPreview>,
+    }
+
+    impl SummaryState {
+        fn add_record(
+            &mut self,
+            record: &StringRecord,
+            filter: Option<&Filter>,
+            headers: &StringRecord,
+        ) -> Result<()> {
+            self.total_rows += 1;
+            if let Some(ref mut p) = self.preview {
+                p.add_record(record);
+            }
+
+            // Apply filter
+            if let Some(f) = filter
+                && !f.matches(record, headers)?
+            {
+                return Ok(());
+            }
+
+            self.matched_rows += 1;
+            match self.grouped {
+                Some(ref mut g) => g.add_record(record)?,
+                None => self.collector.add_record(record, headers)?,
+            }
+            Ok(())
+        }
+
+        fn unique_memory(&self) -> usize {
+            match self.grouped {
+                Some(ref g) => g.unique_memory(),
+                None => self.collector.unique_memory(),
+            }
+        }
+    }
+
+    fn render_summary_state(
+        cli: &Cli,
+        file_path: &str,
+        baseline: Option<&[ColumnStats]>,
+        state: &mut SummaryState,
+    ) -> Result<()> {
+        let preview = state.preview.as_ref().map(RowPreview::preview);
+        let renderer = build_renderer(cli)?.with_preview(preview);
+
+        if let Some(ref mut g) = state.grouped {
+            let group_col = g.group_column().to_string();
+            let groups = g.finalize();
+            if cli.quickstats {
+                return renderer.render_grouped_quickstats(&groups);
+            }
+            renderer.render_grouped_summary(
+                file_path,
+                state.total_rows,
+                state.matched_rows,
+                filter_description(cli).as_deref(),
+                &group_col,
+                &groups,
+            )?;
+            return Ok(());
+        }
+
+        let mut stats = state.collector.finalize();
+        if let Some(baseline) = baseline {
+            compare_to_baseline(&mut stats, baseline);
+        }
+        if cli.quickstats {
+            return renderer.render_quickstats(&stats);
+        }
+        let totals = cli.totals.then(|| summary_totals(&stats));
+        renderer.with_totals(totals).render_summary(
+            file_path,
+            state.total_rows,
+            state.matched_rows,
+            filter_description(cli).as_deref(),
+            &stats,
+        )?;
+
+        Ok(())
+    }
+
+    /// --follow: summarize the rows so far, then check the file for appended
+    /// rows every --follow-interval and redraw when some arrive. Runs until
+    /// interrupted.
+    fn follow_summary(
+        cli: &Cli,
+        file_path: &str,
+        headers: &StringRecord,
+        filter: Option<&Filter>,
+        baseline: Option<&[ColumnStats]>,
+        mut state: SummaryState,
+    ) -> Result<()> {
+        if !(cli.follow_interval.is_finite() && cli.follow_interval > 0.0) {
+            return Err(anyhow::anyhow!(
+                "--follow-interval must be a positive number of seconds"
+            ));
+        }
+        let interval = std::time::Duration::from_secs_f64(cli.follow_interval);
+        let mut tail = FileTail::new(file_path, build_csv_options(cli)?)?;
+        let terminal = std::io::stdout().is_terminal();
+
+        let mut first = true;
+        loop {
+            let records = tail.read_new()?;
+            if first || !records.is_empty() {
+                for record in &records {
+                    state.add_record(record, filter, headers)?;
+                }
+                if terminal {
+                    print!("\x1b[2J\x1b[H"); // Clear the screen and redraw from the top
+                }
+                render_summary_state(cli, file_path, baseline, &mut state)?;
+                std::io::stdout().flush()?;
+                first = false;
+            }
+            std::thread::sleep(interval);
+        }
+    }
+
+    pub fn run_schema(cli: &Cli, args: &cli::SchemaArgs) -> Result<()> {
+        let file_path = cli
+            .file
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("FILE is required"))?;
+
+        let dialect = args
+            .as_kind
+            .as_deref()
+            .map(SchemaDialect::from_str)
+            .transpose()?;
+        let preview = cli.preview.filter(|_| dialect.is_none());
+        let inferred = infer_schema(
+            cli,
+            file_path,
+            args.sample_size,
+            preview,
+            args.explain_types,
+        )?;
+        let sampled = inferred.sampled.then_some(inferred.rows);
+
+        if let Some(dialect) = dialect {
+            return build_renderer(cli)?.render_schema_as(dialect, file_path, &inferred.schema);
+        }
+
+        let renderer = build_renderer(cli)?.with_preview(inferred.preview);
+        renderer.render_schema(
+            file_path,
+            &inferred.schema,
+            sampled,
+            filter_description(cli).as_deref(),
+        )?;
+
+        Ok(())
+    }
+
+    struct InferredSchema {
+        schema: Vec<ColumnSchema>,
+        rows: u64,     // Rows read
+        sampled: bool, // Reading stopped early at `sample_size` rows
+        preview: Option<Preview>,
+    }
+
+    /// Infer the schema of a file. Rows not matching `-w` are read but don't
+    /// contribute to the types; `preview` keeps the first and last N rows,
+    /// and `type_counts` keeps the counts behind each type.
+    fn infer_schema(
+        cli: &Cli,
+        file_path: &str,
+        sample_size: Option<u64>,
+        preview: Option<usize>,
+        type_counts: bool,
+    ) -> Result<InferredSchema> {
+        let options = build_csv_options(cli)?;
+        let mut reader = CsvReader::from_path_with_options(file_path, options)?;
+        let (headers, renames) = read_headers(cli, &mut reader)?;
+
+        let target_cols = target_columns(cli, &headers, &renames)?
+            .unwrap_or_else(|| headers.iter().map(|s| s.to_string()).collect());
+        let mut inferrer = SchemaInferrer::new(
+            &target_cols,
+            &headers,
+            &build_type_overrides(cli, &headers, &renames)?,
+        )
+        .with_preserve_leading_zeros(cli.preserve_leading_zeros)
+        .with_sci_as_float(cli.sci_as_float)
+        .with_type_counts(type_counts);
+        let filter = build_filter(cli, &headers, &renames)?;
+        let mut preview = preview.map(|n| RowPreview::new(n, &headers));
+        let mut progress = ProgressTracker::new(file_path, cli.quiet);
+        let mut total_rows = 0u64;
+        let mut sampled = false;
+
+        for result in reader.records() {
+            let record = result?;
+            if sample_size.is_some_and(|n| total_rows >= n) {
+                sampled = true;
+                break;
+            }
+            total_rows += 1;
+            progress.update(&record);
+            if let Some(ref mut p) = preview {
+                p.add_record(&record);
+            }
+            if let Some(ref f) = filter
+                && !f.matches(&record, &headers)?
+            {
+                continue;
+            }
+            inferrer.add_record(&record)?;
+        }
+
+        progress.finish();
+        report_input(&reader, filter.as_ref());
+        Ok(InferredSchema {
+            schema: inferrer.finalize(),
+            rows: total_rows,
+            sampled,
+            preview: preview.as_ref().map(RowPreview::preview),
+        })
+    }
+
+    pub fn run_profile(cli: &Cli) -> Result<()> {
+        let file_path = cli
+            .file
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("FILE is required"))?;
+
+        let options = build_csv_options(cli)?;
+        let mut reader = CsvReader::from_path_with_options(file_path, options)?;
+        let (headers, renames) = read_headers(cli, &mut reader)?;
+
+        let target_cols = target_columns(cli, &headers, &renames)?
+            .unwrap_or_else(|| headers.iter().map(|s| s.to_string()).collect());
+        let filter = build_filter(cli, &headers, &renames)?;
+
+        let mut inferrer = SchemaInferrer::new(
+            &target_cols,
+            &headers,
+            &build_type_overrides(cli, &headers, &renames)?,
+        )
+        .with_preserve_leading_zeros(cli.preserve_leading_zeros)
+        .with_sci_as_float(cli.sci_as_float);
+        let mut collector = StatsCollector::new(
+            &target_cols,
+            &headers,
+            build_stats_options(cli, &headers, &renames)?,
+        );
         let mut total_rows = 0u64;
         let mut matched_rows = 0u64;
         let mut progress = ProgressTracker::new(file_path, cli.quiet);
@@ -91,11 +1109,8 @@ mod commands {
         for result in reader.records() {
             let record = result?;
             total_rows += 1;
-
-            // Update progress
             progress.update(&record);
 
-            // Apply filter
             if let Some(ref f) = filter
                 && !f.matches(&record, &headers)?
             {
@@ -103,57 +1118,281 @@ mod commands {
             }
 
             matched_rows += 1;
+            inferrer.add_record(&record)?;
             collector.add_record(&record, &headers)?;
         }
 
         progress.finish();
+        report_input(&reader, filter.as_ref());
 
-        let stats = collector.finalize();
-
-        // Render output
-        let format = cli.format.as_deref().unwrap_or("table");
-        let renderer = Renderer::new(OutputFormat::from_str(format)?)
-            .with_output(cli.output.clone())
-            .with_color(ColorMode::from_str(&cli.color));
-        renderer.render_summary(
+        let renderer = build_renderer(cli)?;
+        renderer.render_profile(
             file_path,
             total_rows,
             matched_rows,
-            cli.where_clause.as_deref(),
-            &stats,
+            filter_description(cli).as_deref(),
+            &inferrer.finalize(),
+            &collector.finalize(),
         )?;
 
         Ok(())
     }
 
-    pub fn run_schema(cli: &Cli) -> Result<()> {
+    /// Returns false when the files differ structurally
+    pub fn run_diff(cli: &Cli, args: &cli::DiffArgs) -> Result<bool> {
+        let file_path = cli
+            .file
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("FILE is required"))?;
+
+        let base = infer_schema(cli, &args.base, None, None, false)?;
+        let current = infer_schema(cli, file_path, None, None, false)?;
+
+        let diff = diff_schemas(
+            &SchemaSnapshot {
+                file: &args.base,
+                rows: base.rows,
+                schema: &base.schema,
+            },
+            &SchemaSnapshot {
+                file: file_path,
+                rows: current.rows,
+                schema: &current.schema,
+            },
+            args.data,
+        );
+
+        let renderer = build_renderer(cli)?;
+        renderer.render_diff(&diff)?;
+
+        Ok(!diff.has_structural_changes())
+    }
+
+    pub fn run_pivot(cli: &Cli, args: &cli::PivotArgs) -> Result<()> {
         let file_path = cli
             .file
             .as_ref()
             .ok_or_else(|| anyhow::anyhow!("FILE is required"))?;
 
-        let options = build_csv_options(cli);
+        let options = build_csv_options(cli)?;
         let mut reader = CsvReader::from_path_with_options(file_path, options)?;
-        let headers = reader.headers()?.clone();
+        let (headers, renames) = read_headers(cli, &mut reader)?;
+
+        // -c names the single column whose values become the pivot columns
+        let cols = cli
+            .cols
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("pivot requires -c/--cols <COLUMN>"))?;
+        let col_column = match select_columns(cols, &headers, &renames)?.as_slice() {
+            [col] => col.clone(),
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "pivot requires exactly one column in -c/--cols"
+                ));
+            }
+        };
+        let row_column = select_columns(&args.rows, &headers, &renames)?
+            .first()
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("--rows requires a column"))?;
+
+        let filter = build_filter(cli, &headers, &renames)?;
 
-        let mut inferrer = SchemaInferrer::new(&headers);
+        let mut builder = PivotBuilder::new(
+            &row_column,
+            &col_column,
+            args.value
+                .as_deref()
+                .map(|v| resolve_name(v, &headers, &renames)),
+            Aggregation::from_str(&args.agg)?,
+            &headers,
+        )?;
         let mut progress = ProgressTracker::new(file_path, cli.quiet);
 
         for result in reader.records() {
             let record = result?;
             progress.update(&record);
-            inferrer.add_record(&record)?;
+
+            if let Some(ref f) = filter
+                && !f.matches(&record, &headers)?
+            {
+                continue;
+            }
+
+            builder.add_record(&record);
         }
 
         progress.finish();
-        let schema = inferrer.finalize();
+        report_input(&reader, filter.as_ref());
+        let pivot = builder.finalize();
 
-        let format = cli.format.as_deref().unwrap_or("table");
-        let renderer = Renderer::new(OutputFormat::from_str(format)?)
-            .with_output(cli.output.clone())
-            .with_color(ColorMode::from_str(&cli.color));
-        renderer.render_schema(file_path, &schema)?;
+        let renderer = build_renderer(cli)?;
+        renderer.render_pivot(file_path, &pivot)?;
 
         Ok(())
     }
+
+    pub fn run_freq(cli: &Cli, args: &cli::FreqArgs) -> Result<()> {
+        let file_path = cli
+            .file
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("FILE is required"))?;
+
+        let options = build_csv_options(cli)?;
+        let mut reader = CsvReader::from_path_with_options(file_path, options)?;
+        let (headers, renames) = read_headers(cli, &mut reader)?;
+
+        let cols = cli
+            .cols
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("freq requires -c/--cols <COLUMN>"))?;
+        let column = match select_columns(cols, &headers, &renames)?.as_slice() {
+            [col] => col.clone(),
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "freq requires exactly one column in -c/--cols"
+                ));
+            }
+        };
+
+        let filter = build_filter(cli, &headers, &renames)?;
+        let mut builder = FreqBuilder::new(&column, args.bin_width, &headers)?;
+        let mut progress = ProgressTracker::new(file_path, cli.quiet);
+
+        for result in reader.records() {
+            let record = result?;
+            progress.update(&record);
+
+            if let Some(ref f) = filter
+                && !f.matches(&record, &headers)?
+            {
+                continue;
+            }
+
+            builder.add_record(&record);
+        }
+
+        progress.finish();
+        report_input(&reader, filter.as_ref());
+        let freq = builder.finalize()?;
+
+        let renderer = build_renderer(cli)?;
+        renderer.render_freq(file_path, &freq)?;
+
+        Ok(())
+    }
+
+    pub fn run_convert(cli: &Cli, args: &cli::ConvertArgs) -> Result<()> {
+        let file_path = cli
+            .file
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("FILE is required"))?;
+        let target = ConvertTarget::from_str(&args.to)?;
+
+        let options = build_csv_options(cli)?;
+        let mut reader = CsvReader::from_path_with_options(file_path, options)?;
+        let (headers, renames) = read_headers(cli, &mut reader)?;
+
+        let indices: Vec<usize> = match target_columns(cli, &headers, &renames)? {
+            Some(columns) => columns
+                .iter()
+                .filter_map(|c| headers.iter().position(|h| h == c))
+                .collect(),
+            None => (0..headers.len()).collect(),
+        };
+
+        // Typed targets need the column types before the first row is written.
+        // The schema covers the -c columns, in the same order as `indices`.
+        let types: Vec<DataType> = if target.is_typed() {
+            let inferred = infer_schema(cli, file_path, None, None, false)?;
+            inferred
+                .schema
+                .into_iter()
+                .map(|c| c.inferred_type)
+                .collect()
+        } else {
+            vec![DataType::String; indices.len()]
+        };
+        let columns: Vec<OutputColumn> = indices
+            .iter()
+            .zip(types)
+            .map(|(&i, data_type)| OutputColumn {
+                name: headers[i].to_string(),
+                data_type,
+            })
+            .collect();
+
+        let filter = build_filter(cli, &headers, &renames)?;
+
+        let mut output = open_output(cli.output.as_deref())?;
+        if let Some(ref enc) = args.to_encoding {
+            if target == ConvertTarget::Arrow {
+                return Err(anyhow::anyhow!(
+                    "--to-encoding applies to text targets, not arrow"
+                ));
+            }
+            output = Box::new(EncodingWriter::new(output, lookup_encoding(enc)?));
+        }
+        let mut writer = row_writer(target, output, &columns)?;
+        let mut progress = ProgressTracker::new(file_path, cli.quiet);
+
+        for result in reader.records() {
+            let record = result?;
+            progress.update(&record);
+
+            if let Some(ref f) = filter
+                && !f.matches(&record, &headers)?
+            {
+                continue;
+            }
+
+            let row: StringRecord = indices
+                .iter()
+                .map(|&i| record.get(i).unwrap_or(""))
+                .collect();
+            writer.write_record(&row)?;
+        }
+
+        progress.finish();
+        report_input(&reader, filter.as_ref());
+        writer.finish()
+    }
+
+    pub fn run_count(cli: &Cli, args: &cli::CountArgs) -> Result<()> {
+        let file_path = cli
+            .file
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("FILE is required"))?;
+
+        let options = build_csv_options(cli)?;
+        let mut reader = CsvReader::from_path_with_options(file_path, options)?;
+        let renderer = build_renderer(cli)?;
+
+        if args.fast_count && !args.strict && filter_description(cli).is_none() {
+            let rows = reader.fast_count()?;
+            report_input(&reader, None);
+            return renderer.render_count(file_path, rows);
+        }
+
+        let (headers, renames) = read_headers(cli, &mut reader)?;
+        let filter = build_filter(cli, &headers, &renames)?;
+
+        let mut rows = 0u64;
+        let mut progress = ProgressTracker::new(file_path, cli.quiet);
+        for result in reader.records() {
+            let record = result?;
+            progress.update(&record);
+
+            if let Some(ref f) = filter
+                && !f.matches(&record, &headers)?
+            {
+                continue;
+            }
+            rows += 1;
+        }
+
+        progress.finish();
+        report_input(&reader, filter.as_ref());
+        renderer.render_count(file_path, rows)
+    }
 }
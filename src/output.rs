@@ -1,10 +1,18 @@
 use anyhow::Result;
 use colored::Colorize;
 use comfy_table::{Cell, ContentArrangement, Table};
+use serde::Serialize;
 use std::fs::File;
 use std::io::{self, BufWriter, IsTerminal, Write};
+use unicode_segmentation::UnicodeSegmentation;
 
-use crate::types::{ColumnSchema, ColumnStats, DataType};
+use crate::error::find_similar_name;
+use crate::preview::Preview;
+use crate::stats::StatSelection;
+use crate::types::{
+    ColumnSchema, ColumnStats, DataType, EncodingReport, FreqTable, GroupStats, PivotTable,
+    SchemaDiff, StatDelta, Sum, SummaryTotals,
+};
 
 #[derive(Debug, Clone, Copy)]
 pub enum OutputFormat {
@@ -12,18 +20,33 @@ pub enum OutputFormat {
     Json,
     NdJson,
     Csv,
+    #[cfg(feature = "parquet")]
+    Parquet, // Written to the -o path only
 }
 
 impl OutputFormat {
+    const NAMES: [&str; 5] = ["table", "json", "ndjson", "csv", "parquet"];
+
     pub fn from_str(s: &str) -> Result<Self> {
         match s.to_lowercase().as_str() {
             "table" => Ok(OutputFormat::Table),
             "json" => Ok(OutputFormat::Json),
             "ndjson" => Ok(OutputFormat::NdJson),
             "csv" => Ok(OutputFormat::Csv),
-            _ => Err(anyhow::anyhow!(
-                "Unknown output format: {s}. Supported: table, json, ndjson, csv"
+            #[cfg(feature = "parquet")]
+            "parquet" => Ok(OutputFormat::Parquet),
+            #[cfg(not(feature = "parquet"))]
+            "parquet" => Err(anyhow::anyhow!(
+                "Parquet output requires csvpeek built with `--features parquet`"
             )),
+            _ => match find_similar_name(s, &Self::NAMES) {
+                Some(similar) => Err(anyhow::anyhow!(
+                    "Unknown output format: {s}. Did you mean \"{similar}\"?"
+                )),
+                None => Err(anyhow::anyhow!(
+                    "Unknown output format: {s}. Supported: table, json, ndjson, csv"
+                )),
+            },
         }
     }
 }
@@ -53,10 +76,351 @@ impl ColorMode {
     }
 }
 
+/// Palette for the type column of tables (--color-scheme)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColorScheme {
+    Default,    // Blue, green, yellow, magenta
+    Colorblind, // Blue, yellow, cyan, white: no red-green contrasts
+    Mono,       // Bold and underline instead of hue
+}
+
+impl ColorScheme {
+    pub fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "default" => Ok(ColorScheme::Default),
+            "colorblind" => Ok(ColorScheme::Colorblind),
+            "mono" => Ok(ColorScheme::Mono),
+            _ => Err(anyhow::anyhow!(
+                "Unknown color scheme: {s}. Supported: default, colorblind, mono"
+            )),
+        }
+    }
+}
+
+/// How top values are quoted in table output
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum QuoteMode {
+    Auto, // Only values that would be ambiguous
+    Always,
+    Never,
+}
+
+impl QuoteMode {
+    pub fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "auto" => Ok(QuoteMode::Auto),
+            "always" => Ok(QuoteMode::Always),
+            "never" => Ok(QuoteMode::Never),
+            _ => Err(anyhow::anyhow!(
+                "Unknown quote mode: {s}. Supported: auto, always, never"
+            )),
+        }
+    }
+
+    fn apply(&self, value: &str) -> String {
+        let needs_quotes = match self {
+            QuoteMode::Always => true,
+            QuoteMode::Never => false,
+            QuoteMode::Auto => {
+                value.is_empty()
+                    || value.trim() != value
+                    || value.contains([',', '(', ')', '"', '\\', '\n', '\r'])
+            }
+        };
+        if !needs_quotes {
+            return value.to_string();
+        }
+        let escaped = value
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('\n', "\\n")
+            .replace('\r', "\\r");
+        format!("\"{escaped}\"")
+    }
+}
+
+/// Alternative documents `schema --as` can write instead of the schema report
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SchemaDialect {
+    JsonSchema, // Draft 2020-12
+}
+
+impl SchemaDialect {
+    pub fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "json-schema" | "jsonschema" => Ok(SchemaDialect::JsonSchema),
+            _ => Err(anyhow::anyhow!(
+                "Unknown schema kind: {s}. Supported: json-schema"
+            )),
+        }
+    }
+}
+
+/// A JSON Schema for the rows `convert --to json` writes: one property per
+/// column, all required, nullable where the column has nulls
+#[derive(Serialize)]
+struct JsonSchema<'a> {
+    #[serde(rename = "$schema")]
+    dialect: &'static str,
+    title: &'a str,
+    #[serde(rename = "type")]
+    kind: &'static str,
+    properties: JsonSchemaProperties<'a>,
+    required: Vec<&'a str>,
+}
+
+/// Serialized as a map so properties keep the column order
+struct JsonSchemaProperties<'a>(&'a [ColumnSchema]);
+
+impl Serialize for JsonSchemaProperties<'_> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeMap;
+
+        let mut map = serializer.serialize_map(Some(self.0.len()))?;
+        for col in self.0 {
+            let kind = match col.inferred_type {
+                DataType::Integer => "integer",
+                DataType::Float => "number",
+                DataType::Boolean => "boolean",
+                DataType::String => "string",
+            };
+            let property = if col.null_count > 0 {
+                serde_json::json!({ "type": [kind, "null"] })
+            } else {
+                serde_json::json!({ "type": kind })
+            };
+            map.serialize_entry(&col.name, &property)?;
+        }
+        map.end()
+    }
+}
+
+/// Decimal and grouping separators for table numbers (--locale)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NumberLocale {
+    decimal: char,
+    group: char,
+}
+
+impl NumberLocale {
+    pub fn from_str(s: &str) -> Result<Self> {
+        let language = s.split(['-', '_']).next().unwrap_or("").to_lowercase();
+        let (group, decimal) = match language.as_str() {
+            "en" | "ja" | "zh" | "ko" | "he" | "th" => (',', '.'),
+            "de" | "es" | "it" | "nl" | "pt" | "da" | "id" | "tr" | "el" => ('.', ','),
+            "fr" | "ru" | "sv" | "pl" | "cs" | "fi" | "nb" | "uk" | "hu" => ('\u{a0}', ','),
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "Unknown locale: {s}. Examples: en-US, de-DE, fr-FR, ja-JP"
+                ));
+            }
+        };
+        Ok(Self { decimal, group })
+    }
+
+    /// Format `value` with `decimals` places, grouping the integer digits
+    pub fn format(&self, value: f64, decimals: usize) -> String {
+        self.localize(&format!("{value:.decimals$}"))
+    }
+
+    /// Re-punctuate a plain number like `-1234.5`; anything else (NaN,
+    /// exponents) is returned unchanged
+    pub fn localize(&self, plain: &str) -> String {
+        let (sign, digits) = match plain.strip_prefix('-') {
+            Some(rest) => ("-", rest),
+            None => ("", plain),
+        };
+        let (int_part, frac_part) = match digits.split_once('.') {
+            Some((i, f)) => (i, Some(f)),
+            None => (digits, None),
+        };
+        let is_digits = |p: &str| !p.is_empty() && p.bytes().all(|b| b.is_ascii_digit());
+        if !is_digits(int_part) || frac_part.is_some_and(|f| !is_digits(f)) {
+            return plain.to_string();
+        }
+
+        let mut grouped = String::new();
+        for (i, c) in int_part.chars().enumerate() {
+            if i > 0 && (int_part.len() - i) % 3 == 0 {
+                grouped.push(self.group);
+            }
+            grouped.push(c);
+        }
+        match frac_part {
+            Some(f) => format!("{sign}{grouped}{}{f}", self.decimal),
+            None => format!("{sign}{grouped}"),
+        }
+    }
+}
+
+/// How decimal statistics are printed (--locale, --round, --abbreviate)
+#[derive(Debug, Clone, Copy, Default)]
+struct NumberFormat {
+    locale: Option<NumberLocale>,
+    round: Option<usize>,
+    abbreviate: bool, // 1.23G rather than 1234567890, tables only
+}
+
+impl NumberFormat {
+    /// The same rounding without locale separators or suffixes, for CSV output
+    fn plain(self) -> Self {
+        Self {
+            locale: None,
+            abbreviate: false,
+            ..self
+        }
+    }
+
+    /// A count, abbreviated under --abbreviate
+    fn count(&self, n: u64) -> String {
+        self.abbreviated(n as f64).unwrap_or_else(|| n.to_string())
+    }
+
+    /// `value` with three significant digits and a K, M, G, T, P or E
+    /// suffix; None when abbreviating is off or |value| < 1000
+    fn abbreviated(&self, value: f64) -> Option<String> {
+        if !self.abbreviate || !value.is_finite() || value.abs() < 1000.0 {
+            return None;
+        }
+        let mut scaled = value;
+        for suffix in ['K', 'M', 'G', 'T', 'P', 'E'] {
+            scaled /= 1000.0;
+            // Thresholds are where rounding adds a digit: 999_999 would
+            // print as 1000K, which is 1.00M
+            let places = match scaled.abs() {
+                a if a >= 999.5 && suffix != 'E' => continue,
+                a if a < 9.995 => 2,
+                a if a < 99.95 => 1,
+                _ => 0,
+            };
+            let text = format!("{scaled:.places$}");
+            let text = match self.locale {
+                Some(ref l) => l.localize(&text),
+                None => text,
+            };
+            return Some(format!("{text}{suffix}"));
+        }
+        None
+    }
+
+    /// `default_places` applies unless --round is set. Under --round, whole
+    /// numbers in integer columns (sums, medians) print without decimals.
+    fn decimal(&self, value: f64, dtype: DataType, default_places: usize) -> String {
+        if let Some(short) = self.abbreviated(value) {
+            return short;
+        }
+        let places = match self.round {
+            Some(_) if dtype == DataType::Integer && value.fract() == 0.0 => 0,
+            Some(n) => n,
+            None => default_places,
+        };
+        match self.locale {
+            Some(ref l) => l.format(value, places),
+            None => format!("{value:.places$}"),
+        }
+    }
+}
+
+/// Round decimal statistics for JSON output under --round
+fn round_stats(stats: &[ColumnStats], places: usize) -> Vec<ColumnStats> {
+    let factor = 10f64.powi(places as i32);
+    let round = |v: Option<f64>| v.map(|v| (v * factor).round() / factor);
+    stats
+        .iter()
+        .map(|s| ColumnStats {
+            mean: round(s.mean),
+            weighted_mean: round(s.weighted_mean),
+            geo_mean: round(s.geo_mean),
+            harmonic_mean: round(s.harmonic_mean),
+            sum: s.sum.map(|sum| match sum {
+                Sum::Float(f) => Sum::Float(round(Some(f)).unwrap_or(f)),
+                exact => exact,
+            }),
+            std: round(s.std),
+            median: round(s.median),
+            p25: round(s.p25),
+            p75: round(s.p75),
+            percentiles: s
+                .percentiles
+                .iter()
+                .map(|(name, v)| (name.clone(), round(Some(*v)).unwrap_or(*v)))
+                .collect(),
+            delta: s.delta.as_ref().map(|d| StatDelta {
+                mean: round(d.mean),
+                median: round(d.median),
+                std: round(d.std),
+                ..d.clone()
+            }),
+            ..s.clone()
+        })
+        .collect()
+}
+
+/// ` (+3.10)`: the change from the --compare-to baseline, with its
+/// magnitude formatted by `format`; empty without a baseline
+fn delta_suffix(delta: Option<f64>, format: impl Fn(f64) -> String) -> String {
+    match delta {
+        Some(d) if d > 0.0 => format!(" (+{})", format(d)),
+        Some(d) if d < 0.0 => format!(" (-{})", format(-d)),
+        Some(d) => format!(" (±{})", format(d)),
+        None => String::new(),
+    }
+}
+
+/// Min/max of numeric columns follow the locale and --abbreviate too;
+/// strings are left alone
+fn format_extreme(stat: &ColumnStats, value: Option<&str>, numbers: &NumberFormat) -> String {
+    let numeric = matches!(stat.data_type, DataType::Integer | DataType::Float);
+    match (value, numbers.locale) {
+        (Some(v), _)
+            if numeric && let Some(short) = v.parse().ok().and_then(|n| numbers.abbreviated(n)) =>
+        {
+            short
+        }
+        (Some(v), Some(l)) if numeric => l.localize(v),
+        (Some(v), _) => v.to_string(),
+        (None, _) => "-".to_string(),
+    }
+}
+
+/// JSON shape of the profile subcommand
+#[derive(Serialize)]
+struct Profile<'a> {
+    file: &'a str,
+    rows: u64,
+    matched: u64,
+    schema: &'a [ColumnSchema],
+    summary: &'a [ColumnStats],
+}
+
 pub struct Renderer {
     format: OutputFormat,
     output_path: Option<String>,
     color_mode: ColorMode,
+    color_scheme: ColorScheme,
+    quote_mode: QuoteMode,
+    numbers: NumberFormat,
+    extra_columns: ExtraColumns,
+    preview: Option<Preview>,
+    width: Option<u16>,    // --width for tables
+    max_cell_width: usize, // --max-cell-width; 0 = no truncation
+    top_pct: bool,         // Show each top value's share in tables
+    selection: StatSelection,
+    totals: Option<SummaryTotals>, // Footer under the summary table
+}
+
+/// Longest value shown in a table cell or top-values list by default
+pub const DEFAULT_MAX_CELL_WIDTH: usize = 40;
+
+/// Table width when writing to a file or pipe, where there is no terminal
+/// width to adapt to
+const DEFAULT_TABLE_WIDTH: u16 = 120;
+
+/// Optional summary table columns
+#[derive(Debug, Clone, Copy, Default)]
+struct ExtraColumns {
+    outliers: bool,
+    decimals: bool,
 }
 
 impl Renderer {
@@ -65,6 +429,16 @@ impl Renderer {
             format,
             output_path: None,
             color_mode: ColorMode::Auto,
+            color_scheme: ColorScheme::Default,
+            quote_mode: QuoteMode::Auto,
+            numbers: NumberFormat::default(),
+            extra_columns: ExtraColumns::default(),
+            preview: None,
+            width: None,
+            max_cell_width: DEFAULT_MAX_CELL_WIDTH,
+            top_pct: false,
+            selection: StatSelection::default(),
+            totals: None,
         }
     }
 
@@ -78,11 +452,157 @@ impl Renderer {
         self
     }
 
+    pub fn with_color_scheme(mut self, scheme: ColorScheme) -> Self {
+        self.color_scheme = scheme;
+        self
+    }
+
+    pub fn with_quote_mode(mut self, mode: QuoteMode) -> Self {
+        self.quote_mode = mode;
+        self
+    }
+
+    /// Only affects table output; CSV/JSON stay machine-parseable
+    pub fn with_locale(mut self, locale: Option<NumberLocale>) -> Self {
+        self.numbers.locale = locale;
+        self
+    }
+
+    /// Decimal places for table, CSV and JSON statistics
+    pub fn with_round(mut self, places: Option<usize>) -> Self {
+        self.numbers.round = places;
+        self
+    }
+
+    /// Shorten numbers of 1000 and up to 1.23K, 45.6M, ... in tables
+    pub fn with_abbreviate(mut self, abbreviate: bool) -> Self {
+        self.numbers.abbreviate = abbreviate;
+        self
+    }
+
+    /// Add an outliers column to the summary table
+    pub fn with_outliers(mut self, show: bool) -> Self {
+        self.extra_columns.outliers = show;
+        self
+    }
+
+    /// Add a decimals column (max fractional digits) to the summary table
+    pub fn with_decimals(mut self, show: bool) -> Self {
+        self.extra_columns.decimals = show;
+        self
+    }
+
+    /// Raw rows shown above table output (--preview)
+    pub fn with_preview(mut self, preview: Option<Preview>) -> Self {
+        self.preview = preview;
+        self
+    }
+
+    /// Dataset-level totals shown under the summary table (--totals)
+    pub fn with_totals(mut self, totals: Option<SummaryTotals>) -> Self {
+        self.totals = totals;
+        self
+    }
+
+    pub fn with_width(mut self, width: Option<u16>) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Leave unselected statistics out of the summary table (--stats)
+    pub fn with_stat_selection(mut self, selection: StatSelection) -> Self {
+        self.selection = selection;
+        self
+    }
+
+    pub fn with_top_pct(mut self, top_pct: bool) -> Self {
+        self.top_pct = top_pct;
+        self
+    }
+
+    pub fn with_max_cell_width(mut self, max_cell_width: usize) -> Self {
+        self.max_cell_width = max_cell_width;
+        self
+    }
+
+    #[cfg(feature = "parquet")]
+    fn parquet_path(&self) -> Result<&str> {
+        self.output_path
+            .as_deref()
+            .ok_or_else(|| anyhow::anyhow!("Parquet output needs a file: add -o/--output <FILE>"))
+    }
+
+    /// Head and tail rows, with a `...` row standing for the rows between
+    fn write_preview(&self, w: &mut dyn Write, use_color: bool) -> Result<()> {
+        let Some(ref preview) = self.preview else {
+            return Ok(());
+        };
+
+        let mut table = Table::new();
+        table.set_content_arrangement(ContentArrangement::Dynamic);
+        table.set_header(preview.headers.iter().map(Cell::new));
+        let cells = |record: &csv::StringRecord| -> Vec<Cell> {
+            record
+                .iter()
+                .map(|v| Cell::new(truncate_cell(v, self.max_cell_width)))
+                .collect()
+        };
+        for record in &preview.head {
+            table.add_row(cells(record));
+        }
+        if preview.skipped > 0 {
+            table.add_row(preview.headers.iter().map(|_| Cell::new("...")));
+        }
+        for record in &preview.tail {
+            table.add_row(cells(record));
+        }
+
+        let label = if preview.skipped > 0 {
+            format!(
+                "Preview (first {}, last {}, {} rows between):",
+                preview.head.len(),
+                preview.tail.len(),
+                preview.skipped
+            )
+        } else {
+            "Preview:".to_string()
+        };
+        if use_color {
+            writeln!(w, "{}", label.cyan())?;
+        } else {
+            writeln!(w, "{label}")?;
+        }
+        writeln!(w, "{}", self.sized(table))?;
+        writeln!(w)?;
+        Ok(())
+    }
+
+    /// Fix a table's width: --width, or DEFAULT_TABLE_WIDTH when not writing
+    /// to a terminal. On a terminal it follows the terminal's width.
+    fn sized(&self, mut table: Table) -> Table {
+        let is_tty = self.output_path.is_none() && io::stdout().is_terminal();
+        match self.width {
+            Some(width) => {
+                table.set_width(width);
+            }
+            None if !is_tty => {
+                table.set_width(DEFAULT_TABLE_WIDTH);
+            }
+            None => {}
+        }
+        table
+    }
+
     fn use_color(&self) -> bool {
         let is_tty = self.output_path.is_none() && io::stdout().is_terminal();
         self.color_mode.should_colorize(is_tty)
     }
 
+    /// Palette for the type column, None without color
+    fn type_colors(&self) -> Option<ColorScheme> {
+        self.use_color().then_some(self.color_scheme)
+    }
+
     fn get_writer(&self) -> Result<Box<dyn Write>> {
         match &self.output_path {
             Some(path) => {
@@ -101,6 +621,15 @@ impl Renderer {
         filter: Option<&str>,
         stats: &[ColumnStats],
     ) -> Result<()> {
+        let rounded;
+        let stats = match self.numbers.round {
+            Some(places) if matches!(self.format, OutputFormat::Json | OutputFormat::NdJson) => {
+                rounded = round_stats(stats, places);
+                &rounded
+            }
+            _ => stats,
+        };
+
         match self.format {
             OutputFormat::Table => {
                 self.render_summary_table(file, total_rows, matched_rows, filter, stats)
@@ -108,6 +637,10 @@ impl Renderer {
             OutputFormat::Json => self.render_summary_json(stats),
             OutputFormat::NdJson => self.render_summary_ndjson(stats),
             OutputFormat::Csv => self.render_summary_csv(stats),
+            #[cfg(feature = "parquet")]
+            OutputFormat::Parquet => {
+                crate::parquet_output::write_summary(self.parquet_path()?, stats)
+            }
         }
     }
 
@@ -122,91 +655,35 @@ impl Renderer {
         let use_color = self.use_color();
         let mut w = self.get_writer()?;
 
-        // Header info with optional color
-        if use_color {
-            writeln!(w, "{} {}", "file:".cyan(), file)?;
-            writeln!(
-                w,
-                "{} {} ({} {})",
-                "rows:".cyan(),
-                total_rows,
-                "matched:".cyan(),
-                matched_rows
-            )?;
-            if let Some(f) = filter {
-                writeln!(w, "{} {}", "filter:".cyan(), f)?;
-            }
-        } else {
-            writeln!(w, "file: {file}")?;
-            writeln!(w, "rows: {total_rows} (matched: {matched_rows})")?;
-            if let Some(f) = filter {
-                writeln!(w, "filter: {f}")?;
-            }
-        }
+        write_summary_header(&mut w, use_color, file, total_rows, matched_rows, filter)?;
         writeln!(w)?;
+        self.write_preview(&mut w, use_color)?;
 
-        let mut table = Table::new();
-        table.set_content_arrangement(ContentArrangement::Dynamic);
-        table.set_header(vec![
-            Cell::new("column"),
-            Cell::new("type"),
-            Cell::new("count"),
-            Cell::new("null%"),
-            Cell::new("unique"),
-            Cell::new("min"),
-            Cell::new("max"),
-            Cell::new("mean"),
-            Cell::new("median"),
-            Cell::new("std"),
-        ]);
-
-        for stat in stats {
-            let type_str = if use_color {
-                colorize_type(stat.data_type)
-            } else {
-                stat.data_type.to_string()
-            };
-            table.add_row(vec![
-                Cell::new(&stat.name),
-                Cell::new(type_str),
-                Cell::new(stat.count.to_string()),
-                Cell::new(format!("{:.1}%", stat.null_rate)),
-                Cell::new(stat.unique_count.map_or("-".to_string(), |v| v.to_string())),
-                Cell::new(stat.min.as_deref().unwrap_or("-")),
-                Cell::new(stat.max.as_deref().unwrap_or("-")),
-                Cell::new(
-                    stat.mean
-                        .map(|m| format!("{m:.2}"))
-                        .unwrap_or_else(|| "-".to_string()),
-                ),
-                Cell::new(
-                    stat.median
-                        .map(|m| format!("{m:.2}"))
-                        .unwrap_or_else(|| "-".to_string()),
-                ),
-                Cell::new(
-                    stat.std
-                        .map(|s| format!("{s:.2}"))
-                        .unwrap_or_else(|| "-".to_string()),
-                ),
-            ]);
-        }
-
-        writeln!(w, "{table}")?;
-
-        // Show top values for string columns
-        let has_top_values = stats.iter().any(|s| s.top_values.is_some());
-        if has_top_values {
-            writeln!(w)?;
-            writeln!(w, "Top values:")?;
-            for stat in stats {
-                if let Some(ref top) = stat.top_values {
-                    let top_str: Vec<String> =
-                        top.iter().map(|(v, c)| format!("{v}({c})")).collect();
-                    writeln!(w, "  {}: {}", stat.name, top_str.join(", "))?;
-                }
-            }
+        writeln!(
+            w,
+            "{}",
+            self.sized(summary_table(
+                stats,
+                self.type_colors(),
+                &self.numbers,
+                self.extra_columns,
+                &self.selection,
+                self.max_cell_width
+            ))
+        )?;
+        if let Some(ref totals) = self.totals {
+            write_totals(&mut w, totals, use_color)?;
         }
+        write_booleans(&mut w, stats, "")?;
+        write_top_values(
+            &mut w,
+            stats,
+            "",
+            self.quote_mode,
+            self.max_cell_width,
+            self.top_pct,
+        )?;
+        write_warnings(&mut w, stats, "", use_color)?;
 
         Ok(())
     }
@@ -229,91 +706,263 @@ impl Renderer {
 
     fn render_summary_csv(&self, stats: &[ColumnStats]) -> Result<()> {
         let mut w = self.get_writer()?;
-        writeln!(
-            w,
-            "column,type,count,null_count,null_rate,unique_count,min,max,mean,median,p25,p75,sum,std,min_len,max_len"
-        )?;
+        writeln!(w, "{SUMMARY_CSV_HEADER}")?;
         for stat in stats {
-            writeln!(
-                w,
-                "{},{},{},{},{:.2},{},{},{},{},{},{},{},{},{},{},{}",
-                escape_csv(&stat.name),
-                stat.data_type,
-                stat.count,
-                stat.null_count,
-                stat.null_rate,
-                stat.unique_count.map_or(String::new(), |v| v.to_string()),
-                stat.min.as_deref().map_or(String::new(), escape_csv),
-                stat.max.as_deref().map_or(String::new(), escape_csv),
-                stat.mean.map_or(String::new(), |v| format!("{v:.6}")),
-                stat.median.map_or(String::new(), |v| format!("{v:.6}")),
-                stat.p25.map_or(String::new(), |v| format!("{v:.6}")),
-                stat.p75.map_or(String::new(), |v| format!("{v:.6}")),
-                stat.sum.map_or(String::new(), |v| format!("{v:.6}")),
-                stat.std.map_or(String::new(), |v| format!("{v:.6}")),
-                stat.min_len.map_or(String::new(), |v| v.to_string()),
-                stat.max_len.map_or(String::new(), |v| v.to_string()),
-            )?;
+            writeln!(w, "{}", summary_csv_row(stat, self.numbers.plain()))?;
         }
         Ok(())
     }
 
-    pub fn render_schema(&self, file: &str, schema: &[ColumnSchema]) -> Result<()> {
-        match self.format {
-            OutputFormat::Table => self.render_schema_table(file, schema),
-            OutputFormat::Json => self.render_schema_json(schema),
-            OutputFormat::NdJson => self.render_schema_ndjson(schema),
-            OutputFormat::Csv => self.render_schema_csv(schema),
+    /// One `name<TAB>type<TAB>null_rate<TAB>unique` line per column, no
+    /// header (--quickstats)
+    pub fn render_quickstats(&self, stats: &[ColumnStats]) -> Result<()> {
+        let mut w = self.get_writer()?;
+        for stat in stats {
+            writeln!(w, "{}", quickstats_line(stat))?;
         }
+        Ok(())
     }
 
-    fn render_schema_table(&self, file: &str, schema: &[ColumnSchema]) -> Result<()> {
-        let use_color = self.use_color();
+    /// --quickstats with --group-by: the group comes first on each line
+    pub fn render_grouped_quickstats(&self, groups: &[GroupStats]) -> Result<()> {
         let mut w = self.get_writer()?;
-
-        if use_color {
-            writeln!(w, "{} {}", "file:".cyan(), file)?;
-            writeln!(w, "{} {}", "columns:".cyan(), schema.len())?;
-        } else {
-            writeln!(w, "file: {file}")?;
-            writeln!(w, "columns: {}", schema.len())?;
-        }
-        writeln!(w)?;
-
-        let mut table = Table::new();
-        table.set_content_arrangement(ContentArrangement::Dynamic);
-        table.set_header(vec![
-            Cell::new("column"),
-            Cell::new("type"),
-            Cell::new("null%"),
-            Cell::new("samples"),
-        ]);
-
-        for col in schema {
-            let samples = if col.sample_values.is_empty() {
-                "-".to_string()
-            } else {
-                col.sample_values.join(", ")
-            };
-            let type_str = if use_color {
-                colorize_type(col.inferred_type)
-            } else {
-                col.inferred_type.to_string()
-            };
-            table.add_row(vec![
-                Cell::new(&col.name),
-                Cell::new(type_str),
-                Cell::new(format!("{:.1}%", col.null_rate)),
-                Cell::new(samples),
-            ]);
+        for group in groups {
+            for stat in &group.stats {
+                writeln!(w, "{}\t{}", escape_tsv(&group.group), quickstats_line(stat))?;
+            }
         }
-
-        writeln!(w, "{table}")?;
         Ok(())
     }
 
-    fn render_schema_json(&self, schema: &[ColumnSchema]) -> Result<()> {
-        let mut w = self.get_writer()?;
+    pub fn render_grouped_summary(
+        &self,
+        file: &str,
+        total_rows: u64,
+        matched_rows: u64,
+        filter: Option<&str>,
+        group_column: &str,
+        groups: &[GroupStats],
+    ) -> Result<()> {
+        let rounded: Vec<GroupStats>;
+        let groups = match self.numbers.round {
+            Some(places) if matches!(self.format, OutputFormat::Json | OutputFormat::NdJson) => {
+                rounded = groups
+                    .iter()
+                    .map(|g| GroupStats {
+                        stats: round_stats(&g.stats, places),
+                        ..g.clone()
+                    })
+                    .collect();
+                &rounded
+            }
+            _ => groups,
+        };
+
+        match self.format {
+            #[cfg(feature = "parquet")]
+            OutputFormat::Parquet => Err(anyhow::anyhow!(
+                "Parquet output is not supported for grouped summaries"
+            )),
+            OutputFormat::Table => self.render_grouped_summary_table(
+                file,
+                total_rows,
+                matched_rows,
+                filter,
+                group_column,
+                groups,
+            ),
+            OutputFormat::Json => {
+                let mut w = self.get_writer()?;
+                writeln!(w, "{}", serde_json::to_string_pretty(groups)?)?;
+                Ok(())
+            }
+            OutputFormat::NdJson => {
+                let mut w = self.get_writer()?;
+                for group in groups {
+                    writeln!(w, "{}", serde_json::to_string(group)?)?;
+                }
+                Ok(())
+            }
+            OutputFormat::Csv => {
+                let mut w = self.get_writer()?;
+                writeln!(w, "group,{SUMMARY_CSV_HEADER}")?;
+                for group in groups {
+                    for stat in &group.stats {
+                        writeln!(
+                            w,
+                            "{},{}",
+                            escape_csv(&group.group),
+                            summary_csv_row(stat, self.numbers.plain())
+                        )?;
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn render_grouped_summary_table(
+        &self,
+        file: &str,
+        total_rows: u64,
+        matched_rows: u64,
+        filter: Option<&str>,
+        group_column: &str,
+        groups: &[GroupStats],
+    ) -> Result<()> {
+        let use_color = self.use_color();
+        let mut w = self.get_writer()?;
+
+        write_summary_header(&mut w, use_color, file, total_rows, matched_rows, filter)?;
+        if use_color {
+            writeln!(
+                w,
+                "{} {} ({})",
+                "group by:".cyan(),
+                group_column,
+                groups.len()
+            )?;
+        } else {
+            writeln!(w, "group by: {group_column} ({})", groups.len())?;
+        }
+        if self.preview.is_some() {
+            writeln!(w)?;
+            self.write_preview(&mut w, use_color)?;
+        }
+
+        for group in groups {
+            writeln!(w)?;
+            if use_color {
+                writeln!(
+                    w,
+                    "{} {} = {} ({} {})",
+                    "group:".cyan(),
+                    group_column,
+                    group.group,
+                    "rows:".cyan(),
+                    group.rows
+                )?;
+            } else {
+                writeln!(
+                    w,
+                    "group: {group_column} = {} (rows: {})",
+                    group.group, group.rows
+                )?;
+            }
+            writeln!(
+                w,
+                "{}",
+                self.sized(summary_table(
+                    &group.stats,
+                    self.type_colors(),
+                    &self.numbers,
+                    self.extra_columns,
+                    &self.selection,
+                    self.max_cell_width
+                ))
+            )?;
+            write_booleans(&mut w, &group.stats, "  ")?;
+            write_top_values(
+                &mut w,
+                &group.stats,
+                "  ",
+                self.quote_mode,
+                self.max_cell_width,
+                self.top_pct,
+            )?;
+            write_warnings(&mut w, &group.stats, "  ", use_color)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn render_schema(
+        &self,
+        file: &str,
+        schema: &[ColumnSchema],
+        sampled_rows: Option<u64>,
+        filter: Option<&str>,
+    ) -> Result<()> {
+        match self.format {
+            OutputFormat::Table => self.render_schema_table(file, schema, sampled_rows, filter),
+            OutputFormat::Json => self.render_schema_json(schema),
+            OutputFormat::NdJson => self.render_schema_ndjson(schema),
+            OutputFormat::Csv => self.render_schema_csv(schema),
+            #[cfg(feature = "parquet")]
+            OutputFormat::Parquet => {
+                crate::parquet_output::write_schema(self.parquet_path()?, schema)
+            }
+        }
+    }
+
+    /// Write the schema as another kind of document (--as), whatever -f says
+    pub fn render_schema_as(
+        &self,
+        dialect: SchemaDialect,
+        file: &str,
+        schema: &[ColumnSchema],
+    ) -> Result<()> {
+        let mut w = self.get_writer()?;
+        let json = match dialect {
+            SchemaDialect::JsonSchema => serde_json::to_string_pretty(&JsonSchema {
+                dialect: "https://json-schema.org/draft/2020-12/schema",
+                title: file,
+                kind: "object",
+                properties: JsonSchemaProperties(schema),
+                required: schema.iter().map(|c| c.name.as_str()).collect(),
+            })?,
+        };
+        writeln!(w, "{json}")?;
+        Ok(())
+    }
+
+    fn render_schema_table(
+        &self,
+        file: &str,
+        schema: &[ColumnSchema],
+        sampled_rows: Option<u64>,
+        filter: Option<&str>,
+    ) -> Result<()> {
+        let use_color = self.use_color();
+        let mut w = self.get_writer()?;
+
+        if use_color {
+            writeln!(w, "{} {}", "file:".cyan(), file)?;
+            writeln!(w, "{} {}", "columns:".cyan(), schema.len())?;
+            if let Some(n) = sampled_rows {
+                writeln!(w, "{} first {} rows", "sampled:".cyan(), n)?;
+            }
+            if let Some(f) = filter {
+                writeln!(w, "{} {}", "filter:".cyan(), f)?;
+            }
+        } else {
+            writeln!(w, "file: {file}")?;
+            writeln!(w, "columns: {}", schema.len())?;
+            if let Some(n) = sampled_rows {
+                writeln!(w, "sampled: first {n} rows")?;
+            }
+            if let Some(f) = filter {
+                writeln!(w, "filter: {f}")?;
+            }
+        }
+        writeln!(w)?;
+        self.write_preview(&mut w, use_color)?;
+
+        writeln!(
+            w,
+            "{}",
+            self.sized(schema_table(
+                schema,
+                self.type_colors(),
+                self.max_cell_width
+            ))
+        )?;
+        write_leading_zero_notes(&mut w, schema)?;
+        Ok(())
+    }
+
+    fn render_schema_json(&self, schema: &[ColumnSchema]) -> Result<()> {
+        let mut w = self.get_writer()?;
         let json = serde_json::to_string_pretty(schema)?;
         writeln!(w, "{json}")?;
         Ok(())
@@ -332,23 +981,1026 @@ impl Renderer {
         let mut w = self.get_writer()?;
         writeln!(
             w,
-            "column,type,null_count,total_count,null_rate,sample_values"
+            "column,type,null_count,total_count,null_rate,sample_values,leading_zeros,empty_count,null_literal_count"
         )?;
         for col in schema {
             let samples = col.sample_values.join("; ");
             writeln!(
                 w,
-                "{},{},{},{},{:.2},{}",
+                "{},{},{},{},{:.2},{},{},{},{}",
                 escape_csv(&col.name),
                 col.inferred_type,
                 col.null_count,
                 col.total_count,
                 col.null_rate,
                 escape_csv(&samples),
+                col.leading_zeros,
+                col.empty_count,
+                col.null_literal_count,
             )?;
         }
         Ok(())
     }
+
+    /// Schema and statistics from one pass (profile subcommand)
+    pub fn render_profile(
+        &self,
+        file: &str,
+        total_rows: u64,
+        matched_rows: u64,
+        filter: Option<&str>,
+        schema: &[ColumnSchema],
+        stats: &[ColumnStats],
+    ) -> Result<()> {
+        let rounded;
+        let stats = match self.numbers.round {
+            Some(places) if matches!(self.format, OutputFormat::Json | OutputFormat::NdJson) => {
+                rounded = round_stats(stats, places);
+                &rounded
+            }
+            _ => stats,
+        };
+        let profile = Profile {
+            file,
+            rows: total_rows,
+            matched: matched_rows,
+            schema,
+            summary: stats,
+        };
+
+        match self.format {
+            OutputFormat::Table => {
+                let use_color = self.use_color();
+                let mut w = self.get_writer()?;
+                let heading = |s: &str| {
+                    if use_color {
+                        s.cyan().bold().to_string()
+                    } else {
+                        s.to_string()
+                    }
+                };
+
+                write_summary_header(&mut w, use_color, file, total_rows, matched_rows, filter)?;
+                writeln!(w)?;
+                writeln!(w, "{}", heading("Schema:"))?;
+                writeln!(
+                    w,
+                    "{}",
+                    self.sized(schema_table(
+                        schema,
+                        self.type_colors(),
+                        self.max_cell_width
+                    ))
+                )?;
+                write_leading_zero_notes(&mut w, schema)?;
+                writeln!(w)?;
+                writeln!(w, "{}", heading("Statistics:"))?;
+                writeln!(
+                    w,
+                    "{}",
+                    self.sized(summary_table(
+                        stats,
+                        self.type_colors(),
+                        &self.numbers,
+                        self.extra_columns,
+                        &self.selection,
+                        self.max_cell_width
+                    ))
+                )?;
+                write_booleans(&mut w, stats, "")?;
+                write_top_values(
+                    &mut w,
+                    stats,
+                    "",
+                    self.quote_mode,
+                    self.max_cell_width,
+                    self.top_pct,
+                )?;
+                write_warnings(&mut w, stats, "", use_color)?;
+                Ok(())
+            }
+            OutputFormat::Json => {
+                let mut w = self.get_writer()?;
+                writeln!(w, "{}", serde_json::to_string_pretty(&profile)?)?;
+                Ok(())
+            }
+            OutputFormat::NdJson => {
+                let mut w = self.get_writer()?;
+                writeln!(w, "{}", serde_json::to_string(&profile)?)?;
+                Ok(())
+            }
+            OutputFormat::Csv => Err(anyhow::anyhow!(
+                "CSV output is not supported for profile; use schema or summary with -f csv"
+            )),
+            #[cfg(feature = "parquet")]
+            OutputFormat::Parquet => Err(anyhow::anyhow!(
+                "Parquet output is not supported for profile"
+            )),
+        }
+    }
+
+    pub fn render_count(&self, file: &str, rows: u64) -> Result<()> {
+        let mut w = self.get_writer()?;
+        match self.format {
+            OutputFormat::Table => writeln!(w, "{rows}")?,
+            OutputFormat::Json => writeln!(
+                w,
+                "{}",
+                serde_json::to_string_pretty(&serde_json::json!({"file": file, "rows": rows}))?
+            )?,
+            OutputFormat::NdJson => {
+                writeln!(w, "{}", serde_json::json!({"file": file, "rows": rows}))?
+            }
+            OutputFormat::Csv => {
+                writeln!(w, "file,rows")?;
+                writeln!(w, "{},{rows}", escape_csv(file))?;
+            }
+            #[cfg(feature = "parquet")]
+            OutputFormat::Parquet => {
+                return Err(anyhow::anyhow!("Parquet output is not supported for count"));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn render_pivot(&self, file: &str, pivot: &PivotTable) -> Result<()> {
+        match self.format {
+            #[cfg(feature = "parquet")]
+            OutputFormat::Parquet => {
+                Err(anyhow::anyhow!("Parquet output is not supported for pivot"))
+            }
+            OutputFormat::Table => self.render_pivot_table(file, pivot),
+            OutputFormat::Json => {
+                let mut w = self.get_writer()?;
+                writeln!(w, "{}", serde_json::to_string_pretty(pivot)?)?;
+                Ok(())
+            }
+            OutputFormat::NdJson => {
+                let mut w = self.get_writer()?;
+                for row in &pivot.rows {
+                    writeln!(w, "{}", serde_json::to_string(row)?)?;
+                }
+                Ok(())
+            }
+            OutputFormat::Csv => {
+                let mut w = self.get_writer()?;
+                let mut header = vec![escape_csv(&pivot.row_column)];
+                header.extend(pivot.columns.iter().map(|c| escape_csv(c)));
+                writeln!(w, "{}", header.join(","))?;
+                for row in &pivot.rows {
+                    let mut fields = vec![escape_csv(&row.key)];
+                    fields.extend(
+                        row.values
+                            .iter()
+                            .map(|v| v.map_or(String::new(), |v| v.to_string())),
+                    );
+                    writeln!(w, "{}", fields.join(","))?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn render_pivot_table(&self, file: &str, pivot: &PivotTable) -> Result<()> {
+        let use_color = self.use_color();
+        let mut w = self.get_writer()?;
+
+        let value_desc = match pivot.value_column {
+            Some(ref v) => format!("{}({v})", pivot.agg),
+            None => pivot.agg.clone(),
+        };
+        if use_color {
+            writeln!(w, "{} {}", "file:".cyan(), file)?;
+            writeln!(
+                w,
+                "{} {} x {} ({} {})",
+                "pivot:".cyan(),
+                pivot.row_column,
+                pivot.column_column,
+                "value:".cyan(),
+                value_desc
+            )?;
+        } else {
+            writeln!(w, "file: {file}")?;
+            writeln!(
+                w,
+                "pivot: {} x {} (value: {value_desc})",
+                pivot.row_column, pivot.column_column
+            )?;
+        }
+        writeln!(w)?;
+
+        let is_count = pivot.agg == "count";
+        let mut table = Table::new();
+        table.set_content_arrangement(ContentArrangement::Dynamic);
+        let mut header = vec![Cell::new(&pivot.row_column)];
+        header.extend(pivot.columns.iter().map(Cell::new));
+        table.set_header(header);
+
+        for row in &pivot.rows {
+            let mut cells = vec![Cell::new(&row.key)];
+            cells.extend(row.values.iter().map(|v| match v {
+                Some(v) if is_count => Cell::new(format!("{v}")),
+                Some(v) => Cell::new(self.numbers.decimal(*v, DataType::Float, 2)),
+                None => Cell::new("-"),
+            }));
+            table.add_row(cells);
+        }
+
+        writeln!(w, "{}", self.sized(table))?;
+        Ok(())
+    }
+
+    pub fn render_freq(&self, file: &str, freq: &FreqTable) -> Result<()> {
+        match self.format {
+            #[cfg(feature = "parquet")]
+            OutputFormat::Parquet => {
+                Err(anyhow::anyhow!("Parquet output is not supported for freq"))
+            }
+            OutputFormat::Table => self.render_freq_table(file, freq),
+            OutputFormat::Json => {
+                let mut w = self.get_writer()?;
+                writeln!(w, "{}", serde_json::to_string_pretty(freq)?)?;
+                Ok(())
+            }
+            OutputFormat::NdJson => {
+                let mut w = self.get_writer()?;
+                for row in &freq.values {
+                    writeln!(w, "{}", serde_json::to_string(row)?)?;
+                }
+                Ok(())
+            }
+            OutputFormat::Csv => {
+                let mut w = self.get_writer()?;
+                writeln!(w, "{},count,percent", escape_csv(&freq.column))?;
+                for row in &freq.values {
+                    writeln!(
+                        w,
+                        "{},{},{:.2}",
+                        escape_csv(row.value.as_deref().unwrap_or("")),
+                        row.count,
+                        row.percent
+                    )?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn render_freq_table(&self, file: &str, freq: &FreqTable) -> Result<()> {
+        let use_color = self.use_color();
+        let mut w = self.get_writer()?;
+        let label = |s: &str| {
+            if use_color {
+                s.cyan().to_string()
+            } else {
+                s.to_string()
+            }
+        };
+
+        writeln!(w, "{} {file}", label("file:"))?;
+        match freq.bin_width {
+            Some(width) => writeln!(
+                w,
+                "{} {} ({} {width})",
+                label("freq:"),
+                freq.column,
+                label("bin width:")
+            )?,
+            None => writeln!(w, "{} {}", label("freq:"), freq.column)?,
+        }
+        writeln!(w)?;
+
+        let mut table = Table::new();
+        table.set_content_arrangement(ContentArrangement::Dynamic);
+        table.set_header(vec![
+            Cell::new(&freq.column),
+            Cell::new("count"),
+            Cell::new("%"),
+        ]);
+        for row in &freq.values {
+            table.add_row(vec![
+                Cell::new(match row.value {
+                    // A value that reads (null) is quoted, to tell it from the nulls
+                    Some(ref v) if v == NULL_LABEL => QuoteMode::Always.apply(v),
+                    Some(ref v) => truncate_cell(v, self.max_cell_width),
+                    None => NULL_LABEL.to_string(),
+                }),
+                Cell::new(row.count),
+                Cell::new(format!("{:.1}%", row.percent)),
+            ]);
+        }
+
+        writeln!(w, "{}", self.sized(table))?;
+        Ok(())
+    }
+
+    pub fn render_encoding_report(&self, report: &EncodingReport) -> Result<()> {
+        let mut w = self.get_writer()?;
+        match self.format {
+            #[cfg(feature = "parquet")]
+            OutputFormat::Parquet => {
+                return Err(anyhow::anyhow!(
+                    "Parquet output is not supported for --encoding-report"
+                ));
+            }
+            OutputFormat::Table => {
+                let use_color = self.use_color();
+                let label = |s: &str| {
+                    if use_color {
+                        s.cyan().to_string()
+                    } else {
+                        s.to_string()
+                    }
+                };
+                writeln!(w, "{} {}", label("file:"), report.file)?;
+                if let Some(ref bom) = report.bom {
+                    writeln!(w, "{} {bom}", label("BOM:"))?;
+                }
+                let confidence = if report.confident {
+                    "confident"
+                } else {
+                    "low confidence"
+                };
+                writeln!(
+                    w,
+                    "{} {} ({confidence})",
+                    label("detected:"),
+                    report.detected
+                )?;
+                writeln!(w)?;
+
+                let mut table = Table::new();
+                table.set_content_arrangement(ContentArrangement::Dynamic);
+                table.set_header(vec![Cell::new("encoding"), Cell::new("errors")]);
+                for candidate in &report.candidates {
+                    let name = if candidate.encoding == report.detected {
+                        format!("{} *", candidate.encoding)
+                    } else {
+                        candidate.encoding.clone()
+                    };
+                    table.add_row(vec![Cell::new(name), Cell::new(candidate.errors)]);
+                }
+                writeln!(w, "{}", self.sized(table))?;
+                writeln!(w)?;
+                writeln!(
+                    w,
+                    "* detected. errors are byte sequences that are invalid in that encoding;"
+                )?;
+                writeln!(
+                    w,
+                    "  single-byte encodings (windows-1252, ...) accept almost any bytes."
+                )?;
+            }
+            OutputFormat::Json => writeln!(w, "{}", serde_json::to_string_pretty(report)?)?,
+            OutputFormat::NdJson => writeln!(w, "{}", serde_json::to_string(report)?)?,
+            OutputFormat::Csv => {
+                writeln!(w, "encoding,errors,detected")?;
+                for candidate in &report.candidates {
+                    writeln!(
+                        w,
+                        "{},{},{}",
+                        candidate.encoding,
+                        candidate.errors,
+                        candidate.encoding == report.detected
+                    )?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn render_diff(&self, diff: &SchemaDiff) -> Result<()> {
+        match self.format {
+            #[cfg(feature = "parquet")]
+            OutputFormat::Parquet => {
+                Err(anyhow::anyhow!("Parquet output is not supported for diff"))
+            }
+            OutputFormat::Table => self.render_diff_text(diff),
+            OutputFormat::Json => {
+                let mut w = self.get_writer()?;
+                writeln!(w, "{}", serde_json::to_string_pretty(diff)?)?;
+                Ok(())
+            }
+            OutputFormat::NdJson => {
+                let mut w = self.get_writer()?;
+                writeln!(w, "{}", serde_json::to_string(diff)?)?;
+                Ok(())
+            }
+            OutputFormat::Csv => self.render_diff_csv(diff),
+        }
+    }
+
+    fn render_diff_text(&self, diff: &SchemaDiff) -> Result<()> {
+        let use_color = self.use_color();
+        let mut w = self.get_writer()?;
+        let label = |s: &str| {
+            if use_color {
+                s.cyan().to_string()
+            } else {
+                s.to_string()
+            }
+        };
+
+        writeln!(
+            w,
+            "{} {} ({} rows)",
+            label("base:"),
+            diff.base_file,
+            diff.base_rows
+        )?;
+        writeln!(w, "{} {} ({} rows)", label("file:"), diff.file, diff.rows)?;
+        writeln!(w, "{} {:+}", label("rows:"), diff.row_delta)?;
+        writeln!(w)?;
+
+        if !diff.added.is_empty() {
+            writeln!(w, "{} {}", label("added:"), diff.added.join(", "))?;
+        }
+        if !diff.removed.is_empty() {
+            writeln!(w, "{} {}", label("removed:"), diff.removed.join(", "))?;
+        }
+        for (from, to) in &diff.renamed {
+            writeln!(w, "{} {from} -> {to}", label("renamed:"))?;
+        }
+        for change in &diff.type_changes {
+            writeln!(
+                w,
+                "{} {}: {} -> {}",
+                label("type:"),
+                change.column,
+                change.from,
+                change.to
+            )?;
+        }
+        if let Some(ref changes) = diff.null_rate_changes {
+            for change in changes {
+                writeln!(
+                    w,
+                    "{} {}: {:.1}% -> {:.1}% ({:+.1}pp)",
+                    label("null%:"),
+                    change.column,
+                    change.from,
+                    change.to,
+                    change.to - change.from
+                )?;
+            }
+        }
+
+        if diff.has_structural_changes() {
+            let msg = "structural differences found";
+            if use_color {
+                writeln!(w, "{}", msg.red())?;
+            } else {
+                writeln!(w, "{msg}")?;
+            }
+        } else {
+            writeln!(w, "no structural differences")?;
+        }
+
+        Ok(())
+    }
+
+    fn render_diff_csv(&self, diff: &SchemaDiff) -> Result<()> {
+        let mut w = self.get_writer()?;
+        writeln!(w, "change,column,base,file")?;
+        writeln!(w, "rows,,{},{}", diff.base_rows, diff.rows)?;
+        for col in &diff.added {
+            writeln!(w, "added,{},,", escape_csv(col))?;
+        }
+        for col in &diff.removed {
+            writeln!(w, "removed,{},,", escape_csv(col))?;
+        }
+        for (from, to) in &diff.renamed {
+            writeln!(
+                w,
+                "renamed,{},{},{}",
+                escape_csv(to),
+                escape_csv(from),
+                escape_csv(to)
+            )?;
+        }
+        for change in &diff.type_changes {
+            writeln!(
+                w,
+                "type,{},{},{}",
+                escape_csv(&change.column),
+                change.from,
+                change.to
+            )?;
+        }
+        if let Some(ref changes) = diff.null_rate_changes {
+            for change in changes {
+                writeln!(
+                    w,
+                    "null_rate,{},{:.2},{:.2}",
+                    escape_csv(&change.column),
+                    change.from,
+                    change.to
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+const SUMMARY_CSV_HEADER: &str = "column,type,count,null_count,null_rate,unique_count,min,max,mean,median,p25,p75,sum,std,min_len,max_len,top_values,cardinality_ratio,notes,outlier_count,max_decimals,weighted_mean,geo_mean,harmonic_mean,true_count,false_count,true_rate,empty_count,null_literal_count,non_finite_count,first_value,last_value,percentiles";
+
+fn summary_csv_row(stat: &ColumnStats, numbers: NumberFormat) -> String {
+    let decimal =
+        |v: Option<f64>| v.map_or(String::new(), |v| numbers.decimal(v, stat.data_type, 6));
+    format!(
+        "{},{},{},{},{:.2},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
+        escape_csv(&stat.name),
+        stat.data_type,
+        stat.count,
+        stat.null_count,
+        stat.null_rate,
+        stat.unique_count.map_or(String::new(), |v| v.to_string()),
+        stat.min.as_deref().map_or(String::new(), escape_csv),
+        stat.max.as_deref().map_or(String::new(), escape_csv),
+        decimal(stat.mean),
+        decimal(stat.median),
+        decimal(stat.p25),
+        decimal(stat.p75),
+        match stat.sum {
+            Some(Sum::Integer(i)) => i.to_string(),
+            sum => decimal(sum.map(Sum::as_f64)),
+        },
+        decimal(stat.std),
+        stat.min_len.map_or(String::new(), |v| v.to_string()),
+        stat.max_len.map_or(String::new(), |v| v.to_string()),
+        // Columns added later are appended, so positions stay stable
+        stat.top_values
+            .as_deref()
+            .map_or(String::new(), |top| escape_csv(&top_values_field(top))),
+        stat.cardinality_ratio
+            .map_or(String::new(), |v| format!("{v:.4}")),
+        escape_csv(&stat.notes.join("; ")),
+        stat.outlier_count.map_or(String::new(), |v| v.to_string()),
+        stat.max_decimals.map_or(String::new(), |v| v.to_string()),
+        decimal(stat.weighted_mean),
+        decimal(stat.geo_mean),
+        decimal(stat.harmonic_mean),
+        stat.true_count.map_or(String::new(), |v| v.to_string()),
+        stat.false_count.map_or(String::new(), |v| v.to_string()),
+        stat.true_rate.map_or(String::new(), |v| format!("{v:.2}")),
+        stat.empty_count,
+        stat.null_literal_count,
+        stat.non_finite_count
+            .map_or(String::new(), |v| v.to_string()),
+        stat.first_value
+            .as_deref()
+            .map_or(String::new(), escape_csv),
+        stat.last_value.as_deref().map_or(String::new(), escape_csv),
+        stat.percentiles
+            .iter()
+            .map(|(name, v)| format!("{name}:{}", decimal(Some(*v))))
+            .collect::<Vec<_>>()
+            .join("|"),
+    )
+}
+
+fn quickstats_line(stat: &ColumnStats) -> String {
+    format!(
+        "{}\t{}\t{:.2}\t{}",
+        escape_tsv(&stat.name),
+        stat.data_type,
+        stat.null_rate,
+        stat.unique_count.map_or("-".to_string(), |u| u.to_string())
+    )
+}
+
+/// Backslash-escape tabs and line breaks so each field stays one field
+fn escape_tsv(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
+
+/// Encode top values as `value:count|value:count`, backslash-escaping
+/// `\`, `|` and `:` inside values. The nulls have an empty value.
+fn top_values_field(top: &[(Option<String>, usize)]) -> String {
+    top.iter()
+        .map(|(v, c)| {
+            let escaped = v
+                .as_deref()
+                .unwrap_or("")
+                .replace('\\', "\\\\")
+                .replace('|', "\\|")
+                .replace(':', "\\:");
+            format!("{escaped}:{c}")
+        })
+        .collect::<Vec<_>>()
+        .join("|")
+}
+
+/// Header info with optional color. The matched count is only shown when a
+/// filter is active, since otherwise it always equals the row count.
+fn write_summary_header(
+    w: &mut dyn Write,
+    use_color: bool,
+    file: &str,
+    total_rows: u64,
+    matched_rows: u64,
+    filter: Option<&str>,
+) -> Result<()> {
+    let label = |s: &str| {
+        if use_color {
+            s.cyan().to_string()
+        } else {
+            s.to_string()
+        }
+    };
+    writeln!(w, "{} {file}", label("file:"))?;
+    match filter {
+        Some(f) => {
+            let pct = if total_rows > 0 {
+                matched_rows as f64 / total_rows as f64 * 100.0
+            } else {
+                0.0
+            };
+            writeln!(
+                w,
+                "{} {total_rows}, {} {matched_rows} ({pct:.1}%)",
+                label("rows:"),
+                label("matched:")
+            )?;
+            writeln!(w, "{} {f}", label("filter:"))?;
+        }
+        None => writeln!(w, "{} {total_rows}", label("rows:"))?,
+    }
+    Ok(())
+}
+
+fn summary_table(
+    stats: &[ColumnStats],
+    colors: Option<ColorScheme>,
+    numbers: &NumberFormat,
+    extra: ExtraColumns,
+    selection: &StatSelection,
+    max_width: usize,
+) -> Table {
+    // Only present with --weight-col
+    let weighted = stats.iter().any(|s| s.weighted_mean.is_some());
+    let outliers = extra.outliers || selection.picked("outliers");
+    let decimals = extra.decimals || selection.picked("decimals");
+    let first = selection.picked("first");
+    let last = selection.picked("last");
+    let percentiles: Vec<String> = selection
+        .percentiles()
+        .iter()
+        .map(|p| format!("p{p}"))
+        .collect();
+    // Only when some column had inf/NaN left out of its stats
+    let non_finite = stats
+        .iter()
+        .any(|s| s.non_finite_count.is_some_and(|n| n > 0));
+
+    let mut table = Table::new();
+    table.set_content_arrangement(ContentArrangement::Dynamic);
+    let mut header = vec![
+        "column", "type", "count", "null%", "unique", "unique%", "min", "max", "mean", "median",
+        "std",
+    ];
+    if weighted {
+        header.insert(9, "w.mean");
+    }
+    if first {
+        header.push("first");
+    }
+    if last {
+        header.push("last");
+    }
+    header.extend(percentiles.iter().map(String::as_str));
+    if outliers {
+        header.push("outliers");
+    }
+    if decimals {
+        header.push("decimals");
+    }
+    if non_finite {
+        header.push("non-finite");
+    }
+    // Columns for statistics left out with --stats are dropped
+    let shown: Vec<bool> = header
+        .iter()
+        .map(|&h| match h {
+            "null%" => selection.has("null_rate"),
+            "unique" | "unique%" => selection.has("unique"),
+            "w.mean" => selection.has("weighted_mean"),
+            "column" | "type" | "non-finite" => true,
+            stat => selection.has(stat),
+        })
+        .collect();
+    let keep = |cells: Vec<Cell>| -> Vec<Cell> {
+        cells
+            .into_iter()
+            .zip(&shown)
+            .filter_map(|(c, &show)| show.then_some(c))
+            .collect()
+    };
+    table.set_header(keep(header.into_iter().map(Cell::new).collect()));
+
+    for stat in stats {
+        let type_str = match colors {
+            Some(scheme) => colorize_type(stat.data_type, scheme),
+            None => stat.data_type.to_string(),
+        };
+        // Changes from --compare-to, appended to their statistics
+        let delta = stat.delta.as_ref();
+        let decimal_delta = |pick: fn(&StatDelta) -> Option<f64>| {
+            delta_suffix(delta.and_then(pick), |d| {
+                numbers.decimal(d, stat.data_type, 2)
+            })
+        };
+        let count_delta = |pick: fn(&StatDelta) -> Option<i64>| {
+            delta_suffix(delta.and_then(pick).map(|d| d as f64), |d| {
+                numbers.count(d as u64)
+            })
+        };
+        let mut row = vec![
+            Cell::new(&stat.name),
+            Cell::new(type_str),
+            Cell::new(format!(
+                "{}{}",
+                numbers.count(stat.count),
+                count_delta(|d| Some(d.count))
+            )),
+            Cell::new(format!(
+                "{:.1}%{}",
+                stat.null_rate,
+                delta_suffix(delta.map(|d| d.null_rate), |d| format!("{d:.1}pp"))
+            )),
+            Cell::new(stat.unique_count.map_or("-".to_string(), |v| {
+                format!(
+                    "{}{}",
+                    numbers.count(v as u64),
+                    count_delta(|d| d.unique_count)
+                )
+            })),
+            Cell::new(
+                stat.cardinality_ratio
+                    .map_or("-".to_string(), |r| format!("{:.1}%", r * 100.0)),
+            ),
+            Cell::new(truncate_cell(
+                &format_extreme(stat, stat.min.as_deref(), numbers),
+                max_width,
+            )),
+            Cell::new(truncate_cell(
+                &format_extreme(stat, stat.max.as_deref(), numbers),
+                max_width,
+            )),
+            Cell::new(
+                stat.mean
+                    .map(|m| numbers.decimal(m, stat.data_type, 2) + &decimal_delta(|d| d.mean))
+                    .unwrap_or_else(|| "-".to_string()),
+            ),
+            Cell::new(
+                stat.median
+                    .map(|m| numbers.decimal(m, stat.data_type, 2) + &decimal_delta(|d| d.median))
+                    .unwrap_or_else(|| "-".to_string()),
+            ),
+            Cell::new(
+                stat.std
+                    .map(|s| numbers.decimal(s, stat.data_type, 2) + &decimal_delta(|d| d.std))
+                    .unwrap_or_else(|| "-".to_string()),
+            ),
+        ];
+        if weighted {
+            row.insert(
+                9,
+                Cell::new(
+                    stat.weighted_mean
+                        .map(|m| numbers.decimal(m, stat.data_type, 2))
+                        .unwrap_or_else(|| "-".to_string()),
+                ),
+            );
+        }
+        if first {
+            row.push(Cell::new(truncate_cell(
+                stat.first_value.as_deref().unwrap_or("-"),
+                max_width,
+            )));
+        }
+        if last {
+            row.push(Cell::new(truncate_cell(
+                stat.last_value.as_deref().unwrap_or("-"),
+                max_width,
+            )));
+        }
+        for name in &percentiles {
+            let value = stat.percentiles.iter().find(|(n, _)| n == name);
+            row.push(Cell::new(value.map_or("-".to_string(), |(_, v)| {
+                numbers.decimal(*v, stat.data_type, 2)
+            })));
+        }
+        if outliers {
+            row.push(Cell::new(
+                stat.outlier_count
+                    .map_or("-".to_string(), |v| v.to_string()),
+            ));
+        }
+        if decimals {
+            row.push(Cell::new(
+                stat.max_decimals.map_or("-".to_string(), |v| v.to_string()),
+            ));
+        }
+        if non_finite {
+            row.push(Cell::new(
+                stat.non_finite_count
+                    .map_or("-".to_string(), |v| v.to_string()),
+            ));
+        }
+        table.add_row(keep(row));
+    }
+
+    table
+}
+
+fn schema_table(schema: &[ColumnSchema], colors: Option<ColorScheme>, max_width: usize) -> Table {
+    let show_counts = schema.iter().any(|c| c.type_counts.is_some());
+    let mut table = Table::new();
+    table.set_content_arrangement(ContentArrangement::Dynamic);
+    let mut header = vec![Cell::new("column"), Cell::new("type"), Cell::new("null%")];
+    if show_counts {
+        header.push(Cell::new("counts"));
+    }
+    header.push(Cell::new("samples"));
+    table.set_header(header);
+
+    for col in schema {
+        let samples = if col.sample_values.is_empty() {
+            "-".to_string()
+        } else {
+            col.sample_values
+                .iter()
+                .map(|v| truncate_cell(v, max_width))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let type_str = match colors {
+            Some(scheme) => colorize_type(col.inferred_type, scheme),
+            None => col.inferred_type.to_string(),
+        };
+        let mut row = vec![
+            Cell::new(&col.name),
+            Cell::new(type_str),
+            Cell::new(format!("{:.1}%", col.null_rate)),
+        ];
+        if show_counts {
+            row.push(Cell::new(
+                col.type_counts.map_or("-".to_string(), |c| c.to_string()),
+            ));
+        }
+        row.push(Cell::new(samples));
+        table.add_row(row);
+    }
+
+    table
+}
+
+/// Point out numeric columns that look like codes (01234)
+fn write_leading_zero_notes(w: &mut dyn Write, schema: &[ColumnSchema]) -> Result<()> {
+    let numeric_codes: Vec<&ColumnSchema> = schema
+        .iter()
+        .filter(|c| {
+            c.leading_zeros && matches!(c.inferred_type, DataType::Integer | DataType::Float)
+        })
+        .collect();
+    if !numeric_codes.is_empty() {
+        writeln!(w)?;
+        for col in numeric_codes {
+            writeln!(
+                w,
+                "Note: '{}' has values with leading zeros; --preserve-leading-zeros types it as string",
+                col.name
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// List --flag-issues notes after the table
+fn write_warnings(
+    w: &mut dyn Write,
+    stats: &[ColumnStats],
+    indent: &str,
+    use_color: bool,
+) -> Result<()> {
+    if stats.iter().all(|s| s.notes.is_empty()) {
+        return Ok(());
+    }
+
+    writeln!(w)?;
+    if use_color {
+        writeln!(w, "{indent}{}", "Warnings:".yellow())?;
+    } else {
+        writeln!(w, "{indent}Warnings:")?;
+    }
+    for stat in stats {
+        for note in &stat.notes {
+            writeln!(w, "{indent}  column '{}' is {note}", stat.name)?;
+        }
+    }
+    Ok(())
+}
+
+/// Dataset-level footer under the summary table
+fn write_totals(w: &mut dyn Write, totals: &SummaryTotals, use_color: bool) -> Result<()> {
+    if use_color {
+        writeln!(w, "{}", "Totals:".cyan())?;
+    } else {
+        writeln!(w, "Totals:")?;
+    }
+    writeln!(
+        w,
+        "  cells: {}, null: {} ({:.1}%)",
+        totals.cells, totals.null_count, totals.null_rate
+    )?;
+    writeln!(
+        w,
+        "  columns: {} numeric, {} string, {} boolean",
+        totals.numeric_columns, totals.string_columns, totals.boolean_columns
+    )?;
+    if let Some((ref name, rate)) = totals.most_null {
+        writeln!(w, "  most null: {name} ({rate:.1}%)")?;
+    }
+    Ok(())
+}
+
+/// Show the true/false split of boolean columns
+fn write_booleans(w: &mut dyn Write, stats: &[ColumnStats], indent: &str) -> Result<()> {
+    let booleans: Vec<&ColumnStats> = stats.iter().filter(|s| s.true_count.is_some()).collect();
+    if booleans.is_empty() {
+        return Ok(());
+    }
+
+    writeln!(w)?;
+    writeln!(w, "{indent}Booleans:")?;
+    for stat in booleans {
+        let rate = stat
+            .true_rate
+            .map_or("-".to_string(), |r| format!("{r:.1}%"));
+        writeln!(
+            w,
+            "{indent}  {}: {rate} true ({} true, {} false)",
+            stat.name,
+            stat.true_count.unwrap_or(0),
+            stat.false_count.unwrap_or(0)
+        )?;
+    }
+    Ok(())
+}
+
+/// How tables show the nulls counted in top values and freq
+const NULL_LABEL: &str = "(null)";
+
+/// Show top values for string columns
+fn write_top_values(
+    w: &mut dyn Write,
+    stats: &[ColumnStats],
+    indent: &str,
+    quote_mode: QuoteMode,
+    max_width: usize,
+    show_pct: bool,
+) -> Result<()> {
+    let has_top_values = stats.iter().any(|s| s.top_values.is_some());
+    if has_top_values {
+        writeln!(w)?;
+        writeln!(w, "{indent}Top values:")?;
+        for stat in stats {
+            if let Some(ref top) = stat.top_values {
+                let pcts = stat.top_values_pct.as_deref().filter(|_| show_pct);
+                let top_str: Vec<String> = top
+                    .iter()
+                    .enumerate()
+                    .map(|(i, (v, c))| {
+                        let label = match v {
+                            Some(v) => quote_mode.apply(&truncate_cell(v, max_width)),
+                            None => NULL_LABEL.to_string(), // Not a value, so never quoted
+                        };
+                        match pcts.and_then(|p| p.get(i)) {
+                            Some(pct) => format!("{label}({c}, {pct:.1}%)"),
+                            None => format!("{label}({c})"),
+                        }
+                    })
+                    .collect();
+                writeln!(w, "{indent}  {}: {}", stat.name, top_str.join(", "))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Cut `value` to `max` graphemes, ending in an ellipsis (--max-cell-width)
+fn truncate_cell(value: &str, max: usize) -> String {
+    if max == 0 || value.graphemes(true).nth(max).is_none() {
+        return value.to_string();
+    }
+    let kept: String = value.graphemes(true).take(max.saturating_sub(1)).collect();
+    format!("{kept}…")
 }
 
 fn escape_csv(s: &str) -> String {
@@ -359,11 +2011,23 @@ fn escape_csv(s: &str) -> String {
     }
 }
 
-fn colorize_type(dtype: DataType) -> String {
-    match dtype {
-        DataType::Integer => "integer".blue().to_string(),
-        DataType::Float => "float".green().to_string(),
-        DataType::Boolean => "boolean".yellow().to_string(),
-        DataType::String => "string".magenta().to_string(),
+fn colorize_type(dtype: DataType, scheme: ColorScheme) -> String {
+    let name = dtype.to_string();
+    match (scheme, dtype) {
+        (ColorScheme::Default, DataType::Integer) => name.blue(),
+        (ColorScheme::Default, DataType::Float) => name.green(),
+        (ColorScheme::Default, DataType::Boolean) => name.yellow(),
+        (ColorScheme::Default, DataType::String) => name.magenta(),
+        // Blue/yellow stay apart with red-green deficiency, unlike green/magenta;
+        // plain ANSI colors so terminals without truecolor show the same
+        (ColorScheme::Colorblind, DataType::Integer) => name.blue(),
+        (ColorScheme::Colorblind, DataType::Float) => name.yellow(),
+        (ColorScheme::Colorblind, DataType::Boolean) => name.cyan(),
+        (ColorScheme::Colorblind, DataType::String) => name.white(),
+        (ColorScheme::Mono, DataType::Integer) => name.bold(),
+        (ColorScheme::Mono, DataType::Float) => name.underline(),
+        (ColorScheme::Mono, DataType::Boolean) => name.bold().underline(),
+        (ColorScheme::Mono, DataType::String) => name.normal(),
     }
+    .to_string()
 }
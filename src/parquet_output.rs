@@ -0,0 +1,188 @@
+use anyhow::Result;
+use arrow_array::builder::{Float64Builder, ListBuilder, MapBuilder, StringBuilder, UInt64Builder};
+use arrow_array::{ArrayRef, BooleanArray, Float64Array, RecordBatch, StringArray, UInt64Array};
+use parquet::arrow::ArrowWriter;
+use std::fs::File;
+use std::sync::Arc;
+
+use crate::types::{ColumnSchema, ColumnStats, Sum};
+
+/// Write summary statistics as one Parquet row per column
+pub fn write_summary(path: &str, stats: &[ColumnStats]) -> Result<()> {
+    let strings = |f: fn(&ColumnStats) -> Option<&str>| -> ArrayRef {
+        Arc::new(stats.iter().map(f).collect::<StringArray>())
+    };
+    let floats = |f: fn(&ColumnStats) -> Option<f64>| -> ArrayRef {
+        Arc::new(stats.iter().map(f).collect::<Float64Array>())
+    };
+    let counts = |f: fn(&ColumnStats) -> Option<u64>| -> ArrayRef {
+        Arc::new(stats.iter().map(f).collect::<UInt64Array>())
+    };
+
+    let mut top_values = MapBuilder::new(None, StringBuilder::new(), UInt64Builder::new());
+    for stat in stats {
+        match stat.top_values {
+            Some(ref top) => {
+                for (value, count) in top {
+                    // Map keys can't be null, so nulls are the empty value, as in CSV
+                    top_values
+                        .keys()
+                        .append_value(value.as_deref().unwrap_or(""));
+                    top_values.values().append_value(*count as u64);
+                }
+                top_values.append(true)?;
+            }
+            None => top_values.append(false)?,
+        }
+    }
+
+    let mut percentiles = MapBuilder::new(None, StringBuilder::new(), Float64Builder::new());
+    for stat in stats {
+        for (name, value) in &stat.percentiles {
+            percentiles.keys().append_value(name);
+            percentiles.values().append_value(*value);
+        }
+        percentiles.append(true)?;
+    }
+
+    let batch = RecordBatch::try_from_iter(vec![
+        ("name", strings(|s| Some(&s.name))),
+        (
+            "type",
+            Arc::new(
+                stats
+                    .iter()
+                    .map(|s| Some(s.data_type.to_string()))
+                    .collect::<StringArray>(),
+            ) as ArrayRef,
+        ),
+        ("count", counts(|s| Some(s.count))),
+        ("null_count", counts(|s| Some(s.null_count))),
+        ("empty_count", counts(|s| Some(s.empty_count))),
+        ("null_literal_count", counts(|s| Some(s.null_literal_count))),
+        ("null_rate", floats(|s| Some(s.null_rate))),
+        ("unique_count", counts(|s| s.unique_count.map(|v| v as u64))),
+        ("cardinality_ratio", floats(|s| s.cardinality_ratio)),
+        ("min", strings(|s| s.min.as_deref())),
+        ("max", strings(|s| s.max.as_deref())),
+        ("first_value", strings(|s| s.first_value.as_deref())),
+        ("last_value", strings(|s| s.last_value.as_deref())),
+        ("mean", floats(|s| s.mean)),
+        ("weighted_mean", floats(|s| s.weighted_mean)),
+        ("geo_mean", floats(|s| s.geo_mean)),
+        ("harmonic_mean", floats(|s| s.harmonic_mean)),
+        ("median", floats(|s| s.median)),
+        ("p25", floats(|s| s.p25)),
+        ("p75", floats(|s| s.p75)),
+        ("percentiles", Arc::new(percentiles.finish()) as ArrayRef),
+        ("outlier_count", counts(|s| s.outlier_count)),
+        ("non_finite_count", counts(|s| s.non_finite_count)),
+        ("sum", floats(|s| s.sum.map(Sum::as_f64))),
+        ("std", floats(|s| s.std)),
+        ("min_len", counts(|s| s.min_len.map(|v| v as u64))),
+        ("max_len", counts(|s| s.max_len.map(|v| v as u64))),
+        ("max_decimals", counts(|s| s.max_decimals.map(|v| v as u64))),
+        ("true_count", counts(|s| s.true_count)),
+        ("false_count", counts(|s| s.false_count)),
+        ("true_rate", floats(|s| s.true_rate)),
+        ("top_values", Arc::new(top_values.finish()) as ArrayRef),
+        (
+            "notes",
+            Arc::new(
+                stats
+                    .iter()
+                    .map(|s| (!s.notes.is_empty()).then(|| s.notes.join("; ")))
+                    .collect::<StringArray>(),
+            ) as ArrayRef,
+        ),
+    ])?;
+
+    write_batch(path, &batch)
+}
+
+/// Write an inferred schema as one Parquet row per column
+pub fn write_schema(path: &str, schema: &[ColumnSchema]) -> Result<()> {
+    let mut samples = ListBuilder::new(StringBuilder::new());
+    for col in schema {
+        for value in &col.sample_values {
+            samples.values().append_value(value);
+        }
+        samples.append(true);
+    }
+
+    let batch = RecordBatch::try_from_iter(vec![
+        (
+            "name",
+            Arc::new(
+                schema
+                    .iter()
+                    .map(|c| Some(c.name.as_str()))
+                    .collect::<StringArray>(),
+            ) as ArrayRef,
+        ),
+        (
+            "type",
+            Arc::new(
+                schema
+                    .iter()
+                    .map(|c| Some(c.inferred_type.to_string()))
+                    .collect::<StringArray>(),
+            ) as ArrayRef,
+        ),
+        (
+            "null_count",
+            Arc::new(schema.iter().map(|c| c.null_count).collect::<UInt64Array>()) as ArrayRef,
+        ),
+        (
+            "empty_count",
+            Arc::new(
+                schema
+                    .iter()
+                    .map(|c| c.empty_count)
+                    .collect::<UInt64Array>(),
+            ) as ArrayRef,
+        ),
+        (
+            "null_literal_count",
+            Arc::new(
+                schema
+                    .iter()
+                    .map(|c| c.null_literal_count)
+                    .collect::<UInt64Array>(),
+            ) as ArrayRef,
+        ),
+        (
+            "total_count",
+            Arc::new(
+                schema
+                    .iter()
+                    .map(|c| c.total_count)
+                    .collect::<UInt64Array>(),
+            ) as ArrayRef,
+        ),
+        (
+            "null_rate",
+            Arc::new(schema.iter().map(|c| c.null_rate).collect::<Float64Array>()) as ArrayRef,
+        ),
+        ("sample_values", Arc::new(samples.finish()) as ArrayRef),
+        (
+            "leading_zeros",
+            Arc::new(
+                schema
+                    .iter()
+                    .map(|c| Some(c.leading_zeros))
+                    .collect::<BooleanArray>(),
+            ) as ArrayRef,
+        ),
+    ])?;
+
+    write_batch(path, &batch)
+}
+
+fn write_batch(path: &str, batch: &RecordBatch) -> Result<()> {
+    let file = File::create(path)?;
+    let mut writer = ArrowWriter::try_new(file, batch.schema(), None)?;
+    writer.write(batch)?;
+    writer.close()?;
+    Ok(())
+}
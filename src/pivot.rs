@@ -0,0 +1,158 @@
+use anyhow::Result;
+use csv::StringRecord;
+use std::collections::{BTreeSet, HashMap};
+
+use crate::error::{ColumnSuggestion, CsvpeekError, find_similar_column};
+use crate::types::{PivotRow, PivotTable};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Aggregation {
+    Count,
+    Sum,
+    Mean,
+}
+
+impl Aggregation {
+    pub fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "count" => Ok(Aggregation::Count),
+            "sum" => Ok(Aggregation::Sum),
+            "mean" | "avg" => Ok(Aggregation::Mean),
+            _ => Err(anyhow::anyhow!(
+                "Unknown aggregation: {s}. Supported: count, sum, mean"
+            )),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Aggregation::Count => "count",
+            Aggregation::Sum => "sum",
+            Aggregation::Mean => "mean",
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct CellAccumulator {
+    rows: u64,
+    sum: f64,
+    numeric_count: u64,
+}
+
+pub struct PivotBuilder {
+    row_column: String,
+    col_column: String,
+    value_column: Option<String>,
+    row_index: usize,
+    col_index: usize,
+    value_index: Option<usize>,
+    agg: Aggregation,
+    cells: HashMap<(String, String), CellAccumulator>,
+    row_keys: BTreeSet<String>,
+    col_keys: BTreeSet<String>,
+}
+
+impl PivotBuilder {
+    pub fn new(
+        row_column: &str,
+        col_column: &str,
+        value_column: Option<&str>,
+        agg: Aggregation,
+        headers: &StringRecord,
+    ) -> Result<Self> {
+        let header_vec: Vec<String> = headers.iter().map(|s| s.to_string()).collect();
+
+        if agg != Aggregation::Count && value_column.is_none() {
+            return Err(anyhow::anyhow!(
+                "--value is required for --agg {}",
+                agg.name()
+            ));
+        }
+
+        let row_index = column_index(row_column, &header_vec)?;
+        let col_index = column_index(col_column, &header_vec)?;
+        let value_index = value_column
+            .map(|v| column_index(v, &header_vec))
+            .transpose()?;
+
+        Ok(Self {
+            row_column: row_column.to_string(),
+            col_column: col_column.to_string(),
+            value_column: value_column.map(|v| v.to_string()),
+            row_index,
+            col_index,
+            value_index,
+            agg,
+            cells: HashMap::new(),
+            row_keys: BTreeSet::new(),
+            col_keys: BTreeSet::new(),
+        })
+    }
+
+    pub fn add_record(&mut self, record: &StringRecord) {
+        let row_key = record.get(self.row_index).unwrap_or("").trim().to_string();
+        let col_key = record.get(self.col_index).unwrap_or("").trim().to_string();
+
+        self.row_keys.insert(row_key.clone());
+        self.col_keys.insert(col_key.clone());
+
+        let cell = self.cells.entry((row_key, col_key)).or_default();
+        cell.rows += 1;
+
+        if let Some(idx) = self.value_index
+            && let Ok(num) = record.get(idx).unwrap_or("").trim().parse::<f64>()
+        {
+            cell.sum += num;
+            cell.numeric_count += 1;
+        }
+    }
+
+    pub fn finalize(self) -> PivotTable {
+        let columns: Vec<String> = self.col_keys.into_iter().collect();
+
+        let rows = self
+            .row_keys
+            .into_iter()
+            .map(|row_key| {
+                let values = columns
+                    .iter()
+                    .map(|col_key| {
+                        let cell = self.cells.get(&(row_key.clone(), col_key.clone()));
+                        match (self.agg, cell) {
+                            (Aggregation::Count, cell) => Some(cell.map_or(0, |c| c.rows) as f64),
+                            (_, None) => None,
+                            (_, Some(c)) if c.numeric_count == 0 => None,
+                            (Aggregation::Sum, Some(c)) => Some(c.sum),
+                            (Aggregation::Mean, Some(c)) => Some(c.sum / c.numeric_count as f64),
+                        }
+                    })
+                    .collect();
+                PivotRow {
+                    key: row_key,
+                    values,
+                }
+            })
+            .collect();
+
+        PivotTable {
+            row_column: self.row_column,
+            column_column: self.col_column,
+            value_column: self.value_column,
+            agg: self.agg.name().to_string(),
+            columns,
+            rows,
+        }
+    }
+}
+
+fn column_index(name: &str, headers: &[String]) -> Result<usize> {
+    headers.iter().position(|h| h == name).ok_or_else(|| {
+        CsvpeekError::ColumnNotFound {
+            name: name.to_string(),
+            suggestion: find_similar_column(name, headers)
+                .map(|s| ColumnSuggestion { suggested: s }),
+        }
+        .into()
+    })
+}
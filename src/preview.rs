@@ -0,0 +1,59 @@
+use csv::StringRecord;
+use std::collections::VecDeque;
+
+/// First and last N raw rows, kept while streaming (--preview)
+pub struct RowPreview {
+    headers: StringRecord,
+    size: usize,
+    head: Vec<StringRecord>,
+    tail: VecDeque<StringRecord>, // Ring buffer of the latest rows after the head
+    rows: u64,
+}
+
+/// The collected rows, ready to render
+#[derive(Debug, Clone)]
+pub struct Preview {
+    pub headers: StringRecord,
+    pub head: Vec<StringRecord>,
+    pub tail: Vec<StringRecord>,
+    pub skipped: u64, // Rows between head and tail that aren't shown
+}
+
+impl RowPreview {
+    pub fn new(size: usize, headers: &StringRecord) -> Self {
+        Self {
+            headers: headers.clone(),
+            size,
+            // Grown on demand: size comes from the command line
+            head: Vec::new(),
+            tail: VecDeque::new(),
+            rows: 0,
+        }
+    }
+
+    pub fn add_record(&mut self, record: &StringRecord) {
+        self.rows += 1;
+        if self.head.len() < self.size {
+            self.head.push(record.clone());
+            return;
+        }
+        if self.size == 0 {
+            return;
+        }
+        if self.tail.len() == self.size {
+            self.tail.pop_front();
+        }
+        self.tail.push_back(record.clone());
+    }
+
+    /// The rows kept so far
+    pub fn preview(&self) -> Preview {
+        let shown = (self.head.len() + self.tail.len()) as u64;
+        Preview {
+            headers: self.headers.clone(),
+            head: self.head.clone(),
+            tail: self.tail.iter().cloned().collect(),
+            skipped: self.rows - shown,
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use csv::StringRecord;
 use indicatif::{ProgressBar, ProgressStyle};
 use std::fs;
+use std::time::Duration;
 
 pub struct ProgressTracker {
     bar: Option<ProgressBar>,
@@ -68,3 +69,58 @@ impl ProgressTracker {
         }
     }
 }
+
+/// Where a summary run spent its time (--timings), printed to stderr
+#[derive(Debug, Default)]
+pub struct Timings {
+    pub open: Duration,     // Opening, plus decoding input that isn't streamed
+    pub read: Duration,     // Reading and decoding streamed input
+    pub parse: Duration,    // Splitting records into fields
+    pub stats: Duration,    // Filtering and accumulating statistics
+    pub finalize: Duration, // Finalizing statistics and rendering
+    pub rows: u64,
+    pub unique_bytes: usize, // Approximate, for the unique-value sets
+}
+
+impl Timings {
+    pub fn print(&self) {
+        let phases = [
+            ("read/decode", self.open + self.read),
+            ("parse", self.parse),
+            ("stats", self.stats),
+            ("finalize", self.finalize),
+        ];
+        let total: Duration = phases.iter().map(|(_, d)| *d).sum();
+
+        eprintln!("Timings:");
+        for (name, spent) in phases {
+            let share = match total.as_secs_f64() {
+                0.0 => 0.0,
+                t => 100.0 * spent.as_secs_f64() / t,
+            };
+            eprintln!("  {name:<12} {:>9.3}s {share:>5.1}%", spent.as_secs_f64());
+        }
+        eprintln!("  {:<12} {:>9.3}s", "total", total.as_secs_f64());
+
+        let rate = match self.read + self.parse + self.stats {
+            d if d.is_zero() => 0.0,
+            d => self.rows as f64 / d.as_secs_f64(),
+        };
+        eprintln!("  {:<12} {} ({rate:.0}/s)", "rows", self.rows);
+        eprintln!(
+            "  {:<12} ~{:.1} MiB",
+            "unique sets",
+            self.unique_bytes as f64 / (1024.0 * 1024.0)
+        );
+
+        // What to try when one phase dominates
+        let (slowest, _) = phases.iter().max_by_key(|(_, d)| *d).unwrap();
+        let hint = match *slowest {
+            "read/decode" => "Mostly reading and decoding: the run is I/O bound",
+            "parse" => "Mostly parsing: fewer columns with -c won't help much",
+            "stats" => "Mostly statistics: -c, --no-unique or --no-percentiles would help",
+            _ => "Mostly finalizing: --no-percentiles skips sorting numeric values",
+        };
+        eprintln!("  {hint}");
+    }
+}
@@ -0,0 +1,176 @@
+/// Streaming estimate of one quantile with the P² algorithm (Jain &
+/// Chlamtac, 1985): five markers track the minimum, the maximum, the
+/// quantile and the two points halfway to it, and are moved along a
+/// piecewise-parabolic curve as values arrive. Memory is constant however
+/// many values are added.
+#[derive(Debug, Clone)]
+pub struct P2Quantile {
+    heights: [f64; 5],   // Marker values
+    positions: [f64; 5], // Actual marker positions, 1-based ranks
+    desired: [f64; 5],   // Where the markers should be
+    increments: [f64; 5],
+}
+
+impl P2Quantile {
+    /// Start from values already seen, sorted ascending; needs at least 5.
+    /// The markers begin at the nearest ranks in `sorted`, which is better
+    /// than the usual start from just the first five values.
+    pub fn from_sorted(p: f64, sorted: &[f64]) -> Self {
+        debug_assert!(sorted.len() >= 5);
+        let last = (sorted.len() - 1) as f64;
+        let desired = [
+            1.0,
+            1.0 + last * p / 2.0,
+            1.0 + last * p,
+            1.0 + last * (1.0 + p) / 2.0,
+            1.0 + last,
+        ];
+        // Marker ranks must be whole and strictly increasing, leaving room
+        // for the markers above
+        let mut positions = desired.map(f64::round);
+        for i in 1..4 {
+            positions[i] = positions[i].clamp(positions[i - 1] + 1.0, desired[4] - (4 - i) as f64);
+        }
+
+        Self {
+            heights: positions.map(|n| sorted[n as usize - 1]),
+            positions,
+            desired,
+            increments: [0.0, p / 2.0, p, (1.0 + p) / 2.0, 1.0],
+        }
+    }
+
+    pub fn add(&mut self, x: f64) {
+        let q = &mut self.heights;
+        let n = &mut self.positions;
+
+        // The cell x falls in, widening the extremes when needed
+        let k = if x < q[0] {
+            q[0] = x;
+            0
+        } else if x >= q[4] {
+            q[4] = x;
+            3
+        } else {
+            (0..4).find(|&i| x < q[i + 1]).unwrap_or(3)
+        };
+        for pos in n.iter_mut().skip(k + 1) {
+            *pos += 1.0;
+        }
+        for (d, inc) in self.desired.iter_mut().zip(self.increments) {
+            *d += inc;
+        }
+
+        // Move the middle markers one rank towards where they should be
+        for i in 1..4 {
+            let off = self.desired[i] - n[i];
+            if (off >= 1.0 && n[i + 1] - n[i] > 1.0) || (off <= -1.0 && n[i - 1] - n[i] < -1.0) {
+                let d = off.signum();
+                let parabolic = q[i]
+                    + d / (n[i + 1] - n[i - 1])
+                        * ((n[i] - n[i - 1] + d) * (q[i + 1] - q[i]) / (n[i + 1] - n[i])
+                            + (n[i + 1] - n[i] - d) * (q[i] - q[i - 1]) / (n[i] - n[i - 1]));
+                q[i] = if q[i - 1] < parabolic && parabolic < q[i + 1] {
+                    parabolic
+                } else {
+                    let j = if d > 0.0 { i + 1 } else { i - 1 };
+                    q[i] + d * (q[j] - q[i]) / (n[j] - n[i])
+                };
+                n[i] += d;
+            }
+        }
+    }
+
+    pub fn estimate(&self) -> f64 {
+        self.heights[2]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stats::percentile;
+
+    /// Deterministic uniform values in [0, 1)
+    fn uniform(count: usize) -> Vec<f64> {
+        let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+        (0..count)
+            .map(|_| {
+                state = state
+                    .wrapping_mul(6_364_136_223_846_793_005)
+                    .wrapping_add(1_442_695_040_888_963_407);
+                (state >> 11) as f64 / (1u64 << 53) as f64
+            })
+            .collect()
+    }
+
+    fn normal(count: usize) -> Vec<f64> {
+        // Box-Muller over pairs of uniforms
+        uniform(count * 2)
+            .chunks(2)
+            .map(|u| {
+                let (u1, u2) = (1.0 - u[0], u[1]);
+                100.0 + 15.0 * (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+            })
+            .collect()
+    }
+
+    fn exponential(count: usize) -> Vec<f64> {
+        uniform(count)
+            .into_iter()
+            .map(|u| -(1.0 - u).ln())
+            .collect()
+    }
+
+    /// Estimate after seeding with the first `seed` values, and the exact
+    /// percentile of all of them
+    fn estimate_and_exact(values: &[f64], p: f64, seed: usize) -> (f64, f64) {
+        let mut head = values[..seed].to_vec();
+        head.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let mut estimator = P2Quantile::from_sorted(p, &head);
+        for &v in &values[seed..] {
+            estimator.add(v);
+        }
+        let mut sorted = values.to_vec();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        (estimator.estimate(), percentile(&sorted, p * 100.0))
+    }
+
+    #[test]
+    fn test_p2_close_to_exact() {
+        let cases: [(&str, Vec<f64>, f64); 3] = [
+            ("uniform", uniform(50_000), 0.01),
+            ("normal", normal(50_000), 0.5),
+            ("exponential", exponential(50_000), 0.02),
+        ];
+        for (name, values, tolerance) in cases {
+            for p in [0.25, 0.5, 0.75] {
+                for seed in [5, 1000] {
+                    let (estimate, exact) = estimate_and_exact(&values, p, seed);
+                    assert!(
+                        (estimate - exact).abs() < tolerance,
+                        "{name} p{p} seed {seed}: {estimate} vs exact {exact}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_p2_seed_starts_at_nearest_rank() {
+        let values: Vec<f64> = (1..=11).map(f64::from).collect();
+        assert_eq!(P2Quantile::from_sorted(0.5, &values).estimate(), 6.0);
+        assert_eq!(P2Quantile::from_sorted(0.25, &values).estimate(), 4.0); // Rank 3.5
+    }
+
+    #[test]
+    fn test_p2_sorted_input() {
+        // Ascending input is the classic hard case for streaming estimators
+        let values: Vec<f64> = (0..10_000).map(f64::from).collect();
+        let (estimate, exact) = estimate_and_exact(&values, 0.5, 5);
+        assert!(
+            (estimate - exact).abs() / exact < 0.01,
+            "{estimate} vs {exact}"
+        );
+    }
+}
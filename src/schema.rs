@@ -1,44 +1,61 @@
 use anyhow::Result;
 use csv::StringRecord;
+use std::collections::HashMap;
 
-use crate::types::{ColumnSchema, DataType, is_null, parse_value};
+use crate::types::{
+    ColumnSchema, DataType, NullKind, TypeCounts, has_leading_zero, is_scientific, null_kind,
+    parse_value,
+};
 
 pub struct SchemaInferrer {
     columns: Vec<ColumnTypeAccumulator>,
+    column_indices: Vec<usize>,
+    preserve_leading_zeros: bool,
+    sci_as_float: bool,
+    type_counts: bool,
 }
 
-const MAX_SAMPLE_VALUES: usize = 3;
+const MAX_SAMPLE_VALUES: usize = 5;
 
 struct ColumnTypeAccumulator {
     name: String,
     total_count: u64,
     null_count: u64,
+    empty_count: u64,
     integer_count: u64,
     float_count: u64,
     boolean_count: u64,
     string_count: u64,
-    sample_values: Vec<String>,
+    leading_zero_count: u64,
+    sample_values: Vec<String>, // In first-seen order, so output is stable across runs
+    forced_type: Option<DataType>, // From --types
 }
 
 impl ColumnTypeAccumulator {
-    fn new(name: String) -> Self {
+    fn new(name: String, forced_type: Option<DataType>) -> Self {
         Self {
             name,
             total_count: 0,
             null_count: 0,
+            empty_count: 0,
             integer_count: 0,
             float_count: 0,
             boolean_count: 0,
             string_count: 0,
+            leading_zero_count: 0,
             sample_values: Vec::new(),
+            forced_type,
         }
     }
 
-    fn add_value(&mut self, value: &str) {
+    fn add_value(&mut self, value: &str, sci_as_float: bool) {
         self.total_count += 1;
 
-        if is_null(value) {
+        if let Some(kind) = null_kind(value) {
             self.null_count += 1;
+            if kind == NullKind::Empty {
+                self.empty_count += 1;
+            }
             return;
         }
 
@@ -50,6 +67,11 @@ impl ColumnTypeAccumulator {
 
         let (dtype, _) = parse_value(value);
         match dtype {
+            DataType::Integer if has_leading_zero(value) => {
+                self.leading_zero_count += 1;
+                self.integer_count += 1;
+            }
+            DataType::Integer if sci_as_float && is_scientific(value) => self.float_count += 1,
             DataType::Integer => self.integer_count += 1,
             DataType::Float => self.float_count += 1,
             DataType::Boolean => self.boolean_count += 1,
@@ -57,13 +79,22 @@ impl ColumnTypeAccumulator {
         }
     }
 
-    fn infer_type(&self) -> DataType {
+    fn infer_type(&self, preserve_leading_zeros: bool) -> DataType {
+        if let Some(dtype) = self.forced_type {
+            return dtype;
+        }
+
         let non_null = self.total_count - self.null_count;
 
         if non_null == 0 {
             return DataType::String;
         }
 
+        // Codes like 01234 lose their zeros as numbers
+        if preserve_leading_zeros && self.leading_zero_count > 0 {
+            return DataType::String;
+        }
+
         // If all non-null values are integers, it's an integer column
         if self.integer_count == non_null {
             return DataType::Integer;
@@ -82,45 +113,98 @@ impl ColumnTypeAccumulator {
         DataType::String
     }
 
-    fn finalize(self) -> ColumnSchema {
+    fn finalize(self, preserve_leading_zeros: bool, type_counts: bool) -> ColumnSchema {
         let null_rate = if self.total_count > 0 {
             (self.null_count as f64) / (self.total_count as f64) * 100.0
         } else {
             0.0
         };
 
-        let inferred_type = self.infer_type();
+        let inferred_type = self.infer_type(preserve_leading_zeros);
+        let type_counts = type_counts.then_some(TypeCounts {
+            integer: self.integer_count,
+            float: self.float_count,
+            boolean: self.boolean_count,
+            string: self.string_count,
+            null: self.null_count,
+        });
 
         ColumnSchema {
             name: self.name,
             inferred_type,
             null_count: self.null_count,
+            empty_count: self.empty_count,
+            null_literal_count: self.null_count - self.empty_count,
             total_count: self.total_count,
             null_rate,
             sample_values: self.sample_values,
+            leading_zeros: self.leading_zero_count > 0,
+            type_counts,
         }
     }
 }
 
 impl SchemaInferrer {
-    pub fn new(headers: &StringRecord) -> Self {
-        let columns = headers
-            .iter()
-            .map(|h| ColumnTypeAccumulator::new(h.to_string()))
-            .collect();
+    /// Infers `target_columns`, in that order; names not in `headers` are skipped
+    pub fn new(
+        target_columns: &[String],
+        headers: &StringRecord,
+        type_overrides: &HashMap<String, DataType>,
+    ) -> Self {
+        let mut columns = Vec::new();
+        let mut column_indices = Vec::new();
+
+        for col in target_columns {
+            if let Some(idx) = headers.iter().position(|h| h == col) {
+                columns.push(ColumnTypeAccumulator::new(
+                    col.clone(),
+                    type_overrides.get(col).copied(),
+                ));
+                column_indices.push(idx);
+            }
+        }
+
+        Self {
+            columns,
+            column_indices,
+            preserve_leading_zeros: false,
+            sci_as_float: false,
+            type_counts: false,
+        }
+    }
+
+    /// Infer String for integer columns with values like 01234
+    pub fn with_preserve_leading_zeros(mut self, preserve: bool) -> Self {
+        self.preserve_leading_zeros = preserve;
+        self
+    }
+
+    /// Infer Float for whole numbers in scientific notation such as 1e3
+    pub fn with_sci_as_float(mut self, sci_as_float: bool) -> Self {
+        self.sci_as_float = sci_as_float;
+        self
+    }
 
-        Self { columns }
+    /// Keep the per-type value counts behind each inferred type (--explain-types)
+    pub fn with_type_counts(mut self, type_counts: bool) -> Self {
+        self.type_counts = type_counts;
+        self
     }
 
     pub fn add_record(&mut self, record: &StringRecord) -> Result<()> {
-        for (i, acc) in self.columns.iter_mut().enumerate() {
-            let value = record.get(i).unwrap_or("");
-            acc.add_value(value);
+        for (acc, &idx) in self.columns.iter_mut().zip(self.column_indices.iter()) {
+            let value = record.get(idx).unwrap_or("");
+            acc.add_value(value, self.sci_as_float);
         }
         Ok(())
     }
 
     pub fn finalize(self) -> Vec<ColumnSchema> {
-        self.columns.into_iter().map(|acc| acc.finalize()).collect()
+        let preserve = self.preserve_leading_zeros;
+        let type_counts = self.type_counts;
+        self.columns
+            .into_iter()
+            .map(|acc| acc.finalize(preserve, type_counts))
+            .collect()
     }
 }
@@ -3,75 +3,566 @@ use csv::StringRecord;
 use rayon::prelude::*;
 use std::collections::{HashMap, HashSet};
 
-use crate::types::{ColumnStats, DataType, is_null, parse_value};
+use crate::quantile::P2Quantile;
+use crate::types::{
+    ColumnStats, DataType, MAX_EXACT_INTEGER, NullKind, StatDelta, Sum, SummaryTotals, Value,
+    has_leading_zero, is_scientific, null_kind, parse_value,
+};
+
+/// What min/max mean for string columns (--string-extremes)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StringExtremes {
+    Lexical, // Smallest and largest by byte order
+    Length,  // Shortest and longest value, first seen on ties
+}
+
+impl StringExtremes {
+    pub fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "lexical" => Ok(StringExtremes::Lexical),
+            "length" => Ok(StringExtremes::Length),
+            _ => Err(anyhow::anyhow!(
+                "Unknown string extremes mode: {s}. Supported: lexical, length"
+            )),
+        }
+    }
+}
+
+/// Numeric values kept per column for exact percentiles; past this, unless
+/// --exact-percentiles, they seed P² estimates and are dropped
+pub const EXACT_PERCENTILE_VALUES: usize = 10_000;
+
+/// What to do with inf, -inf and NaN in numeric columns (--nan-policy)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NanPolicy {
+    Ignore, // Left out of numeric stats, counted in non_finite_count
+    Error,  // Abort on the first one
+    Count,  // Counted as nulls
+}
+
+impl NanPolicy {
+    pub fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "ignore" => Ok(NanPolicy::Ignore),
+            "error" => Ok(NanPolicy::Error),
+            "count" => Ok(NanPolicy::Count),
+            _ => Err(anyhow::anyhow!(
+                "Unknown NaN policy: {s}. Supported: ignore, error, count"
+            )),
+        }
+    }
+}
+
+/// Totals across the finalized columns of a summary (--totals)
+pub fn summary_totals(stats: &[ColumnStats]) -> SummaryTotals {
+    let cells: u64 = stats.iter().map(|s| s.count + s.null_count).sum();
+    let null_count: u64 = stats.iter().map(|s| s.null_count).sum();
+    let columns = |pred: fn(DataType) -> bool| stats.iter().filter(|s| pred(s.data_type)).count();
+    // First column wins ties, so the result follows the column order
+    let most_null = stats
+        .iter()
+        .filter(|s| s.null_count > 0)
+        .fold(None::<&ColumnStats>, |best, s| match best {
+            Some(b) if b.null_rate >= s.null_rate => Some(b),
+            _ => Some(s),
+        })
+        .map(|s| (s.name.clone(), s.null_rate));
+
+    SummaryTotals {
+        cells,
+        null_count,
+        null_rate: if cells > 0 {
+            null_count as f64 / cells as f64 * 100.0
+        } else {
+            0.0
+        },
+        numeric_columns: columns(|t| matches!(t, DataType::Integer | DataType::Float)),
+        string_columns: columns(|t| t == DataType::String),
+        boolean_columns: columns(|t| t == DataType::Boolean),
+        most_null,
+    }
+}
+
+/// Set each column's change from the column of the same name in a saved
+/// summary (--compare-to); columns the baseline lacks get no delta
+pub fn compare_to_baseline(stats: &mut [ColumnStats], baseline: &[ColumnStats]) {
+    let diff = |now: Option<f64>, then: Option<f64>| Some(now? - then?);
+    for stat in stats {
+        let Some(base) = baseline.iter().find(|b| b.name == stat.name) else {
+            continue;
+        };
+        stat.delta = Some(StatDelta {
+            count: stat.count as i64 - base.count as i64,
+            null_rate: stat.null_rate - base.null_rate,
+            unique_count: stat
+                .unique_count
+                .zip(base.unique_count)
+                .map(|(now, then)| now as i64 - then as i64),
+            mean: diff(stat.mean, base.mean),
+            median: diff(stat.median, base.median),
+            std: diff(stat.std, base.std),
+        });
+    }
+}
+
+/// The rank of a pNN statistic, p1 to p99
+fn percentile_rank(name: &str) -> Option<u8> {
+    let digits = name.strip_prefix('p')?;
+    if !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    digits.parse().ok().filter(|p| (1..=99).contains(p))
+}
+
+/// inf, -inf, infinity or NaN, in any case
+fn is_non_finite(value: &str) -> bool {
+    value.parse::<f64>().is_ok_and(|n| !n.is_finite())
+}
+
+/// Statistics that can be picked with --stats. unique covers unique_count
+/// and cardinality_ratio, top_values also top_values_pct, booleans the
+/// true/false counts and rate, outliers outlier_count, decimals
+/// max_decimals, and first/last first_value/last_value. Any other
+/// percentile can be picked as pNN, p1 to p99 (see percentile_rank).
+pub const STAT_NAMES: &[&str] = &[
+    "count",
+    "null_rate",
+    "unique",
+    "min",
+    "max",
+    "first",
+    "last",
+    "mean",
+    "weighted_mean",
+    "geo_mean",
+    "harmonic_mean",
+    "median",
+    "p25",
+    "p75",
+    "outliers",
+    "sum",
+    "std",
+    "min_len",
+    "max_len",
+    "decimals",
+    "booleans",
+    "top_values",
+];
+
+/// The statistics to compute and show (--stats); everything by default
+#[derive(Debug, Clone, Default)]
+pub struct StatSelection(Option<HashSet<String>>);
+
+impl StatSelection {
+    pub fn parse(list: &str) -> Result<Self> {
+        let mut names = HashSet::new();
+        for name in list.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+            let mut name = name.to_lowercase();
+            if let Some(rank) = percentile_rank(&name) {
+                name = format!("p{rank}"); // p05 is p5
+            } else if !STAT_NAMES.contains(&name.as_str()) {
+                return Err(anyhow::anyhow!(
+                    "Unknown statistic: {name}. Supported: {}, or pNN for another percentile (p1 to p99, e.g. p95)",
+                    STAT_NAMES.join(", ")
+                ));
+            }
+            names.insert(name);
+        }
+        if names.is_empty() {
+            return Err(anyhow::anyhow!("--stats needs at least one statistic"));
+        }
+        Ok(Self(Some(names)))
+    }
+
+    pub fn has(&self, name: &str) -> bool {
+        self.0.as_ref().is_none_or(|names| names.contains(name))
+    }
+
+    /// Named in --stats, rather than selected by default
+    pub fn picked(&self, name: &str) -> bool {
+        self.0.as_ref().is_some_and(|names| names.contains(name))
+    }
+
+    /// The pNN percentiles picked besides p25, median and p75, in
+    /// ascending order
+    pub fn percentiles(&self) -> Vec<u8> {
+        let mut ranks: Vec<u8> = self
+            .0
+            .iter()
+            .flatten()
+            .filter(|name| !STAT_NAMES.contains(&name.as_str()))
+            .filter_map(|name| percentile_rank(name))
+            .collect();
+        ranks.sort_unstable();
+        ranks
+    }
+
+    /// Whether every numeric value has to be kept
+    fn needs_percentiles(&self) -> bool {
+        ["median", "p25", "p75", "outliers"]
+            .iter()
+            .any(|n| self.has(n))
+            || !self.percentiles().is_empty()
+    }
+
+    /// Clear the statistics that weren't selected. count and null_rate
+    /// aren't optional, so they stay; renderers leave them out instead.
+    fn apply(&self, stat: &mut ColumnStats) {
+        if self.0.is_none() {
+            return;
+        }
+        let keep = |name: &str| self.has(name);
+        if !keep("unique") {
+            stat.unique_count = None;
+            stat.cardinality_ratio = None;
+        }
+        if !keep("min") {
+            stat.min = None;
+        }
+        if !keep("max") {
+            stat.max = None;
+        }
+        if !keep("first") {
+            stat.first_value = None;
+        }
+        if !keep("last") {
+            stat.last_value = None;
+        }
+        if !keep("mean") {
+            stat.mean = None;
+        }
+        if !keep("weighted_mean") {
+            stat.weighted_mean = None;
+        }
+        if !keep("geo_mean") {
+            stat.geo_mean = None;
+        }
+        if !keep("harmonic_mean") {
+            stat.harmonic_mean = None;
+        }
+        if !keep("median") {
+            stat.median = None;
+        }
+        if !keep("p25") {
+            stat.p25 = None;
+        }
+        if !keep("p75") {
+            stat.p75 = None;
+        }
+        if !keep("outliers") {
+            stat.outlier_count = None;
+        }
+        if !keep("sum") {
+            stat.sum = None;
+        }
+        if !keep("std") {
+            stat.std = None;
+        }
+        if !keep("min_len") {
+            stat.min_len = None;
+        }
+        if !keep("max_len") {
+            stat.max_len = None;
+        }
+        if !keep("decimals") {
+            stat.max_decimals = None;
+        }
+        if !keep("booleans") {
+            stat.true_count = None;
+            stat.false_count = None;
+            stat.true_rate = None;
+        }
+        if !keep("top_values") {
+            stat.top_values = None;
+            stat.top_values_pct = None;
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct StatsOptions {
+    pub unique: bool,
+    pub percentiles: bool,
+    pub exact_percentiles: bool, // Keep every value instead of estimating
+    pub flag_issues: bool,
+    pub precision_warning: bool, // Note integer columns past 2^53
+    pub preserve_leading_zeros: bool,
+    pub sci_as_float: bool, // Type 1e3 as float rather than integer
+    pub null_as_unique: bool,
+    pub weight_column: Option<String>, // --weight-col, for weighted_mean
+    pub top_min_count: usize,          // Drop rarer values from top_values
+    pub string_extremes: StringExtremes,
+    pub nan_policy: NanPolicy,
+    pub selection: StatSelection,
+    pub type_overrides: HashMap<String, DataType>,
+}
+
+impl Default for StatsOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StatsOptions {
+    pub fn new() -> Self {
+        Self {
+            unique: true,
+            percentiles: true,
+            exact_percentiles: false,
+            flag_issues: false,
+            precision_warning: false,
+            preserve_leading_zeros: false,
+            sci_as_float: false,
+            null_as_unique: false,
+            weight_column: None,
+            top_min_count: 1,
+            string_extremes: StringExtremes::Lexical,
+            nan_policy: NanPolicy::Ignore,
+            selection: StatSelection::default(),
+            type_overrides: HashMap::new(),
+        }
+    }
+
+    pub fn with_unique(mut self, unique: bool) -> Self {
+        self.unique = unique;
+        self
+    }
+
+    pub fn with_percentiles(mut self, percentiles: bool) -> Self {
+        self.percentiles = percentiles;
+        self
+    }
+
+    pub fn with_exact_percentiles(mut self, exact: bool) -> Self {
+        self.exact_percentiles = exact;
+        self
+    }
+
+    pub fn with_flag_issues(mut self, flag_issues: bool) -> Self {
+        self.flag_issues = flag_issues;
+        self
+    }
+
+    pub fn with_precision_warning(mut self, precision_warning: bool) -> Self {
+        self.precision_warning = precision_warning;
+        self
+    }
+
+    pub fn with_preserve_leading_zeros(mut self, preserve: bool) -> Self {
+        self.preserve_leading_zeros = preserve;
+        self
+    }
+
+    pub fn with_sci_as_float(mut self, sci_as_float: bool) -> Self {
+        self.sci_as_float = sci_as_float;
+        self
+    }
+
+    pub fn with_null_as_unique(mut self, null_as_unique: bool) -> Self {
+        self.null_as_unique = null_as_unique;
+        self
+    }
+
+    pub fn with_weight_column(mut self, weight_column: Option<String>) -> Self {
+        self.weight_column = weight_column;
+        self
+    }
+
+    pub fn with_top_min_count(mut self, top_min_count: usize) -> Self {
+        self.top_min_count = top_min_count;
+        self
+    }
+
+    pub fn with_nan_policy(mut self, nan_policy: NanPolicy) -> Self {
+        self.nan_policy = nan_policy;
+        self
+    }
+
+    pub fn with_string_extremes(mut self, string_extremes: StringExtremes) -> Self {
+        self.string_extremes = string_extremes;
+        self
+    }
+
+    pub fn with_selection(mut self, selection: StatSelection) -> Self {
+        self.selection = selection;
+        self
+    }
+
+    pub fn with_type_overrides(mut self, type_overrides: HashMap<String, DataType>) -> Self {
+        self.type_overrides = type_overrides;
+        self
+    }
+}
 
 pub struct StatsCollector {
     columns: Vec<ColumnAccumulator>,
     column_indices: Vec<usize>,
+    weight_index: Option<usize>,
+    selection: StatSelection,
 }
 
 struct ColumnAccumulator {
     name: String,
     count: u64,
     null_count: u64,
+    empty_count: u64,
     data_type: Option<DataType>,
+    forced_type: bool, // data_type was set by --types
+    flag_issues: bool,
+    precision_warning: bool, // Note integers too large for exact f64 stats
+    preserve_leading_zeros: bool,
+    sci_as_float: bool,
+    null_as_unique: bool, // Count nulls as one more distinct value
+    top_min_count: usize,
+    string_extremes: StringExtremes,
+    nan_policy: NanPolicy,
 
     // Numeric stats
     sum: f64,
-    sum_squares: f64, // For standard deviation
+    int_sum: Option<i128>, // Exact sum while every number is an integer
+    inexact_ints: u64,     // Integers past ±2^53, which f64 stats round
+    sum_squares: f64,      // For standard deviation
     numeric_count: u64,
+    non_finite_count: u64, // inf/NaN left out under --nan-policy ignore
     min_numeric: Option<f64>,
     max_numeric: Option<f64>,
+    max_decimals: Option<usize>,
+    weighted_sum: f64, // Σ(value * weight), with --weight-col
+    weight_total: f64, // Σweight over the same values
+    weighted: bool,
+    sum_ln: f64,         // For geometric mean
+    sum_reciprocal: f64, // For harmonic mean
+    all_positive: bool,  // Both need every value > 0
+
+    // Boolean stats
+    true_count: u64,
+    false_count: u64,
 
     // String stats (for min/max)
     min_string: Option<String>,
     max_string: Option<String>,
 
+    // First and last non-null values, in file order
+    first_value: Option<String>,
+    last_value: String, // Reused buffer, copied into only with keep_last
+    keep_last: bool,    // Whether last was asked for
+
     // v1.1 string stats
     min_len: Option<usize>,
     max_len: Option<usize>,
-    unique_values: HashSet<String>,
+    unique_values: Option<HashSet<String>>, // None when --no-unique
 
     // v1.2 stats
-    numeric_values: Vec<f64>,             // For median/percentile
+    numeric_values: Option<Vec<f64>>, // For median/percentile; None when --no-percentiles
+    exact_percentiles: bool,
+    percentiles: Vec<u8>, // pNN picked with --stats, kept apart from p25/median/p75
+    quantiles: Option<Box<[P2Quantile; 3]>>, // p25, median, p75 once values are dropped
     value_counts: HashMap<String, usize>, // For top_n
+    count_values: bool,   // Whether top values were asked for
 }
 
 impl ColumnAccumulator {
-    fn new(name: String) -> Self {
+    fn new(name: String, options: &StatsOptions) -> Self {
+        let forced_type = options.type_overrides.get(&name).copied();
         Self {
             name,
             count: 0,
             null_count: 0,
-            data_type: None,
+            empty_count: 0,
+            data_type: forced_type,
+            forced_type: forced_type.is_some(),
+            flag_issues: options.flag_issues,
+            precision_warning: options.precision_warning,
+            preserve_leading_zeros: options.preserve_leading_zeros,
+            sci_as_float: options.sci_as_float,
+            null_as_unique: options.null_as_unique,
+            top_min_count: options.top_min_count,
+            string_extremes: options.string_extremes,
+            nan_policy: options.nan_policy,
             sum: 0.0,
+            int_sum: Some(0),
+            inexact_ints: 0,
             sum_squares: 0.0,
             numeric_count: 0,
+            non_finite_count: 0,
             min_numeric: None,
             max_numeric: None,
+            max_decimals: None,
+            weighted_sum: 0.0,
+            weight_total: 0.0,
+            weighted: options.weight_column.is_some(),
+            sum_ln: 0.0,
+            sum_reciprocal: 0.0,
+            all_positive: true,
+            true_count: 0,
+            false_count: 0,
             min_string: None,
             max_string: None,
+            first_value: None,
+            last_value: String::new(),
+            keep_last: options.selection.has("last"),
             min_len: None,
             max_len: None,
-            unique_values: HashSet::new(),
-            numeric_values: Vec::new(),
+            unique_values: (options.unique && options.selection.has("unique")).then(HashSet::new),
+            numeric_values: (options.percentiles && options.selection.needs_percentiles())
+                .then(Vec::new),
+            exact_percentiles: options.exact_percentiles,
+            percentiles: options.selection.percentiles(),
+            quantiles: None,
             value_counts: HashMap::new(),
+            count_values: options.selection.has("top_values"),
         }
     }
 
-    fn add_value(&mut self, value: &str) {
+    /// `weight` is the row's --weight-col value, None when it isn't numeric
+    fn add_value(&mut self, value: &str, weight: Option<f64>) -> Result<()> {
         self.count += 1;
 
-        if is_null(value) {
+        // inf/NaN are numbers only where numbers are expected; in a column
+        // of text (by --types or by its values so far) they are just words
+        let numeric_column = matches!(
+            self.data_type,
+            None | Some(DataType::Integer) | Some(DataType::Float)
+        );
+        let non_finite = numeric_column && is_non_finite(value.trim());
+        if non_finite && self.nan_policy == NanPolicy::Error {
+            return Err(anyhow::anyhow!(
+                "Non-finite value {} in column '{}' (use --nan-policy ignore or count)",
+                value.trim(),
+                self.name
+            ));
+        }
+        let null = null_kind(value)
+            .or((non_finite && self.nan_policy == NanPolicy::Count).then_some(NullKind::Literal));
+        if let Some(kind) = null {
             self.null_count += 1;
-            return;
+            if kind == NullKind::Empty {
+                self.empty_count += 1;
+            }
+            return Ok(());
         }
 
         let trimmed = value.trim();
-        let (dtype, _) = parse_value(trimmed);
+        let (mut dtype, parsed) = parse_value(trimmed);
+        match parsed {
+            Value::Boolean(true) => self.true_count += 1,
+            Value::Boolean(false) => self.false_count += 1,
+            _ => {}
+        }
+        if non_finite {
+            dtype = DataType::Float; // Doesn't turn the column into strings
+            self.non_finite_count += 1;
+        }
+        if dtype == DataType::Integer && self.preserve_leading_zeros && has_leading_zero(trimmed) {
+            dtype = DataType::String; // A code like 01234, not a number
+        }
+        if dtype == DataType::Integer && self.sci_as_float && is_scientific(trimmed) {
+            dtype = DataType::Float;
+        }
 
         // Update data type (promote to more general type if needed)
         self.data_type = Some(match (self.data_type, dtype) {
+            (Some(t), _) if self.forced_type => t,
             (None, t) => t,
             (Some(DataType::Integer), DataType::Float) => DataType::Float,
             (Some(DataType::Float), DataType::Integer) => DataType::Float,
@@ -84,11 +575,48 @@ impl ColumnAccumulator {
         });
 
         // Update numeric stats if applicable
-        if let Ok(num) = trimmed.parse::<f64>() {
+        if let Ok(num) = trimmed.parse::<f64>()
+            && num.is_finite()
+        {
             self.sum += num;
+            match parsed {
+                Value::Integer(i) => {
+                    self.int_sum = self.int_sum.map(|s| s + i128::from(i));
+                    if i.unsigned_abs() > MAX_EXACT_INTEGER as u64 {
+                        self.inexact_ints += 1;
+                    }
+                }
+                _ => self.int_sum = None,
+            }
             self.sum_squares += num * num;
             self.numeric_count += 1;
-            self.numeric_values.push(num); // v1.2: collect for median/percentile
+            if num > 0.0 {
+                self.sum_ln += num.ln();
+                self.sum_reciprocal += num.recip();
+            } else {
+                self.all_positive = false;
+            }
+            if let Some(w) = weight {
+                self.weighted_sum += num * w;
+                self.weight_total += w;
+            }
+            let decimals = decimal_places(trimmed);
+            self.max_decimals = Some(self.max_decimals.map_or(decimals, |m| m.max(decimals)));
+            if let Some(ref mut values) = self.numeric_values {
+                values.push(num); // v1.2: collect for median/percentile
+            } else if let Some(ref mut estimators) = self.quantiles {
+                for estimator in estimators.iter_mut() {
+                    estimator.add(num);
+                }
+            }
+            if !self.exact_percentiles
+                && self
+                    .numeric_values
+                    .as_ref()
+                    .is_some_and(|v| v.len() > EXACT_PERCENTILE_VALUES)
+            {
+                self.start_estimating();
+            }
             self.min_numeric = Some(
                 self.min_numeric
                     .map_or(num, |m| if num < m { num } else { m }),
@@ -103,36 +631,58 @@ impl ColumnAccumulator {
         let trimmed_str = trimmed.to_string();
         let len = trimmed.chars().count();
 
+        let (is_min, is_max) = match self.string_extremes {
+            StringExtremes::Lexical => (
+                self.min_string
+                    .as_ref()
+                    .is_none_or(|m| trimmed < m.as_str()),
+                self.max_string
+                    .as_ref()
+                    .is_none_or(|m| trimmed > m.as_str()),
+            ),
+            StringExtremes::Length => (
+                self.min_len.is_none_or(|m| len < m),
+                self.max_len.is_none_or(|m| len > m),
+            ),
+        };
+        if is_min {
+            self.min_string = Some(trimmed_str.clone());
+        }
+        if is_max {
+            self.max_string = Some(trimmed_str.clone());
+        }
+        if self.first_value.is_none() {
+            self.first_value = Some(trimmed_str.clone());
+        }
+        if self.keep_last {
+            self.last_value.clear();
+            self.last_value.push_str(trimmed);
+        }
+
         self.min_len = Some(self.min_len.map_or(len, |m| m.min(len)));
         self.max_len = Some(self.max_len.map_or(len, |m| m.max(len)));
-        self.unique_values.insert(trimmed_str.clone());
+        if let Some(ref mut unique_values) = self.unique_values {
+            unique_values.insert(trimmed_str.clone());
+        }
 
         // v1.2: track value frequencies for top_n
-        *self.value_counts.entry(trimmed_str.clone()).or_insert(0) += 1;
+        if self.count_values {
+            *self.value_counts.entry(trimmed_str).or_insert(0) += 1;
+        }
+        Ok(())
+    }
 
-        self.min_string = Some(match &self.min_string {
-            None => trimmed_str.clone(),
-            Some(m) => {
-                if trimmed_str < *m {
-                    trimmed_str.clone()
-                } else {
-                    m.clone()
-                }
-            }
-        });
-        self.max_string = Some(match &self.max_string {
-            None => trimmed_str,
-            Some(m) => {
-                if trimmed_str > *m {
-                    trimmed_str
-                } else {
-                    m.clone()
-                }
-            }
-        });
+    /// Swap the kept values for P² estimators seeded with them
+    fn start_estimating(&mut self) {
+        if let Some(mut values) = self.numeric_values.take() {
+            values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            self.quantiles = Some(Box::new(
+                [0.25, 0.5, 0.75].map(|p| P2Quantile::from_sorted(p, &values)),
+            ));
+        }
     }
 
-    fn finalize(mut self) -> ColumnStats {
+    fn finalize(&mut self) -> ColumnStats {
         let total = self.count;
         let null_rate = if total > 0 {
             (self.null_count as f64) / (total as f64) * 100.0
@@ -143,31 +693,103 @@ impl ColumnAccumulator {
         let data_type = self.data_type.unwrap_or(DataType::String);
 
         // v1.2: Calculate percentiles (median, p25, p75)
-        let (median, p25, p75) = if !self.numeric_values.is_empty() {
-            self.numeric_values
-                .sort_by(|a, b| a.partial_cmp(b).unwrap());
-            (
-                Some(percentile(&self.numeric_values, 50.0)),
-                Some(percentile(&self.numeric_values, 25.0)),
-                Some(percentile(&self.numeric_values, 75.0)),
+        let mut percentiles = Vec::new();
+        let (median, p25, p75, outlier_count) = match self.numeric_values {
+            Some(ref mut values) if !values.is_empty() => {
+                values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+                let (q1, q3) = (percentile(values, 25.0), percentile(values, 75.0));
+                let is_numeric = matches!(data_type, DataType::Integer | DataType::Float);
+                percentiles = self
+                    .percentiles
+                    .iter()
+                    .map(|&p| (format!("p{p}"), percentile(values, p as f64)))
+                    .collect();
+                (
+                    Some(percentile(values, 50.0)),
+                    Some(q1),
+                    Some(q3),
+                    is_numeric.then(|| count_outliers(values, q1, q3)),
+                )
+            }
+            // Outliers can't be counted without the values
+            _ => match self.quantiles {
+                Some(ref q) => (
+                    Some(q[1].estimate()),
+                    Some(q[0].estimate()),
+                    Some(q[2].estimate()),
+                    None,
+                ),
+                None => (None, None, None, None),
+            },
+        };
+
+        // With --count-null-as-unique, nulls are one more distinct value,
+        // kept apart from any cell that happens to read (null)
+        let null_bucket = self.null_as_unique && self.null_count > 0;
+
+        // v1.2: Calculate top values (top 5 most frequent, seen at least
+        // top_min_count times)
+        let nulls = (null_bucket && self.count_values).then_some((None, self.null_count as usize));
+        let mut counts: Vec<(Option<&String>, usize)> = self
+            .value_counts
+            .iter()
+            .map(|(v, &c)| (Some(v), c))
+            .chain(nulls)
+            .filter(|&(_, c)| c >= self.top_min_count)
+            .collect();
+        let top_values = if !counts.is_empty() {
+            // By count descending, ties by value (nulls first), so output
+            // doesn't follow the HashMap's per-run order
+            counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+            Some(
+                counts
+                    .into_iter()
+                    .take(5)
+                    .map(|(v, c)| (v.cloned(), c))
+                    .collect(),
             )
         } else {
-            (None, None, None)
+            None
         };
 
-        // v1.2: Calculate top values (top 5 most frequent)
-        let top_values = if !self.value_counts.is_empty() {
-            let mut counts: Vec<(String, usize)> = self.value_counts.into_iter().collect();
-            counts.sort_by(|a, b| b.1.cmp(&a.1)); // Sort by count descending
-            Some(counts.into_iter().take(5).collect())
+        let max_decimals = self
+            .max_decimals
+            .filter(|_| matches!(data_type, DataType::Integer | DataType::Float));
+
+        let weighted_mean = (self.weighted
+            && self.weight_total != 0.0
+            && matches!(data_type, DataType::Integer | DataType::Float))
+        .then(|| self.weighted_sum / self.weight_total);
+
+        let (geo_mean, harmonic_mean) = if self.all_positive
+            && self.numeric_count > 0
+            && matches!(data_type, DataType::Integer | DataType::Float)
+        {
+            let n = self.numeric_count as f64;
+            (Some((self.sum_ln / n).exp()), Some(n / self.sum_reciprocal))
         } else {
-            None
+            (None, None)
+        };
+
+        let non_finite_count = matches!(data_type, DataType::Integer | DataType::Float)
+            .then_some(self.non_finite_count);
+
+        let (true_count, false_count, true_rate) = if data_type == DataType::Boolean {
+            let flags = self.true_count + self.false_count;
+            let rate = (flags > 0).then(|| self.true_count as f64 / flags as f64 * 100.0);
+            (Some(self.true_count), Some(self.false_count), rate)
+        } else {
+            (None, None, None)
         };
 
+        // Exact for integer columns; a forced --types integer column can
+        // still hold decimals, which leave only the f64 sum
+        let int_sum = self.int_sum.filter(|_| data_type == DataType::Integer);
         let (min, max, mean, sum, std) = match data_type {
             DataType::Integer | DataType::Float => {
                 let mean = if self.numeric_count > 0 {
-                    Some(self.sum / self.numeric_count as f64)
+                    let sum = int_sum.map_or(self.sum, |s| s as f64);
+                    Some(sum / self.numeric_count as f64)
                 } else {
                     None
                 };
@@ -182,7 +804,7 @@ impl ColumnAccumulator {
                 };
 
                 let sum = if self.numeric_count > 0 {
-                    Some(self.sum)
+                    Some(int_sum.map_or(Sum::Float(self.sum), Sum::Integer))
                 } else {
                     None
                 };
@@ -195,33 +817,110 @@ impl ColumnAccumulator {
                     std,
                 )
             }
-            _ => (self.min_string, self.max_string, None, None, None),
+            _ => (
+                self.min_string.clone(),
+                self.max_string.clone(),
+                None,
+                None,
+                None,
+            ),
+        };
+
+        let unique_count = self
+            .unique_values
+            .as_ref()
+            .map(|u| u.len() + null_bucket as usize);
+        let count = total - self.null_count;
+        let last_value = (self.keep_last && count > 0).then(|| self.last_value.clone());
+        // With nulls as a value, the ratio is over every cell
+        let distinct_base = if null_bucket { total } else { count };
+        let cardinality_ratio = unique_count
+            .filter(|_| distinct_base > 0)
+            .map(|u| u as f64 / distinct_base as f64);
+        let top_values_pct = top_values
+            .as_ref()
+            .map(|top: &Vec<(Option<String>, usize)>| {
+                top.iter()
+                    .map(|(_, c)| *c as f64 / distinct_base as f64 * 100.0)
+                    .collect()
+            });
+        let mut notes = if self.flag_issues {
+            // Judged on the non-null values, as without the flag
+            column_issues(
+                count,
+                self.null_count,
+                unique_count.map(|u| u - null_bucket as usize),
+            )
+        } else {
+            Vec::new()
         };
+        if self.precision_warning && int_sum.is_some() && self.inexact_ints > 0 {
+            notes.push(format!(
+                "past ±2^53 in {} value(s); mean, std, min/max and percentiles are approximate (sum is exact)",
+                self.inexact_ints
+            ));
+        }
 
         ColumnStats {
-            name: self.name,
+            name: self.name.clone(),
             data_type,
-            count: total - self.null_count,
+            count,
             null_count: self.null_count,
+            empty_count: self.empty_count,
+            null_literal_count: self.null_count - self.empty_count,
             null_rate,
             min,
             max,
+            first_value: self.first_value.clone(),
+            last_value,
             mean,
+            weighted_mean,
+            geo_mean,
+            harmonic_mean,
             sum,
             std,
             min_len: self.min_len,
             max_len: self.max_len,
-            unique_count: Some(self.unique_values.len()),
+            max_decimals,
+            unique_count,
+            cardinality_ratio,
             median,
             p25,
             p75,
+            percentiles,
+            outlier_count,
+            non_finite_count,
+            true_count,
+            false_count,
+            true_rate,
             top_values,
+            top_values_pct,
+            notes,
+            delta: None,
         }
     }
 }
 
+/// Data-quality notes for --flag-issues
+fn column_issues(count: u64, null_count: u64, unique_count: Option<usize>) -> Vec<String> {
+    let mut notes = Vec::new();
+    if count == 0 {
+        if null_count > 0 {
+            notes.push("100% null".to_string());
+        }
+        return notes;
+    }
+
+    match unique_count {
+        Some(1) => notes.push("constant".to_string()),
+        Some(u) if count > 1 && u as u64 == count => notes.push("all unique".to_string()),
+        _ => {}
+    }
+    notes
+}
+
 /// Calculate percentile using linear interpolation
-fn percentile(sorted_data: &[f64], p: f64) -> f64 {
+pub fn percentile(sorted_data: &[f64], p: f64) -> f64 {
     if sorted_data.is_empty() {
         return 0.0;
     }
@@ -242,18 +941,45 @@ fn percentile(sorted_data: &[f64], p: f64) -> f64 {
     }
 }
 
+/// Fractional digits as written, trailing zeros included ("1.50" is 2). An
+/// exponent shifts the point: "1.5e-3" is 4, "1.25e1" is 1.
+fn decimal_places(value: &str) -> usize {
+    let (mantissa, exponent) = match value.find(['e', 'E']) {
+        Some(pos) => (&value[..pos], value[pos + 1..].parse::<i64>().unwrap_or(0)),
+        None => (value, 0),
+    };
+    let fraction = mantissa.split_once('.').map_or(0, |(_, f)| f.len()) as i64;
+    (fraction - exponent).max(0) as usize
+}
+
+/// Count values outside the Tukey fences, [Q1 - 1.5*IQR, Q3 + 1.5*IQR]
+fn count_outliers(sorted_data: &[f64], q1: f64, q3: f64) -> u64 {
+    let iqr = q3 - q1;
+    let (low, high) = (q1 - 1.5 * iqr, q3 + 1.5 * iqr);
+    // Sorted, so the outliers are a prefix and a suffix
+    let below = sorted_data.partition_point(|&v| v < low);
+    let above = sorted_data.len() - sorted_data.partition_point(|&v| v <= high);
+    (below + above) as u64
+}
+
 fn format_number(v: f64, dtype: DataType) -> String {
     match dtype {
         DataType::Integer => format!("{}", v as i64),
-        _ => format!("{v:.6}")
-            .trim_end_matches('0')
-            .trim_end_matches('.')
-            .to_string(),
+        _ => {
+            // Keep at least one decimal so float columns never look like integers
+            let s = format!("{v:.6}");
+            let s = s.trim_end_matches('0');
+            if s.ends_with('.') {
+                format!("{s}0")
+            } else {
+                s.to_string()
+            }
+        }
     }
 }
 
 impl StatsCollector {
-    pub fn new(target_columns: &[String], headers: &StringRecord) -> Self {
+    pub fn new(target_columns: &[String], headers: &StringRecord, options: StatsOptions) -> Self {
         let header_vec: Vec<String> = headers.iter().map(|s| s.to_string()).collect();
 
         let mut columns = Vec::new();
@@ -261,30 +987,62 @@ impl StatsCollector {
 
         for col in target_columns {
             if let Some(idx) = header_vec.iter().position(|h| h == col) {
-                columns.push(ColumnAccumulator::new(col.clone()));
+                columns.push(ColumnAccumulator::new(col.clone(), &options));
                 column_indices.push(idx);
             }
         }
 
+        let weight_index = options
+            .weight_column
+            .as_ref()
+            .and_then(|w| header_vec.iter().position(|h| h == w));
+
         Self {
             columns,
             column_indices,
+            weight_index,
+            selection: options.selection,
         }
     }
 
     pub fn add_record(&mut self, record: &StringRecord, _headers: &StringRecord) -> Result<()> {
+        // Rows whose weight isn't a number still count, just not towards weighted_mean
+        let weight = self
+            .weight_index
+            .and_then(|i| record.get(i))
+            .and_then(|w| w.trim().parse::<f64>().ok())
+            .filter(|w| w.is_finite());
         for (acc, &idx) in self.columns.iter_mut().zip(self.column_indices.iter()) {
             let value = record.get(idx).unwrap_or("");
-            acc.add_value(value);
+            acc.add_value(value, weight)?;
         }
         Ok(())
     }
 
-    pub fn finalize(self) -> Vec<ColumnStats> {
+    /// Approximate heap bytes held by the unique-value sets (--timings)
+    pub fn unique_memory(&self) -> usize {
+        self.columns
+            .iter()
+            .filter_map(|acc| acc.unique_values.as_ref())
+            .map(|set| {
+                // One String and a control byte per bucket, plus the text
+                set.capacity() * (std::mem::size_of::<String>() + 1)
+                    + set.iter().map(String::capacity).sum::<usize>()
+            })
+            .sum()
+    }
+
+    /// Statistics over the records so far; more can still be added after
+    pub fn finalize(&mut self) -> Vec<ColumnStats> {
         // Use parallel iteration for finalizing columns (sorting, percentile calculation)
+        let selection = &self.selection;
         self.columns
-            .into_par_iter()
-            .map(|acc| acc.finalize())
+            .par_iter_mut()
+            .map(|acc| {
+                let mut stat = acc.finalize();
+                selection.apply(&mut stat);
+                stat
+            })
             .collect()
     }
 }
@@ -1,6 +1,7 @@
-use serde::Serialize;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum DataType {
     Integer,
@@ -9,6 +10,20 @@ pub enum DataType {
     String,
 }
 
+impl DataType {
+    pub fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "integer" | "int" => Ok(DataType::Integer),
+            "float" | "double" | "number" => Ok(DataType::Float),
+            "boolean" | "bool" => Ok(DataType::Boolean),
+            "string" | "str" | "text" => Ok(DataType::String),
+            _ => Err(anyhow::anyhow!(
+                "Unknown type: {s}. Supported: integer, float, boolean, string"
+            )),
+        }
+    }
+}
+
 impl std::fmt::Display for DataType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -20,28 +35,190 @@ impl std::fmt::Display for DataType {
     }
 }
 
-#[derive(Debug, Clone, Serialize)]
+/// Serialized in field order. Any map-valued statistic added later should
+/// be a BTreeMap, never a HashMap, so JSON output stays byte-stable.
+/// Deserialized from a saved JSON summary for --compare-to.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ColumnStats {
     pub name: String,
     pub data_type: DataType,
     pub count: u64,
     pub null_count: u64,
+    pub empty_count: u64,        // Nulls that are blank cells
+    pub null_literal_count: u64, // Nulls written as null, NA or N/A
     pub null_rate: f64,
     pub min: Option<String>,
     pub max: Option<String>,
+    pub first_value: Option<String>, // First non-null value in file order
+    pub last_value: Option<String>,  // Last non-null value
     pub mean: Option<f64>,
+    pub weighted_mean: Option<f64>, // Σ(value*weight)/Σweight, with --weight-col
+    pub geo_mean: Option<f64>,      // None unless every value is positive
+    pub harmonic_mean: Option<f64>, // Likewise
     // v1.1 numeric statistics
-    pub sum: Option<f64>,
+    pub sum: Option<Sum>,
     pub std: Option<f64>,
     // v1.1 string statistics
     pub min_len: Option<usize>,
     pub max_len: Option<usize>,
+    pub max_decimals: Option<usize>, // Most fractional digits written in a numeric value
     pub unique_count: Option<usize>,
+    pub cardinality_ratio: Option<f64>, // unique_count / count: ~1.0 key-like, ~0 constant
     // v1.2 statistics
     pub median: Option<f64>,
     pub p25: Option<f64>,
     pub p75: Option<f64>,
-    pub top_values: Option<Vec<(String, usize)>>,
+    // Other percentiles picked with --stats, e.g. ("p95", 97.5)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub percentiles: Vec<(String, f64)>,
+    pub outlier_count: Option<u64>, // Outside p25 - 1.5*IQR ..= p75 + 1.5*IQR
+    pub non_finite_count: Option<u64>, // inf/NaN left out of the numeric stats
+    // Boolean columns only
+    pub true_count: Option<u64>,
+    pub false_count: Option<u64>,
+    pub true_rate: Option<f64>, // Percentage of non-null values that are true
+    pub top_values: Option<Vec<(Option<String>, usize)>>, // None counts the nulls (--count-null-as-unique)
+    pub top_values_pct: Option<Vec<f64>>, // Share of each top value, in the same order
+    // --flag-issues: "100% null", "constant", "all unique"
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub notes: Vec<String>,
+    // --compare-to: change since the baseline, None if it lacks the column
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delta: Option<StatDelta>,
+}
+
+/// A column's sum. Integer columns are added up exactly in an i128, so
+/// large IDs or amounts don't lose digits past 2^53 as an f64 sum would.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Sum {
+    Integer(i128),
+    Float(f64),
+}
+
+impl Sum {
+    pub fn as_f64(self) -> f64 {
+        match self {
+            Sum::Integer(i) => i as f64,
+            Sum::Float(f) => f,
+        }
+    }
+}
+
+/// Change of a column's statistics from a --compare-to baseline. Each is
+/// None when the baseline or the current run lacks that statistic.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StatDelta {
+    pub count: i64,
+    pub null_rate: f64, // Percentage points
+    pub unique_count: Option<i64>,
+    pub mean: Option<f64>,
+    pub median: Option<f64>,
+    pub std: Option<f64>,
+}
+
+/// Dataset-level footer of the summary table (--totals)
+#[derive(Debug, Clone, PartialEq)]
+pub struct SummaryTotals {
+    pub cells: u64, // Every cell of the analyzed columns, nulls included
+    pub null_count: u64,
+    pub null_rate: f64, // null_count / cells, so wider columns weigh more
+    pub numeric_columns: usize,
+    pub string_columns: usize,
+    pub boolean_columns: usize,
+    pub most_null: Option<(String, f64)>, // Column with the highest null rate, if any nulls
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GroupStats {
+    pub group: String,
+    pub rows: u64,
+    pub stats: Vec<ColumnStats>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PivotTable {
+    pub row_column: String,
+    pub column_column: String,
+    pub value_column: Option<String>,
+    pub agg: String,
+    pub columns: Vec<String>,
+    pub rows: Vec<PivotRow>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PivotRow {
+    pub key: String,
+    pub values: Vec<Option<f64>>, // None for empty combinations (sum/mean)
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FreqTable {
+    pub column: String,
+    pub bin_width: Option<f64>, // Set when the values were binned
+    pub rows: u64,
+    pub values: Vec<FreqRow>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FreqRow {
+    pub value: Option<String>, // The value or a bucket like [10, 20); None for nulls
+    pub count: u64,
+    pub percent: f64, // Of all rows counted
+}
+
+/// How well the file decodes as each common encoding (--encoding-report)
+#[derive(Debug, Clone, Serialize)]
+pub struct EncodingReport {
+    pub file: String,
+    pub bom: Option<String>, // Encoding named by a byte order mark, which decides it
+    pub detected: String,    // What auto-detection picks
+    pub confident: bool,     // chardetng's own assessment of its guess
+    pub candidates: Vec<EncodingCandidate>, // Fewest errors first
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EncodingCandidate {
+    pub encoding: String,
+    pub errors: u64, // Malformed byte sequences, each decoded as U+FFFD
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SchemaDiff {
+    pub base_file: String,
+    pub file: String,
+    pub base_rows: u64,
+    pub rows: u64,
+    pub row_delta: i64,
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub renamed: Vec<(String, String)>,
+    pub type_changes: Vec<TypeChange>,
+    pub null_rate_changes: Option<Vec<NullRateChange>>, // Only with --data
+}
+
+impl SchemaDiff {
+    /// Added, removed, renamed or retyped columns (row counts and null rates don't count)
+    pub fn has_structural_changes(&self) -> bool {
+        !self.added.is_empty()
+            || !self.removed.is_empty()
+            || !self.renamed.is_empty()
+            || !self.type_changes.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TypeChange {
+    pub column: String,
+    pub from: DataType,
+    pub to: DataType,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NullRateChange {
+    pub column: String,
+    pub from: f64,
+    pub to: f64,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -49,9 +226,35 @@ pub struct ColumnSchema {
     pub name: String,
     pub inferred_type: DataType,
     pub null_count: u64,
+    pub empty_count: u64,
+    pub null_literal_count: u64,
     pub total_count: u64,
     pub null_rate: f64,
     pub sample_values: Vec<String>,
+    pub leading_zeros: bool, // Some integer values are written like 01234
+    // --explain-types: how many values parsed as each type
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub type_counts: Option<TypeCounts>,
+}
+
+/// Non-null values of a column by the type each one parses as, plus nulls
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct TypeCounts {
+    pub integer: u64,
+    pub float: u64,
+    pub boolean: u64,
+    pub string: u64,
+    pub null: u64,
+}
+
+impl std::fmt::Display for TypeCounts {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "integer:{} float:{} boolean:{} string:{} null:{}",
+            self.integer, self.float, self.boolean, self.string, self.null
+        )
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -86,10 +289,21 @@ pub fn parse_value(s: &str) -> (DataType, Value) {
         return (DataType::Integer, Value::Integer(i));
     }
 
+    // Integers beyond i64 range (e.g. 20-digit IDs) would lose precision as
+    // floats, so keep them as strings instead
+    if is_integer_literal(trimmed) {
+        return (DataType::String, Value::String);
+    }
+
     // Try float
     if let Ok(f) = trimmed.parse::<f64>()
         && f.is_finite()
     {
+        // 1e3 is the same whole number as 1000, so it types the same; past
+        // 2^53 floats no longer hold every integer and it stays a float
+        if is_scientific(trimmed) && f.fract() == 0.0 && f.abs() <= MAX_EXACT_INTEGER {
+            return (DataType::Integer, Value::Integer(f as i64));
+        }
         return (
             DataType::Float,
             Value::Float(f.to_bits() as i64, f.to_bits()),
@@ -99,15 +313,116 @@ pub fn parse_value(s: &str) -> (DataType, Value) {
     (DataType::String, Value::String)
 }
 
-pub fn is_null(s: &str) -> bool {
+/// 2^53; up to here an f64 holds every integer exactly
+pub const MAX_EXACT_INTEGER: f64 = 9_007_199_254_740_992.0;
+
+/// A number written with an exponent, like 1e3 or 2.5E-4
+pub fn is_scientific(s: &str) -> bool {
+    let s = s.trim();
+    s.contains(['e', 'E']) && s.parse::<f64>().is_ok_and(f64::is_finite)
+}
+
+fn is_integer_literal(s: &str) -> bool {
+    let digits = s.strip_prefix(['+', '-']).unwrap_or(s);
+    !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// An integer written with a leading zero, like a zip code ("01234"). Such
+/// columns are usually codes that would be corrupted by loading as numbers.
+pub fn has_leading_zero(s: &str) -> bool {
+    let digits = s.trim();
+    let digits = digits.strip_prefix(['+', '-']).unwrap_or(digits);
+    digits.len() > 1 && digits.starts_with('0') && is_integer_literal(digits)
+}
+
+/// Why a cell counts as null
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NullKind {
+    Empty,   // Blank or whitespace only
+    Literal, // null, NA or N/A, in any case
+}
+
+pub fn null_kind(s: &str) -> Option<NullKind> {
     let trimmed = s.trim().to_lowercase();
-    trimmed.is_empty() || trimmed == "null" || trimmed == "na" || trimmed == "n/a"
+    if trimmed.is_empty() {
+        Some(NullKind::Empty)
+    } else if trimmed == "null" || trimmed == "na" || trimmed == "n/a" {
+        Some(NullKind::Literal)
+    } else {
+        None
+    }
+}
+
+pub fn is_null(s: &str) -> bool {
+    null_kind(s).is_some()
+}
+
+/// A calendar date, ordered chronologically
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Date {
+    year: i32,
+    month: u32,
+    day: u32,
+}
+
+/// YYYY-MM-DD or YYYY/MM/DD. A time after `T` or a space is allowed and
+/// ignored, so timestamps compare by their day.
+pub fn parse_date(s: &str) -> Option<Date> {
+    let s = s.trim();
+    let day_part = s.split(['T', ' ']).next()?;
+    let sep = if day_part.contains('/') { '/' } else { '-' };
+    let mut parts = day_part.split(sep);
+    let (year, month, day) = (parts.next()?, parts.next()?, parts.next()?);
+    let all_digits = |p: &str, len: usize| p.len() == len && p.bytes().all(|b| b.is_ascii_digit());
+    if parts.next().is_some()
+        || !all_digits(year, 4)
+        || !all_digits(month, 2)
+        || !all_digits(day, 2)
+    {
+        return None;
+    }
+
+    let date = Date {
+        year: year.parse().ok()?,
+        month: month.parse().ok()?,
+        day: day.parse().ok()?,
+    };
+    let leap = date.year % 4 == 0 && (date.year % 100 != 0 || date.year % 400 == 0);
+    let days = match date.month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if leap => 29,
+        2 => 28,
+        _ => return None,
+    };
+    (1..=days).contains(&date.day).then_some(date)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_null_kind() {
+        assert_eq!(null_kind(""), Some(NullKind::Empty));
+        assert_eq!(null_kind("   "), Some(NullKind::Empty));
+        assert_eq!(null_kind("NULL"), Some(NullKind::Literal));
+        assert_eq!(null_kind(" n/a "), Some(NullKind::Literal));
+        assert_eq!(null_kind("none"), None);
+        assert!(is_null("NA"));
+    }
+
+    #[test]
+    fn test_has_leading_zero() {
+        assert!(has_leading_zero("01234"));
+        assert!(has_leading_zero(" 007 "));
+        assert!(has_leading_zero("-01"));
+        assert!(!has_leading_zero("0"));
+        assert!(!has_leading_zero("1200"));
+        assert!(!has_leading_zero("0.5"));
+        assert!(!has_leading_zero("0x1F"));
+    }
+
     #[test]
     fn test_parse_value_integer() {
         let (dtype, val) = parse_value("42");
@@ -135,6 +450,51 @@ mod tests {
         assert_eq!(dtype, DataType::Float);
     }
 
+    #[test]
+    fn test_parse_value_integer_overflow() {
+        let (dtype, val) = parse_value("12345678901234567890");
+        assert_eq!(dtype, DataType::String);
+        assert_eq!(val, Value::String);
+
+        let (dtype, _) = parse_value("-99999999999999999999");
+        assert_eq!(dtype, DataType::String);
+
+        // i64 boundaries still parse as integers
+        let (dtype, _) = parse_value("9223372036854775807");
+        assert_eq!(dtype, DataType::Integer);
+        let (dtype, _) = parse_value("-9223372036854775808");
+        assert_eq!(dtype, DataType::Integer);
+    }
+
+    #[test]
+    fn test_parse_value_scientific() {
+        // Whole numbers type the same however they're written
+        assert_eq!(
+            parse_value("1e3"),
+            (DataType::Integer, Value::Integer(1000))
+        );
+        assert_eq!(
+            parse_value("1E10"),
+            (DataType::Integer, Value::Integer(10_000_000_000))
+        );
+        assert_eq!(
+            parse_value("2.5e3"),
+            (DataType::Integer, Value::Integer(2500))
+        );
+        assert_eq!(parse_value("-4e0"), (DataType::Integer, Value::Integer(-4)));
+
+        assert_eq!(parse_value("2.5e-1").0, DataType::Float);
+        assert_eq!(parse_value("1.25E1").0, DataType::Float);
+        // Beyond 2^53 the digits aren't exact any more
+        assert_eq!(parse_value("1e20").0, DataType::Float);
+
+        assert!(is_scientific("1e3"));
+        assert!(is_scientific(" 2.5E-4 "));
+        assert!(!is_scientific("1000"));
+        assert!(!is_scientific("e3"));
+        assert!(!is_scientific("1e400"));
+    }
+
     #[test]
     fn test_parse_value_boolean() {
         let (dtype, val) = parse_value("true");
@@ -194,6 +554,15 @@ mod tests {
         assert!(!is_null("hello"));
     }
 
+    #[test]
+    fn test_datatype_from_str() {
+        assert_eq!(DataType::from_str("string").unwrap(), DataType::String);
+        assert_eq!(DataType::from_str("INT").unwrap(), DataType::Integer);
+        assert_eq!(DataType::from_str("float").unwrap(), DataType::Float);
+        assert_eq!(DataType::from_str("bool").unwrap(), DataType::Boolean);
+        assert!(DataType::from_str("date").is_err());
+    }
+
     #[test]
     fn test_datatype_display() {
         assert_eq!(format!("{}", DataType::Integer), "integer");
@@ -201,4 +570,24 @@ mod tests {
         assert_eq!(format!("{}", DataType::Boolean), "boolean");
         assert_eq!(format!("{}", DataType::String), "string");
     }
+
+    #[test]
+    fn test_parse_date() {
+        let date = |year, month, day| Some(Date { year, month, day });
+        assert_eq!(parse_date("2024-02-29"), date(2024, 2, 29));
+        assert_eq!(parse_date("2024/06/15"), date(2024, 6, 15));
+        assert_eq!(parse_date(" 2024-06-15T08:30:00Z "), date(2024, 6, 15));
+        assert_eq!(parse_date("2024-06-15 08:30"), date(2024, 6, 15));
+        for bad in [
+            "2023-02-29",
+            "2024-13-01",
+            "2024-04-31",
+            "15/06/2024",
+            "2024-6-5",
+            "",
+        ] {
+            assert_eq!(parse_date(bad), None, "{bad}");
+        }
+        assert!(parse_date("2024-02-01") < parse_date("2024-10-01"));
+    }
 }
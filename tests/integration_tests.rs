@@ -1,13 +1,55 @@
 use std::process::Command;
 
 fn csvp() -> Command {
-    Command::new(env!("CARGO_BIN_EXE_csvp"))
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_csvp"));
+    // Bare `csvp FILE` must mean summary whatever the caller's shell sets
+    cmd.env_remove("CSVP_DEFAULT_COMMAND");
+    cmd
 }
 
 fn fixtures_path(name: &str) -> String {
     format!("tests/fixtures/{name}")
 }
 
+/// A scratch file under the temp dir, unique to the test and the run
+fn temp_path(name: &str) -> String {
+    let name = format!("csvpeek_test_{}_{name}", std::process::id());
+    std::env::temp_dir()
+        .join(name)
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Output that must be one JSON document
+fn parse_json(output: &[u8]) -> serde_json::Value {
+    serde_json::from_slice(output).expect("Invalid JSON")
+}
+
+/// `csvp ARGS -f json`, which must succeed
+fn summary_json(args: &[&str]) -> serde_json::Value {
+    let output = csvp()
+        .args(args)
+        .args(["-f", "json"])
+        .output()
+        .expect("Failed to execute command");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    parse_json(&output.stdout)
+}
+
+/// The columns of a -f json summary, in order
+fn column_names(summary: &serde_json::Value) -> Vec<&str> {
+    summary
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|c| c["name"].as_str().unwrap())
+        .collect()
+}
+
 mod summary_command {
     use super::*;
 
@@ -75,7 +117,7 @@ mod summary_command {
 
         assert!(output.status.success());
         let stdout = String::from_utf8_lossy(&output.stdout);
-        assert!(stdout.contains("matched: 2"));
+        assert!(stdout.contains("rows: 5, matched: 2 (40.0%)"));
     }
 
     #[test]
@@ -130,43 +172,46 @@ mod summary_command {
     }
 
     #[test]
-    fn test_summary_with_nulls() {
+    fn test_summary_no_unique() {
         let output = csvp()
-            .arg(fixtures_path("with_nulls.csv"))
+            .arg(fixtures_path("basic.csv"))
+            .arg("--no-unique")
+            .arg("-f")
+            .arg("json")
             .output()
             .expect("Failed to execute command");
 
         assert!(output.status.success());
         let stdout = String::from_utf8_lossy(&output.stdout);
-        // Should show null rates
-        assert!(stdout.contains("%"));
+        assert!(stdout.contains("\"unique_count\": null"));
+        assert!(!stdout.contains("\"unique_count\": 5"));
     }
-}
-
-mod schema_command {
-    use super::*;
 
     #[test]
-    fn test_schema_basic() {
+    fn test_summary_no_percentiles() {
         let output = csvp()
-            .arg("schema")
             .arg(fixtures_path("basic.csv"))
+            .arg("--no-percentiles")
+            .arg("-c")
+            .arg("age")
+            .arg("-f")
+            .arg("json")
             .output()
             .expect("Failed to execute command");
 
         assert!(output.status.success());
         let stdout = String::from_utf8_lossy(&output.stdout);
-        assert!(stdout.contains("columns: 5"));
-        assert!(stdout.contains("integer"));
-        assert!(stdout.contains("string"));
-        assert!(stdout.contains("boolean"));
+        assert!(stdout.contains("\"median\": null"));
+        assert!(stdout.contains("\"p25\": null"));
+        assert!(stdout.contains("\"mean\": 30.0"));
     }
 
     #[test]
-    fn test_schema_json_format() {
+    fn test_summary_float_min_max_keep_decimal() {
         let output = csvp()
-            .arg("schema")
-            .arg(fixtures_path("basic.csv"))
+            .arg(fixtures_path("floats.csv"))
+            .arg("-c")
+            .arg("reading")
             .arg("-f")
             .arg("json")
             .output()
@@ -174,282 +219,3628 @@ mod schema_command {
 
         assert!(output.status.success());
         let stdout = String::from_utf8_lossy(&output.stdout);
-        assert!(stdout.contains("["));
-        assert!(stdout.contains("\"inferred_type\""));
+        assert!(stdout.contains("\"min\": \"-4.0\""));
+        assert!(stdout.contains("\"max\": \"3.0\""));
     }
 
     #[test]
-    fn test_schema_with_sample_values() {
+    fn test_summary_with_nulls() {
         let output = csvp()
-            .arg("schema")
-            .arg(fixtures_path("basic.csv"))
+            .arg(fixtures_path("with_nulls.csv"))
             .output()
             .expect("Failed to execute command");
 
         assert!(output.status.success());
         let stdout = String::from_utf8_lossy(&output.stdout);
-        // Should show sample values like Alice, Bob
-        assert!(stdout.contains("samples"));
+        // Should show null rates
+        assert!(stdout.contains("%"));
     }
-}
 
-mod delimiter_options {
-    use super::*;
+    #[test]
+    fn test_empty_and_null_literal_counts() {
+        for command in ["schema", "summary"] {
+            let output = csvp()
+                .arg(command)
+                .arg(fixtures_path("with_nulls.csv"))
+                .arg("-c")
+                .arg("age,city")
+                .arg("-f")
+                .arg("json")
+                .output()
+                .expect("Failed to execute command");
+
+            assert!(output.status.success());
+            let json = parse_json(&output.stdout);
+            // age: "" and NA; city: null, N/A and ""
+            assert_eq!(json[0]["empty_count"], 1);
+            assert_eq!(json[0]["null_literal_count"], 1);
+            assert_eq!(json[1]["empty_count"], 1);
+            assert_eq!(json[1]["null_literal_count"], 2);
+            assert_eq!(json[1]["null_count"], 3);
+        }
+    }
 
     #[test]
-    fn test_tab_delimiter() {
+    fn test_top_values_quote_special_chars() {
         let output = csvp()
-            .arg("-d")
-            .arg("tab")
-            .arg(fixtures_path("tab_separated.tsv"))
+            .arg(fixtures_path("special_values.csv"))
             .output()
             .expect("Failed to execute command");
 
         assert!(output.status.success());
         let stdout = String::from_utf8_lossy(&output.stdout);
-        assert!(stdout.contains("product"));
-        assert!(stdout.contains("quantity"));
+        assert!(stdout.contains("\"a,b\"(2)"));
+        assert!(stdout.contains("\"x(1)\"(1)"));
+        assert!(stdout.contains("\"say \\\"hi\\\"\"(1)"));
+        assert!(stdout.contains("\"two\\nlines\"(1)"));
+        assert!(stdout.contains("note: plain(5)"));
     }
 
     #[test]
-    fn test_tab_delimiter_backslash() {
+    fn test_locale_table_numbers() {
         let output = csvp()
-            .arg("-d")
-            .arg("\\t")
-            .arg(fixtures_path("tab_separated.tsv"))
+            .arg(fixtures_path("basic.csv"))
+            .arg("-c")
+            .arg("salary")
+            .arg("--locale")
+            .arg("de-DE")
             .output()
             .expect("Failed to execute command");
 
         assert!(output.status.success());
-    }
-}
-
-mod no_header_option {
-    use super::*;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("61.000,30"));
+        assert!(stdout.contains("75.000,25"));
 
-    #[test]
-    fn test_no_header() {
+        // CSV stays machine-parseable
         let output = csvp()
-            .arg("--no-header")
-            .arg(fixtures_path("no_header.csv"))
+            .arg(fixtures_path("basic.csv"))
+            .arg("-c")
+            .arg("salary")
+            .arg("--locale")
+            .arg("de-DE")
+            .arg("-f")
+            .arg("csv")
             .output()
             .expect("Failed to execute command");
 
-        assert!(output.status.success());
         let stdout = String::from_utf8_lossy(&output.stdout);
-        // Should generate col0, col1, col2, col3
-        assert!(stdout.contains("col0"));
-        assert!(stdout.contains("col1"));
-        assert!(stdout.contains("col2"));
-        assert!(stdout.contains("col3"));
+        assert!(stdout.contains("61000.300000"));
     }
-}
-
-mod filter_expressions {
-    use super::*;
 
     #[test]
-    fn test_filter_equals() {
+    fn test_weight_col() {
         let output = csvp()
-            .arg("summary")
-            .arg(fixtures_path("basic.csv"))
-            .arg("--where")
-            .arg("name == \"Alice\"")
+            .arg(fixtures_path("weighted.csv"))
+            .arg("--weight-col")
+            .arg("qty")
+            .arg("-f")
+            .arg("json")
             .output()
             .expect("Failed to execute command");
 
         assert!(output.status.success());
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        assert!(stdout.contains("matched: 1"));
+        let json = parse_json(&output.stdout);
+        // The row with a non-numeric weight is left out: (10*1 + 20*3) / 4
+        assert_eq!(json[0]["weighted_mean"], 17.5);
+        assert_eq!(json[0]["mean"], 20.0);
+        assert!(json[1]["weighted_mean"].is_null());
+
+        let output = csvp()
+            .arg(fixtures_path("weighted.csv"))
+            .arg("--weight-col")
+            .arg("quantity")
+            .output()
+            .expect("Failed to execute command");
+        assert!(!output.status.success());
     }
 
     #[test]
-    fn test_filter_greater_than() {
+    fn test_geo_and_harmonic_mean() {
         let output = csvp()
-            .arg("summary")
-            .arg(fixtures_path("basic.csv"))
-            .arg("--where")
-            .arg("age >= 30")
+            .arg(fixtures_path("weighted.csv"))
+            .arg("-c")
+            .arg("price")
+            .arg("-f")
+            .arg("json")
             .output()
             .expect("Failed to execute command");
 
         assert!(output.status.success());
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        assert!(stdout.contains("matched: 3"));
+        let json = parse_json(&output.stdout);
+        let geo = json[0]["geo_mean"].as_f64().unwrap();
+        let harmonic = json[0]["harmonic_mean"].as_f64().unwrap();
+        assert!((geo - 6000f64.cbrt()).abs() < 1e-9);
+        assert!((harmonic - 3.0 / (1.0 / 10.0 + 1.0 / 20.0 + 1.0 / 30.0)).abs() < 1e-9);
     }
 
     #[test]
-    fn test_filter_contains() {
+    fn test_geo_mean_requires_positive_values() {
         let output = csvp()
-            .arg("summary")
-            .arg(fixtures_path("basic.csv"))
-            .arg("--where")
-            .arg("contains(name, \"a\")")
+            .arg(fixtures_path("floats.csv"))
+            .arg("-f")
+            .arg("json")
             .output()
             .expect("Failed to execute command");
 
         assert!(output.status.success());
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        // Charlie, Diana have 'a'
-        assert!(stdout.contains("matched:"));
+        let json = parse_json(&output.stdout);
+        assert!(json[0]["geo_mean"].is_null());
+        assert!(json[0]["harmonic_mean"].is_null());
     }
 
     #[test]
-    fn test_filter_in() {
+    fn test_count_null_as_unique() {
+        let run = |extra: &[&str]| {
+            let output = csvp()
+                .arg(fixtures_path("with_nulls.csv"))
+                .arg("-c")
+                .arg("city")
+                .arg("-f")
+                .arg("json")
+                .args(extra)
+                .output()
+                .expect("Failed to execute command");
+            assert!(output.status.success());
+            let json = parse_json(&output.stdout);
+            json[0].clone()
+        };
+
+        // By default nulls are left out of the distinct values
+        let city = run(&[]);
+        assert_eq!(city["unique_count"], 3);
+        let top = city["top_values"].as_array().unwrap();
+        assert!(top.iter().all(|t| t[0].is_string()));
+
+        let city = run(&["--count-null-as-unique"]);
+        assert_eq!(city["unique_count"], 4);
+        assert_eq!(city["null_count"], 3);
+        assert_eq!(city["top_values"][0], serde_json::json!([null, 3]));
+    }
+
+    #[test]
+    fn test_null_bucket_apart_from_null_text() {
+        // Two cells read (null), two are nulls
+        let json = summary_json(&[&fixtures_path("null_text.csv"), "--count-null-as-unique"]);
+        assert_eq!(json[0]["unique_count"], 3);
+        assert_eq!(
+            json[0]["top_values"],
+            serde_json::json!([[null, 2], ["(null)", 2], ["a", 1]])
+        );
+
         let output = csvp()
-            .arg("summary")
-            .arg(fixtures_path("basic.csv"))
-            .arg("--where")
-            .arg("in(name, [\"Alice\", \"Bob\"])")
+            .arg(fixtures_path("null_text.csv"))
+            .arg("--count-null-as-unique")
             .output()
             .expect("Failed to execute command");
-
         assert!(output.status.success());
         let stdout = String::from_utf8_lossy(&output.stdout);
-        assert!(stdout.contains("matched: 2"));
-    }
+        assert!(stdout.contains("label: (null)(2), \"(null)\"(2), a(1)"));
 
-    #[test]
-    fn test_filter_is_null() {
         let output = csvp()
-            .arg("summary")
-            .arg(fixtures_path("with_nulls.csv"))
-            .arg("--where")
-            .arg("is_null(age)")
+            .arg(fixtures_path("null_text.csv"))
+            .args(["--count-null-as-unique", "-f", "csv"])
             .output()
             .expect("Failed to execute command");
-
         assert!(output.status.success());
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        // Bob and Diana have null ages
-        assert!(stdout.contains("matched:"));
+        assert!(String::from_utf8_lossy(&output.stdout).contains(",:2|(null):2|a:1,"));
     }
 
     #[test]
-    fn test_filter_logical_and() {
+    fn test_top_min_count() {
         let output = csvp()
-            .arg("summary")
-            .arg(fixtures_path("basic.csv"))
-            .arg("--where")
-            .arg("age > 25 && active == true")
+            .arg(fixtures_path("sales.csv"))
+            .args(["-c", "region,status", "-f", "json", "--top-min-count", "2"])
             .output()
             .expect("Failed to execute command");
-
         assert!(output.status.success());
+        let json = parse_json(&output.stdout);
+
+        // North is seen once; unique_count still counts it
+        let region = &json[0];
+        assert_eq!(region["unique_count"], 3);
+        let names: Vec<&str> = region["top_values"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|v| v[0].as_str().unwrap())
+            .collect();
+        assert_eq!(names.len(), 2);
+        assert!(!names.contains(&"North"));
+
+        let status = &json[1];
+        assert_eq!(status["top_values"][0], serde_json::json!(["open", 3]));
+        assert_eq!(status["top_values"][1], serde_json::json!(["closed", 2]));
     }
 
     #[test]
-    fn test_filter_matches_regex() {
+    fn test_top_pct() {
+        let run = |extra: &[&str]| {
+            let output = csvp()
+                .arg(fixtures_path("sales.csv"))
+                .args(["-c", "status"])
+                .args(extra)
+                .output()
+                .expect("Failed to execute command");
+            assert!(output.status.success());
+            String::from_utf8_lossy(&output.stdout).to_string()
+        };
+
+        let table = run(&[]);
+        assert!(table.contains("status: open(3), closed(2)"));
+        let table = run(&["--top-pct"]);
+        assert!(table.contains("status: open(3, 60.0%), closed(2, 40.0%)"));
+
+        let json = parse_json(run(&["-f", "json"]).as_bytes());
+        assert_eq!(json[0]["top_values_pct"], serde_json::json!([60.0, 40.0]));
+    }
+
+    #[test]
+    fn test_quickstats() {
         let output = csvp()
-            .arg("summary")
-            .arg(fixtures_path("basic.csv"))
-            .arg("--where")
-            .arg("matches(name, \"^[AB]\")")
+            .arg(fixtures_path("with_nulls.csv"))
+            .arg("--quickstats")
             .output()
             .expect("Failed to execute command");
-
         assert!(output.status.success());
         let stdout = String::from_utf8_lossy(&output.stdout);
-        // Alice and Bob start with A or B
-        assert!(stdout.contains("matched: 2"));
-    }
-}
+        let lines: Vec<&str> = stdout.lines().collect();
+        assert_eq!(lines.len(), 5);
+        assert_eq!(lines[0], "id\tinteger\t0.00\t6");
+        assert_eq!(lines[3], "city\tstring\t50.00\t3");
 
-mod error_handling {
-    use super::*;
-
-    #[test]
-    fn test_file_not_found() {
         let output = csvp()
-            .arg("nonexistent.csv")
+            .arg(fixtures_path("with_nulls.csv"))
+            .args(["--quickstats", "--no-unique", "-c", "age"])
             .output()
             .expect("Failed to execute command");
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "age\tinteger\t33.33\t-\n"
+        );
+    }
 
-        assert!(!output.status.success());
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        assert!(stderr.contains("not found") || stderr.contains("No such file"));
+    #[test]
+    fn test_estimated_percentiles() {
+        // 0..20000 shuffled, twice the values kept for exact percentiles
+        let path = &temp_path("percentiles.csv");
+        let mut data = String::from("value\n");
+        for i in 0..20_000u64 {
+            data.push_str(&format!("{}\n", i * 7919 % 20_000));
+        }
+        std::fs::write(path, data).unwrap();
+        let summary = |args: &[&str]| summary_json(&[&[path.as_str()], args].concat());
+
+        let estimated = summary(&[]);
+        let median = estimated[0]["median"].as_f64().unwrap();
+        let p75 = estimated[0]["p75"].as_f64().unwrap();
+        assert!((median - 9999.5).abs() < 100.0, "median {median}");
+        assert!((p75 - 14999.25).abs() < 150.0, "p75 {p75}");
+        assert!(estimated[0]["outlier_count"].is_null());
+
+        let exact = summary(&["--exact-percentiles"]);
+        assert_eq!(exact[0]["median"], 9999.5);
+        assert_eq!(exact[0]["outlier_count"], 0);
+
+        // Outliers need every value, so asking for them keeps them
+        let with_outliers = summary(&["--show-outliers"]);
+        assert_eq!(with_outliers[0]["median"], 9999.5);
+
+        std::fs::remove_file(path).ok();
     }
 
     #[test]
-    fn test_invalid_column() {
+    fn test_totals_footer() {
         let output = csvp()
-            .arg("summary")
-            .arg(fixtures_path("basic.csv"))
-            .arg("--cols")
-            .arg("nonexistent")
+            .arg(fixtures_path("with_nulls.csv"))
+            .arg("--totals")
             .output()
             .expect("Failed to execute command");
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("Totals:"));
+        assert!(stdout.contains("cells: 30, null: 7 (23.3%)"));
+        assert!(stdout.contains("columns: 3 numeric, 2 string, 0 boolean"));
+        assert!(stdout.contains("most null: city (50.0%)"));
 
-        assert!(!output.status.success());
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        assert!(stderr.contains("not found") || stderr.contains("Column"));
-    }
-
-    #[test]
-    fn test_column_index_out_of_range() {
+        // Off by default
         let output = csvp()
-            .arg("summary")
-            .arg(fixtures_path("basic.csv"))
-            .arg("--cols")
-            .arg("999")
+            .arg(fixtures_path("with_nulls.csv"))
             .output()
             .expect("Failed to execute command");
+        assert!(!String::from_utf8_lossy(&output.stdout).contains("Totals:"));
+    }
+
+    #[test]
+    fn test_nan_policy() {
+        let summary = |policy: &str| {
+            csvp()
+                .arg(fixtures_path("non_finite.csv"))
+                .args(["-c", "reading", "-f", "json", "--nan-policy", policy])
+                .output()
+                .expect("Failed to execute command")
+        };
+
+        let output = summary("ignore");
+        assert!(output.status.success());
+        let json = parse_json(&output.stdout);
+        assert_eq!(json[0]["data_type"], "float");
+        assert_eq!(json[0]["non_finite_count"], 3);
+        assert_eq!(json[0]["max"], "2.5");
+        assert_eq!(json[0]["mean"], 2.0);
+        assert_eq!(json[0]["median"], 2.0);
+
+        let output = summary("count");
+        assert!(output.status.success());
+        let json = parse_json(&output.stdout);
+        assert_eq!(json[0]["null_count"], 3);
+        assert_eq!(json[0]["non_finite_count"], 0);
+        assert_eq!(json[0]["mean"], 2.0);
 
+        let output = summary("error");
         assert!(!output.status.success());
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        assert!(stderr.contains("out of range") || stderr.contains("index"));
+        assert!(String::from_utf8_lossy(&output.stderr).contains("Non-finite value inf"));
     }
 
     #[test]
-    fn test_invalid_filter() {
+    fn test_nan_policy_skips_text_columns() {
+        let path = &temp_path("nan_text.csv");
+        std::fs::write(path, "word,code\nAlice,NaN\nNaN,Infinity\nInfinity,inf\n").unwrap();
+        let summary = |policy: &str| {
+            csvp()
+                .arg(path)
+                .args([
+                    "--types",
+                    "code=string",
+                    "-f",
+                    "json",
+                    "--nan-policy",
+                    policy,
+                ])
+                .output()
+                .expect("Failed to execute command")
+        };
+
+        // Inferred (word) and forced (code) text columns hold words, not numbers
+        let output = summary("error");
+        assert!(output.status.success());
+
+        let output = summary("count");
+        assert!(output.status.success());
+        let json = parse_json(&output.stdout);
+        for column in [&json[0], &json[1]] {
+            assert_eq!(column["data_type"], "string");
+            assert_eq!(column["null_count"], 0);
+            assert_eq!(column["unique_count"], 3);
+        }
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_string_extremes() {
+        let run = |extra: &[&str]| {
+            let output = csvp()
+                .arg(fixtures_path("basic.csv"))
+                .args(["-c", "name", "-f", "json"])
+                .args(extra)
+                .output()
+                .expect("Failed to execute command");
+            assert!(output.status.success());
+            let json = parse_json(&output.stdout);
+            (json[0]["min"].clone(), json[0]["max"].clone())
+        };
+
+        assert_eq!(run(&[]), ("Alice".into(), "Eve".into()));
+        // Bob and Eve are both shortest; the first one seen wins
+        assert_eq!(
+            run(&["--string-extremes", "length"]),
+            ("Bob".into(), "Charlie".into())
+        );
+
         let output = csvp()
-            .arg("summary")
             .arg(fixtures_path("basic.csv"))
-            .arg("--where")
-            .arg("invalid_column > 5")
+            .args(["--string-extremes", "longest"])
             .output()
             .expect("Failed to execute command");
-
         assert!(!output.status.success());
+        assert!(String::from_utf8_lossy(&output.stderr).contains("lexical, length"));
     }
-}
-
-mod quiet_option {
-    use super::*;
 
     #[test]
-    fn test_quiet_flag() {
+    fn test_integer_sum_is_exact() {
+        // 2^53 + 1 has no f64; an f64 sum would come out at ...984
+        let path = &temp_path("big_integers.csv");
+        std::fs::write(path, "id\n9007199254740993\n9007199254740993\n1\n").unwrap();
+
         let output = csvp()
-            .arg("-q")
-            .arg(fixtures_path("basic.csv"))
+            .arg(path)
+            .args(["-f", "json"])
             .output()
             .expect("Failed to execute command");
-
         assert!(output.status.success());
-        // Output should still contain results
         let stdout = String::from_utf8_lossy(&output.stdout);
-        assert!(stdout.contains("file:"));
-    }
-}
+        assert!(stdout.contains("\"sum\": 18014398509481987,"), "{stdout}");
 
-mod output_option {
-    use super::*;
-    use std::fs;
+        let output = csvp()
+            .arg(path)
+            .args(["-f", "csv", "--precision-loss-warning"])
+            .output()
+            .expect("Failed to execute command");
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains(",18014398509481987,"), "{stdout}");
+        assert!(stdout.contains("past ±2^53 in 2 value(s)"), "{stdout}");
 
-    #[test]
-    fn test_output_to_file() {
-        let output_file = "/tmp/csvpeek_test_output.txt";
+        // Without the flag, no note
+        let output = csvp()
+            .arg(path)
+            .output()
+            .expect("Failed to execute command");
+        assert!(!String::from_utf8_lossy(&output.stdout).contains("2^53"));
+        std::fs::remove_file(path).ok();
+    }
 
+    #[test]
+    fn test_compare_to_baseline() {
+        let baseline = &temp_path("baseline.json");
         let output = csvp()
-            .arg("-o")
-            .arg(output_file)
             .arg(fixtures_path("basic.csv"))
+            .args(["-f", "json", "-o", baseline])
             .output()
             .expect("Failed to execute command");
-
         assert!(output.status.success());
 
-        // Check file was created and has content
+        let output = csvp()
+            .arg(fixtures_path("with_nulls.csv"))
+            .args(["--compare-to", baseline, "--width", "200"])
+            .output()
+            .expect("Failed to execute command");
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let age = stdout.lines().find(|l| l.starts_with("| age")).unwrap();
+        assert!(age.contains("4 (-1)"), "{age}");
+        assert!(age.contains("33.3% (+33.3pp)"), "{age}");
+        assert!(age.contains("30.00 (±0.00)"), "{age}");
+        // Columns the baseline doesn't have are shown as they are
+        let city = stdout.lines().find(|l| l.starts_with("| city")).unwrap();
+        assert!(!city.contains('('), "{city}");
+
+        let output = csvp()
+            .arg(fixtures_path("with_nulls.csv"))
+            .args(["--compare-to", baseline, "-f", "json"])
+            .output()
+            .expect("Failed to execute command");
+        let json = parse_json(&output.stdout);
+        assert_eq!(json[0]["delta"]["count"], 1);
+        assert_eq!(json[0]["delta"]["mean"], 0.5);
+        assert!(json[3].get("delta").is_none());
+
+        // Only a summary saved with -f json works as a baseline
+        let output = csvp()
+            .arg(fixtures_path("basic.csv"))
+            .args(["--compare-to", &fixtures_path("basic.csv")])
+            .output()
+            .expect("Failed to execute command");
+        assert!(!output.status.success());
+        assert!(
+            String::from_utf8_lossy(&output.stderr).contains("not a summary saved with -f json")
+        );
+        std::fs::remove_file(baseline).ok();
+    }
+
+    #[test]
+    fn test_json_output_is_stable() {
+        let run = || {
+            csvp()
+                .arg(fixtures_path("sales.csv"))
+                .args(["-g", "region", "-f", "json"])
+                .output()
+                .expect("Failed to execute command")
+                .stdout
+        };
+        let first = run();
+        for _ in 0..5 {
+            assert_eq!(run(), first);
+        }
+
+        // Top values tied on count come in value order
+        let output = csvp()
+            .arg(fixtures_path("basic.csv"))
+            .args(["-c", "age", "-f", "json"])
+            .output()
+            .expect("Failed to execute command");
+        let json = parse_json(&output.stdout);
+        let values: Vec<&str> = json[0]["top_values"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|v| v[0].as_str().unwrap())
+            .collect();
+        assert_eq!(values, ["25", "28", "30", "32", "35"]);
+    }
+
+    #[test]
+    fn test_stats_selection() {
+        let output = csvp()
+            .arg(fixtures_path("basic.csv"))
+            .args(["-c", "age,name", "--stats", "count,mean,outliers"])
+            .output()
+            .expect("Failed to execute command");
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let header = stdout.lines().find(|l| l.contains("column")).unwrap();
+        let columns: Vec<&str> = header
+            .split('|')
+            .map(str::trim)
+            .filter(|c| !c.is_empty())
+            .collect();
+        assert_eq!(columns, ["column", "type", "count", "mean", "outliers"]);
+        assert!(!stdout.contains("Top values:"));
+
+        let output = csvp()
+            .arg(fixtures_path("basic.csv"))
+            .args(["-c", "age", "--stats", "mean", "-f", "json"])
+            .output()
+            .expect("Failed to execute command");
+        assert!(output.status.success());
+        let json = parse_json(&output.stdout);
+        assert_eq!(json[0]["mean"], 30.0);
+        assert!(json[0]["median"].is_null());
+        assert!(json[0]["unique_count"].is_null());
+        assert!(json[0]["top_values"].is_null());
+
+        let output = csvp()
+            .arg(fixtures_path("basic.csv"))
+            .args(["--stats", "mean,p100"])
+            .output()
+            .expect("Failed to execute command");
+        assert!(!output.status.success());
+        assert!(String::from_utf8_lossy(&output.stderr).contains("Unknown statistic: p100"));
+    }
+
+    #[test]
+    fn test_stats_percentiles() {
+        let basic = fixtures_path("basic.csv");
+        let output = csvp()
+            .arg(&basic)
+            .args(["-c", "age", "--stats", "count,null_rate,mean,p95"])
+            .output()
+            .expect("Failed to execute command");
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let header = stdout.lines().find(|l| l.contains("column")).unwrap();
+        let columns: Vec<&str> = header
+            .split('|')
+            .map(str::trim)
+            .filter(|c| !c.is_empty())
+            .collect();
+        assert_eq!(columns, ["column", "type", "count", "null%", "mean", "p95"]);
+        assert!(stdout.contains("| 34.40 |"));
+
+        // Ascending whatever the order given; p05 is p5
+        let json = summary_json(&[&basic, "-c", "age", "--stats", "p95,p05"]);
+        assert_eq!(
+            json[0]["percentiles"],
+            serde_json::json!([["p5", 25.6], ["p95", 34.4]])
+        );
+        assert!(json[0]["median"].is_null());
+
+        let output = csvp()
+            .arg(&basic)
+            .args(["-c", "age", "--stats", "p95,p99", "-f", "csv"])
+            .output()
+            .expect("Failed to execute command");
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.lines().next().unwrap().ends_with(",percentiles"));
+        assert!(
+            stdout
+                .lines()
+                .nth(1)
+                .unwrap()
+                .ends_with(",p95:34.400000|p99:34.880000")
+        );
+    }
+
+    #[test]
+    fn test_first_and_last_values() {
+        let output = csvp()
+            .arg(fixtures_path("with_nulls.csv"))
+            .args(["-c", "age,city", "-f", "json"])
+            .output()
+            .expect("Failed to execute command");
+        assert!(output.status.success());
+        let json = parse_json(&output.stdout);
+        // Nulls are skipped at both ends
+        assert_eq!(json[0]["first_value"], "25");
+        assert_eq!(json[0]["last_value"], "28");
+        assert_eq!(json[1]["first_value"], "Tokyo");
+        assert_eq!(json[1]["last_value"], "Nagoya");
+
+        let output = csvp()
+            .arg(fixtures_path("with_nulls.csv"))
+            .args(["-c", "city", "--stats", "first,last"])
+            .output()
+            .expect("Failed to execute command");
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let row = stdout.lines().find(|l| l.contains("city")).unwrap();
+        let cells: Vec<&str> = row
+            .split('|')
+            .map(str::trim)
+            .filter(|c| !c.is_empty())
+            .collect();
+        assert_eq!(cells, ["city", "string", "Tokyo", "Nagoya"]);
+    }
+
+    #[test]
+    fn test_boolean_stats() {
+        let output = csvp()
+            .arg(fixtures_path("basic.csv"))
+            .arg("-c")
+            .arg("age,active")
+            .arg("-f")
+            .arg("json")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        let json = parse_json(&output.stdout);
+        assert!(json[0]["true_count"].is_null());
+        assert_eq!(json[1]["true_count"], 3);
+        assert_eq!(json[1]["false_count"], 2);
+        assert_eq!(json[1]["true_rate"], 60.0);
+
+        let output = csvp()
+            .arg(fixtures_path("basic.csv"))
+            .arg("-c")
+            .arg("age,active")
+            .output()
+            .expect("Failed to execute command");
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("Booleans:\n  active: 60.0% true (3 true, 2 false)"));
+    }
+
+    #[test]
+    fn test_cols_match() {
+        let basic = fixtures_path("basic.csv");
+        let json = summary_json(&[&basic, "--cols-match", "^(a|s)"]);
+        assert_eq!(column_names(&json), ["age", "salary", "active"]);
+        // Union with -c, without repeating a column
+        let json = summary_json(&[&basic, "-c", "age,id", "--cols-match", "^a"]);
+        assert_eq!(column_names(&json), ["age", "id", "active"]);
+
+        let output = csvp()
+            .arg(fixtures_path("basic.csv"))
+            .arg("--cols-match")
+            .arg("^metric_")
+            .output()
+            .expect("Failed to execute command");
+        assert!(!output.status.success());
+        assert!(String::from_utf8_lossy(&output.stderr).contains("matched no columns"));
+    }
+
+    #[test]
+    fn test_cols_glob() {
+        let basic = fixtures_path("basic.csv");
+        let json = summary_json(&[&basic, "-c", "a*"]);
+        assert_eq!(column_names(&json), ["age", "active"]);
+        let json = summary_json(&[&basic, "-c", "*e"]);
+        assert_eq!(column_names(&json), ["name", "age", "active"]);
+        let json = summary_json(&[&basic, "-c", "id,*a*y"]);
+        assert_eq!(column_names(&json), ["id", "salary"]);
+
+        let output = csvp()
+            .arg(fixtures_path("basic.csv"))
+            .args(["-c", "metric_*"])
+            .output()
+            .expect("Failed to execute command");
+        assert!(!output.status.success());
+        assert!(
+            String::from_utf8_lossy(&output.stderr)
+                .contains("No columns match 'metric_*' (available: id, name, age, salary, active)")
+        );
+    }
+
+    #[test]
+    fn test_cols_file() {
+        let json = summary_json(&[
+            &fixtures_path("basic.csv"),
+            "-c",
+            "age",
+            "--cols-file",
+            &fixtures_path("basic_cols.txt"),
+        ]);
+        // -c first, then the file's entries in order
+        assert_eq!(
+            column_names(&json),
+            ["age", "salary", "id", "name", "active"]
+        );
+
+        let output = csvp()
+            .arg(fixtures_path("basic.csv"))
+            .args(["--cols-file", "tests/fixtures/missing_cols.txt"])
+            .output()
+            .expect("Failed to execute command");
+        assert!(!output.status.success());
+        assert!(String::from_utf8_lossy(&output.stderr).contains("Could not read --cols-file"));
+    }
+
+    #[test]
+    fn test_table_width() {
+        let widest = |extra: &[&str]| {
+            let output = csvp()
+                .arg(fixtures_path("basic.csv"))
+                .args([
+                    "--show-outliers",
+                    "--precision-detect",
+                    "--weight-col",
+                    "age",
+                ])
+                .args(extra)
+                .output()
+                .expect("Failed to execute command");
+            assert!(output.status.success());
+            String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .filter(|l| l.starts_with(['+', '|']))
+                .map(|l| l.chars().count())
+                .max()
+                .unwrap()
+        };
+
+        // Not a terminal, so 120 unless --width says otherwise
+        assert_eq!(widest(&[]), 120);
+        assert_eq!(widest(&["--width", "80"]), 80);
+    }
+
+    #[test]
+    fn test_max_cell_width() {
+        let run = |args: &[&str]| {
+            let output = csvp()
+                .arg(fixtures_path("long_text.csv"))
+                .args(args)
+                .output()
+                .expect("Failed to execute command");
+            assert!(output.status.success());
+            String::from_utf8_lossy(&output.stdout).to_string()
+        };
+        let long = "x".repeat(60);
+
+        // Cut to 40 graphemes; e + combining acute counts as one
+        let table = run(&["--width", "250"]);
+        assert!(table.contains(&format!("{}…(1)", "x".repeat(39))));
+        assert!(table.contains(&format!("{}…(1)", "e\u{301}".repeat(39))));
+        assert!(!table.contains(&long));
+
+        let table = run(&["--width", "250", "--max-cell-width", "10"]);
+        assert!(table.contains(&format!("{}…(1)", "x".repeat(9))));
+        assert!(table.contains("short(1)"));
+
+        let table = run(&["--width", "250", "--max-cell-width", "0"]);
+        assert!(table.contains(&format!("{long}(1)")));
+
+        // JSON keeps the full value
+        let json = parse_json(run(&["-f", "json"]).as_bytes());
+        assert!(json.to_string().contains(&long));
+    }
+
+    #[test]
+    fn test_preserve_order() {
+        let basic = fixtures_path("basic.csv");
+        let json = summary_json(&[&basic, "-c", "salary,name,0"]);
+        assert_eq!(column_names(&json), ["salary", "name", "id"]);
+        let json = summary_json(&[&basic, "-c", "salary,name,0", "--preserve-order"]);
+        assert_eq!(column_names(&json), ["id", "name", "salary"]);
+    }
+
+    #[test]
+    fn test_abbreviate_option() {
+        let path = &temp_path("abbreviate.csv");
+        std::fs::write(path, "amount\n1234567890\n45600000\n-12300\n999999\n").unwrap();
+
+        let output = csvp()
+            .arg(path)
+            .args(["--abbreviate", "--stats", "min,max,median"])
+            .output()
+            .expect("Failed to execute command");
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let row = stdout.lines().find(|l| l.contains("amount")).unwrap();
+        let cells: Vec<&str> = row
+            .split('|')
+            .map(str::trim)
+            .filter(|c| !c.is_empty())
+            .collect();
+        assert_eq!(cells, ["amount", "integer", "-12.3K", "1.23G", "23.3M"]);
+
+        // CSV keeps exact values
+        let output = csvp()
+            .arg(path)
+            .args(["--abbreviate", "-f", "csv"])
+            .output()
+            .expect("Failed to execute command");
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains(",-12300,1234567890,"));
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_round_option() {
+        let output = csvp()
+            .arg(fixtures_path("basic.csv"))
+            .arg("-c")
+            .arg("age,salary")
+            .arg("--round")
+            .arg("3")
+            .arg("-f")
+            .arg("csv")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        // Whole numbers in integer columns get no spurious decimals
+        assert!(stdout.contains("age,integer,5,0,0.00,5,25,35,30,30,28,32,150,3.808,"));
+        assert!(stdout.contains(",61000.300,60000.000,"));
+
+        let output = csvp()
+            .arg(fixtures_path("basic.csv"))
+            .arg("-c")
+            .arg("age")
+            .arg("--round")
+            .arg("1")
+            .arg("-f")
+            .arg("json")
+            .output()
+            .expect("Failed to execute command");
+
+        let json = parse_json(&output.stdout);
+        assert_eq!(json[0]["std"], 3.8);
+    }
+
+    #[test]
+    fn test_cardinality_ratio() {
+        let output = csvp()
+            .arg(fixtures_path("basic.csv"))
+            .arg("-c")
+            .arg("id,active")
+            .arg("-f")
+            .arg("json")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        let json = parse_json(&output.stdout);
+        assert_eq!(json[0]["cardinality_ratio"], 1.0);
+        assert_eq!(json[1]["cardinality_ratio"], 0.4);
+
+        let output = csvp()
+            .arg(fixtures_path("basic.csv"))
+            .arg("-c")
+            .arg("active")
+            .output()
+            .expect("Failed to execute command");
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("unique%"));
+        assert!(stdout.contains("40.0%"));
+    }
+
+    #[test]
+    fn test_flag_issues() {
+        let output = csvp()
+            .arg(fixtures_path("column_issues.csv"))
+            .arg("--flag-issues")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("Warnings:"));
+        assert!(stdout.contains("column 'id' is all unique"));
+        assert!(stdout.contains("column 'status' is constant"));
+        assert!(stdout.contains("column 'comment' is 100% null"));
+        assert!(!stdout.contains("column 'category'"));
+
+        let output = csvp()
+            .arg(fixtures_path("column_issues.csv"))
+            .arg("--flag-issues")
+            .arg("-f")
+            .arg("json")
+            .output()
+            .expect("Failed to execute command");
+
+        let json = parse_json(&output.stdout);
+        assert_eq!(json[1]["notes"][0], "constant");
+        assert!(json[3].get("notes").is_none());
+
+        // Without the flag nothing is reported
+        let output = csvp()
+            .arg(fixtures_path("column_issues.csv"))
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(!String::from_utf8_lossy(&output.stdout).contains("Warnings:"));
+    }
+
+    #[test]
+    fn test_outlier_count() {
+        let output = csvp()
+            .arg(fixtures_path("outliers.csv"))
+            .arg("-f")
+            .arg("json")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        let json = parse_json(&output.stdout);
+        assert_eq!(json[0]["outlier_count"], 0);
+        assert_eq!(json[1]["outlier_count"], 2); // 250 and -90
+
+        let output = csvp()
+            .arg(fixtures_path("outliers.csv"))
+            .arg("--show-outliers")
+            .output()
+            .expect("Failed to execute command");
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("| outliers |"));
+
+        // The table only shows the column when asked
+        let output = csvp()
+            .arg(fixtures_path("outliers.csv"))
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(!String::from_utf8_lossy(&output.stdout).contains("| outliers |"));
+    }
+
+    #[test]
+    fn test_max_decimals() {
+        let output = csvp()
+            .arg(fixtures_path("precision.csv"))
+            .arg("-f")
+            .arg("json")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        let json = parse_json(&output.stdout);
+        assert_eq!(json[0]["max_decimals"], 3);
+        assert_eq!(json[1]["max_decimals"], 4); // 1.5e-3 is 0.0015
+        assert_eq!(json[2]["max_decimals"], 0);
+
+        let output = csvp()
+            .arg(fixtures_path("precision.csv"))
+            .arg("--precision-detect")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(String::from_utf8_lossy(&output.stdout).contains("| decimals |"));
+    }
+
+    #[test]
+    fn test_preview_head_and_tail() {
+        let output = csvp()
+            .arg(fixtures_path("sequence.csv"))
+            .arg("--preview")
+            .arg("2")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("Preview (first 2, last 2, 16 rows between):"));
+        let preview = &stdout[..stdout.find("| column").unwrap()];
+        for row in ["| 1 ", "| 2 ", "| ... ", "| 19 ", "| 20 "] {
+            assert!(preview.contains(row), "missing {row}");
+        }
+        assert!(!preview.contains("| 3 "));
+        assert!(!preview.contains("| 18 "));
+    }
+
+    #[test]
+    fn test_preview_short_file() {
+        let output = csvp()
+            .arg("schema")
+            .arg(fixtures_path("basic.csv"))
+            .arg("--preview")
+            .arg("3")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        // Five rows fit in head + tail, so nothing is elided or repeated
+        assert!(stdout.contains("Preview:"));
+        assert!(!stdout.contains("..."));
+        assert_eq!(stdout.matches("| Charlie ").count(), 1);
+    }
+
+    #[test]
+    fn test_preview_huge_size() {
+        let output = csvp()
+            .arg(fixtures_path("basic.csv"))
+            .arg("--preview")
+            .arg("10000000000000")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("Preview:"));
+        assert_eq!(stdout.matches("| Charlie ").count(), 1);
+    }
+
+    #[test]
+    fn test_top_values_csv_column() {
+        let output = csvp()
+            .arg(fixtures_path("special_values.csv"))
+            .arg("-c")
+            .arg("note")
+            .arg("-f")
+            .arg("csv")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let mut lines = stdout.lines();
+        // Appended after the original columns, which keep their positions
+        let header: Vec<&str> = lines.next().unwrap().split(',').collect();
+        assert_eq!(header[15], "max_len");
+        assert_eq!(header[16], "top_values");
+        assert_eq!(lines.next().unwrap().split(',').nth(16), Some("plain:5"));
+    }
+
+    #[test]
+    fn test_top_values_quote_output_modes() {
+        let output = csvp()
+            .arg(fixtures_path("special_values.csv"))
+            .arg("--quote-output")
+            .arg("always")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("note: \"plain\"(5)"));
+
+        let output = csvp()
+            .arg(fixtures_path("special_values.csv"))
+            .arg("--quote-output")
+            .arg("never")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("a,b(2)"));
+    }
+}
+
+mod type_overrides {
+    use super::*;
+
+    #[test]
+    fn test_types_override_summary() {
+        let output = csvp()
+            .arg(fixtures_path("zip_codes.csv"))
+            .arg("--types")
+            .arg("zip=string")
+            .arg("-f")
+            .arg("csv")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        // Leading zeros survive because min/max are compared as strings
+        assert!(stdout.contains("zip,string,3,0,0.00,3,00501,98765"));
+        assert!(stdout.contains("amount,integer"));
+    }
+
+    #[test]
+    fn test_types_override_schema() {
+        let output = csvp()
+            .arg("schema")
+            .arg(fixtures_path("zip_codes.csv"))
+            .arg("--types")
+            .arg("zip=string,1=float")
+            .arg("-f")
+            .arg("csv")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("zip,string"));
+        assert!(stdout.contains("flag,float"));
+        assert!(stdout.contains("amount,integer"));
+    }
+
+    #[test]
+    fn test_scientific_notation() {
+        let schema = |extra: &[&str]| {
+            let output = csvp()
+                .arg("schema")
+                .arg(fixtures_path("scientific.csv"))
+                .args(["-f", "csv"])
+                .args(extra)
+                .output()
+                .expect("Failed to execute command");
+            assert!(output.status.success());
+            String::from_utf8_lossy(&output.stdout).to_string()
+        };
+
+        // 1000, 1e3 and 2.5e3 are all whole numbers
+        let stdout = schema(&[]);
+        assert!(stdout.contains("amount,integer"));
+        assert!(stdout.contains("ratio,float"));
+        assert!(stdout.contains("id,integer"));
+
+        let stdout = schema(&["--sci-as-float"]);
+        assert!(stdout.contains("amount,float"));
+        assert!(stdout.contains("id,float"));
+
+        let output = csvp()
+            .arg(fixtures_path("scientific.csv"))
+            .args(["-c", "amount", "-f", "json"])
+            .output()
+            .expect("Failed to execute command");
+        assert!(output.status.success());
+        let json = parse_json(&output.stdout);
+        assert_eq!(json[0]["data_type"], "integer");
+        assert_eq!(json[0]["min"], "1000");
+        assert_eq!(json[0]["max"], "2500");
+
+        let output = csvp()
+            .arg(fixtures_path("scientific.csv"))
+            .args(["-c", "amount", "-f", "json", "--sci-as-float"])
+            .output()
+            .expect("Failed to execute command");
+        let json = parse_json(&output.stdout);
+        assert_eq!(json[0]["data_type"], "float");
+    }
+
+    #[test]
+    fn test_types_override_invalid_type() {
+        let output = csvp()
+            .arg(fixtures_path("zip_codes.csv"))
+            .arg("--types")
+            .arg("zip=date")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(!output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("Unknown type"));
+    }
+}
+
+mod group_by_option {
+    use super::*;
+
+    #[test]
+    fn test_group_by_table() {
+        let output = csvp()
+            .arg(fixtures_path("sales.csv"))
+            .arg("--group-by")
+            .arg("region")
+            .arg("-c")
+            .arg("sales")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("group by: region (3)"));
+        assert!(stdout.contains("group: region = East (rows: 2)"));
+        assert!(stdout.contains("group: region = West (rows: 2)"));
+        assert!(stdout.contains("group: region = North (rows: 1)"));
+    }
+
+    #[test]
+    fn test_group_by_csv_long_format() {
+        let output = csvp()
+            .arg(fixtures_path("sales.csv"))
+            .arg("-g")
+            .arg("region")
+            .arg("-c")
+            .arg("sales")
+            .arg("-f")
+            .arg("csv")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.starts_with("group,column,type"));
+        assert!(stdout.contains("East,sales,float,2"));
+        assert!(stdout.contains("West,sales,integer,2"));
+    }
+
+    #[test]
+    fn test_group_by_unknown_column() {
+        let output = csvp()
+            .arg(fixtures_path("sales.csv"))
+            .arg("-g")
+            .arg("regin")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(!output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("region"));
+    }
+
+    #[test]
+    fn test_group_by_other_commands() {
+        for command in ["schema", "profile"] {
+            // Also with the options that answer without running the command
+            for extra in [
+                &[][..],
+                &["--check"],
+                &["--encoding-report"],
+                &["--examples"],
+            ] {
+                let output = csvp()
+                    .arg(command)
+                    .arg(fixtures_path("sales.csv"))
+                    .args(["-g", "region"])
+                    .args(extra)
+                    .output()
+                    .expect("Failed to execute command");
+
+                assert!(!output.status.success(), "{command} {extra:?}");
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                assert!(stderr.contains(&format!("--group-by is not supported by {command}")));
+            }
+        }
+    }
+
+    #[test]
+    fn test_max_groups() {
+        let output = csvp()
+            .arg(fixtures_path("sales.csv"))
+            .args(["-g", "region", "-c", "sales", "--max-groups", "2"])
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(!output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("Too many groups in column 'region' (max: 2)"));
+
+        // a has the fewest rows though it comes first, so it is pooled
+        let output = csvp()
+            .arg(fixtures_path("group_sizes.csv"))
+            .args(["-g", "key", "-c", "value", "--max-groups", "2"])
+            .args(["--group-overflow", "top", "-f", "csv"])
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let groups: Vec<(&str, &str)> = stdout
+            .lines()
+            .skip(1)
+            .map(|l| {
+                let mut cells = l.split(',');
+                (cells.next().unwrap(), cells.nth(2).unwrap())
+            })
+            .collect();
+        assert_eq!(groups, [("b", "2"), ("c", "3"), ("(other)", "2")]);
+
+        // The counts are of the rows the filter keeps
+        let output = csvp()
+            .arg(fixtures_path("group_sizes.csv"))
+            .args(["-g", "key", "-c", "value", "--max-groups", "1"])
+            .args(["--group-overflow", "top", "-f", "csv", "-w", "value < 3"])
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.lines().nth(1).unwrap().starts_with("a,"), "{stdout}");
+
+        let output = csvp()
+            .arg(fixtures_path("group_sizes.csv"))
+            .args(["-g", "key", "--group-overflow", "top", "--follow"])
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(!output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("doesn't work with --follow"));
+    }
+}
+
+mod profile_command {
+    use super::*;
+
+    #[test]
+    fn test_profile_table() {
+        let output = csvp()
+            .arg("profile")
+            .arg(fixtures_path("basic.csv"))
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("rows: 5\n"));
+        assert!(!stdout.contains("matched:"));
+        let schema_at = stdout.find("Schema:").unwrap();
+        let stats_at = stdout.find("Statistics:").unwrap();
+        assert!(schema_at < stats_at);
+        assert!(stdout[schema_at..stats_at].contains("samples"));
+        assert!(stdout[stats_at..].contains("median"));
+    }
+
+    #[test]
+    fn test_profile_json() {
+        let output = csvp()
+            .arg("profile")
+            .arg(fixtures_path("basic.csv"))
+            .arg("-c")
+            .arg("age,salary")
+            .arg("-w")
+            .arg("active == true")
+            .arg("-f")
+            .arg("json")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        let json = parse_json(&output.stdout);
+        assert_eq!(json["rows"], 5);
+        assert_eq!(json["matched"], 3);
+        assert_eq!(json["schema"].as_array().unwrap().len(), 2);
+        assert_eq!(json["schema"][1]["inferred_type"], "float");
+        assert_eq!(json["summary"][0]["name"], "age");
+        assert_eq!(json["summary"][0]["count"], 3);
+    }
+
+    #[test]
+    fn test_profile_rejects_csv() {
+        let output = csvp()
+            .arg("profile")
+            .arg(fixtures_path("basic.csv"))
+            .arg("-f")
+            .arg("csv")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(!output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("not supported for profile"));
+    }
+}
+
+mod pivot_command {
+    use super::*;
+
+    #[test]
+    fn test_pivot_count() {
+        let output = csvp()
+            .arg("pivot")
+            .arg(fixtures_path("sales.csv"))
+            .arg("--rows")
+            .arg("region")
+            .arg("-c")
+            .arg("status")
+            .arg("-f")
+            .arg("csv")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert_eq!(
+            stdout,
+            "region,closed,open\nEast,1,1\nNorth,0,1\nWest,1,1\n"
+        );
+    }
+
+    #[test]
+    fn test_pivot_sum_sparse() {
+        let output = csvp()
+            .arg("pivot")
+            .arg(fixtures_path("sales.csv"))
+            .arg("--rows")
+            .arg("region")
+            .arg("-c")
+            .arg("status")
+            .arg("--value")
+            .arg("sales")
+            .arg("--agg")
+            .arg("sum")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("pivot: region x status (value: sum(sales))"));
+        assert!(stdout.contains("7.50"));
+        assert!(stdout.contains("| North  | -"));
+    }
+
+    #[test]
+    fn test_pivot_sum_requires_value() {
+        let output = csvp()
+            .arg("pivot")
+            .arg(fixtures_path("sales.csv"))
+            .arg("--rows")
+            .arg("region")
+            .arg("-c")
+            .arg("status")
+            .arg("--agg")
+            .arg("sum")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(!output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("--value"));
+    }
+}
+
+mod freq_command {
+    use super::*;
+
+    fn freq_json(args: &[&str]) -> serde_json::Value {
+        summary_json(&[&["freq"], args].concat())
+    }
+
+    #[test]
+    fn test_freq_values() {
+        let freq = freq_json(&[&fixtures_path("sales.csv"), "-c", "region"]);
+        assert_eq!(freq["rows"], 5);
+        assert!(freq["bin_width"].is_null());
+        let values = freq["values"].as_array().unwrap();
+        // East and West twice, ties by value
+        assert_eq!(values[0]["value"], "East");
+        assert_eq!(values[0]["count"], 2);
+        assert_eq!(values[0]["percent"], 40.0);
+        assert_eq!(values[1]["value"], "West");
+        assert_eq!(values[2]["value"], "North");
+    }
+
+    #[test]
+    fn test_freq_bin_width() {
+        let freq = freq_json(&[&fixtures_path("basic.csv"), "-c", "age", "--bin-width", "5"]);
+        assert_eq!(freq["bin_width"], 5.0);
+        let buckets: Vec<(String, u64)> = freq["values"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|v| {
+                (
+                    v["value"].as_str().unwrap().to_string(),
+                    v["count"].as_u64().unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            buckets,
+            [
+                ("[25, 30)".to_string(), 2),
+                ("[30, 35)".to_string(), 2),
+                ("[35, 40)".to_string(), 1)
+            ]
+        );
+
+        // Non-numeric columns are counted per value
+        let freq = freq_json(&[
+            &fixtures_path("basic.csv"),
+            "-c",
+            "name",
+            "--bin-width",
+            "5",
+        ]);
+        assert!(freq["bin_width"].is_null());
+        assert_eq!(freq["values"].as_array().unwrap().len(), 5);
+    }
+
+    #[test]
+    fn test_freq_bin_width_huge_value() {
+        let path = &temp_path("freq_huge.csv");
+        std::fs::write(path, "x\n1\n1e300\n").unwrap();
+        let output = csvp()
+            .args(["freq", path, "-c", "x", "--bin-width", "1"])
+            .output()
+            .expect("Failed to execute command");
+        std::fs::remove_file(path).ok();
+
+        assert!(!output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("too far from 0"), "{stderr}");
+        assert!(!stderr.contains("panicked"));
+    }
+
+    #[test]
+    fn test_freq_nulls_and_csv() {
+        let output = csvp()
+            .arg("freq")
+            .arg(fixtures_path("with_nulls.csv"))
+            .args(["-c", "city", "-f", "csv"])
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.starts_with("city,count,percent\n"));
+        assert!(stdout.ends_with("\n,3,50.00\n"));
+
+        // A cell that reads (null) is counted apart from the nulls
+        let json = freq_json(&[&fixtures_path("null_text.csv"), "-c", "label"]);
+        let values: Vec<_> = json["values"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|row| (row["value"].clone(), row["count"].clone()))
+            .collect();
+        assert_eq!(
+            values,
+            [
+                (serde_json::json!("(null)"), serde_json::json!(2)),
+                (serde_json::json!("a"), serde_json::json!(1)),
+                (serde_json::Value::Null, serde_json::json!(2)),
+            ]
+        );
+
+        let output = csvp()
+            .arg("freq")
+            .arg(fixtures_path("null_text.csv"))
+            .args(["-c", "label"])
+            .output()
+            .expect("Failed to execute command");
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("| \"(null)\" | 2 "));
+        assert!(stdout.contains("| (null)   | 2 "));
+    }
+
+    #[test]
+    fn test_freq_requires_one_column() {
+        let output = csvp()
+            .arg("freq")
+            .arg(fixtures_path("basic.csv"))
+            .args(["-c", "name,age"])
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(!output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("exactly one column"));
+    }
+}
+
+mod diff_command {
+    use super::*;
+
+    #[test]
+    fn test_diff_identical_files() {
+        let output = csvp()
+            .arg("diff")
+            .arg(fixtures_path("basic.csv"))
+            .arg(fixtures_path("basic.csv"))
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("rows: +0"));
+        assert!(stdout.contains("no structural differences"));
+    }
+
+    #[test]
+    fn test_diff_structural_changes() {
+        let output = csvp()
+            .arg("diff")
+            .arg(fixtures_path("basic.csv"))
+            .arg(fixtures_path("basic_changed.csv"))
+            .output()
+            .expect("Failed to execute command");
+
+        assert_eq!(output.status.code(), Some(1));
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("rows: -2"));
+        assert!(stdout.contains("renamed: name -> full_name"));
+        assert!(stdout.contains("added: dept"));
+        assert!(stdout.contains("removed: active"));
+        assert!(stdout.contains("type: age: integer -> float"));
+    }
+
+    #[test]
+    fn test_diff_json() {
+        let output = csvp()
+            .arg("diff")
+            .arg(fixtures_path("basic.csv"))
+            .arg(fixtures_path("basic_changed.csv"))
+            .arg("--data")
+            .arg("-f")
+            .arg("json")
+            .output()
+            .expect("Failed to execute command");
+
+        assert_eq!(output.status.code(), Some(1));
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("\"row_delta\": -2"));
+        assert!(stdout.contains("\"null_rate_changes\": []"));
+    }
+}
+
+mod count_command {
+    use super::*;
+
+    fn count(args: &[&str]) -> String {
+        let output = csvp()
+            .arg("count")
+            .args(args)
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    }
+
+    #[test]
+    fn test_count_rows() {
+        assert_eq!(count(&[&fixtures_path("basic.csv")]), "5");
+        assert_eq!(count(&[&fixtures_path("basic.csv"), "-w", "age > 28"]), "3");
+    }
+
+    #[test]
+    fn test_fast_count() {
+        assert_eq!(count(&[&fixtures_path("basic.csv"), "--fast-count"]), "5");
+        assert_eq!(
+            count(&[
+                &fixtures_path("no_header.csv"),
+                "--no-header",
+                "--fast-count"
+            ]),
+            count(&[&fixtures_path("no_header.csv"), "--no-header"])
+        );
+    }
+
+    #[test]
+    fn test_fast_count_quoted_newline() {
+        // The quoted newline is counted as an extra record unless --strict
+        let file = fixtures_path("special_values.csv");
+        assert_eq!(count(&[&file, "--fast-count"]), "6");
+        assert_eq!(count(&[&file, "--fast-count", "--strict"]), "5");
+    }
+
+    #[test]
+    fn test_count_json() {
+        let json = parse_json(count(&[&fixtures_path("basic.csv"), "-f", "json"]).as_bytes());
+        assert_eq!(json["rows"], 5);
+    }
+}
+
+mod convert_command {
+    use super::*;
+
+    #[cfg(not(feature = "arrow"))]
+    #[test]
+    fn test_convert_arrow_requires_feature() {
+        let output = csvp()
+            .arg("convert")
+            .arg(fixtures_path("basic.csv"))
+            .arg("--to")
+            .arg("arrow")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(!output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("--features arrow"));
+    }
+
+    #[cfg(feature = "arrow")]
+    #[test]
+    fn test_convert_arrow_stream() {
+        use arrow_array::cast::AsArray;
+        use arrow_array::types::Int64Type;
+        use arrow_ipc::reader::StreamReader;
+
+        let output = csvp()
+            .arg("convert")
+            .arg(fixtures_path("basic.csv"))
+            .arg("--to")
+            .arg("arrow")
+            .arg("-c")
+            .arg("age,name,active")
+            .arg("-w")
+            .arg("age > 26")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        let reader = StreamReader::try_new(output.stdout.as_slice(), None).unwrap();
+        let schema = reader.schema();
+        assert_eq!(schema.field(0).name(), "age");
+        assert_eq!(schema.field(0).data_type(), &arrow_schema::DataType::Int64);
+        assert_eq!(schema.field(1).data_type(), &arrow_schema::DataType::Utf8);
+        assert_eq!(
+            schema.field(2).data_type(),
+            &arrow_schema::DataType::Boolean
+        );
+
+        let batches: Vec<_> = reader.map(|b| b.unwrap()).collect();
+        let ages: Vec<i64> = batches
+            .iter()
+            .flat_map(|b| b.column(0).as_primitive::<Int64Type>().values().to_vec())
+            .collect();
+        assert_eq!(ages, vec![30, 35, 28, 32]);
+    }
+
+    #[test]
+    fn test_convert_to_tsv() {
+        let output = csvp()
+            .arg("convert")
+            .arg(fixtures_path("special_values.csv"))
+            .arg("--to")
+            .arg("tsv")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.starts_with("label\tnote\n"));
+        assert!(stdout.contains("a,b\tplain\n"));
+        assert!(stdout.contains("\"say \"\"hi\"\"\"\tplain"));
+    }
+
+    #[test]
+    fn test_convert_to_json() {
+        let output = csvp()
+            .arg("convert")
+            .arg(fixtures_path("zip_codes.csv"))
+            .arg("--types")
+            .arg("zip=string")
+            .arg("--to")
+            .arg("json")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        let json = parse_json(&output.stdout);
+        assert_eq!(json.as_array().unwrap().len(), 3);
+        assert_eq!(json[0]["zip"], "01234");
+        assert_eq!(json[0]["amount"], 10);
+        assert_eq!(json[2]["flag"], 1);
+    }
+
+    #[test]
+    fn test_convert_json_nulls_and_booleans() {
+        let output = csvp()
+            .arg("convert")
+            .arg(fixtures_path("with_nulls.csv"))
+            .arg("--to")
+            .arg("ndjson")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(
+            stdout
+                .lines()
+                .all(|l| l.starts_with('{') && l.ends_with('}'))
+        );
+        assert!(stdout.contains(":null"));
+    }
+
+    #[test]
+    fn test_convert_transcodes_shift_jis() {
+        let output = csvp()
+            .arg("convert")
+            .arg(fixtures_path("shift_jis.csv"))
+            .arg("-e")
+            .arg("shift_jis")
+            .arg("--to")
+            .arg("csv")
+            .arg("--to-encoding")
+            .arg("utf-8")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8(output.stdout).expect("Output is not UTF-8");
+        assert_eq!(stdout, "名前,年齢\n山田,30\n佐藤,25\n");
+
+        // And back again, byte for byte
+        let output = csvp()
+            .arg("convert")
+            .arg(fixtures_path("shift_jis.csv"))
+            .arg("-e")
+            .arg("shift_jis")
+            .arg("--to")
+            .arg("csv")
+            .arg("--to-encoding")
+            .arg("sjis")
+            .output()
+            .expect("Failed to execute command");
+
+        let original = std::fs::read(fixtures_path("shift_jis.csv")).unwrap();
+        assert_eq!(output.stdout, original);
+    }
+
+    #[test]
+    fn test_convert_unmappable_characters() {
+        let output = csvp()
+            .arg("convert")
+            .arg(fixtures_path("shift_jis.csv"))
+            .arg("-e")
+            .arg("shift_jis")
+            .arg("--to")
+            .arg("csv")
+            .arg("--to-encoding")
+            .arg("latin1")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        assert!(output.stdout.starts_with(b"??,??\n??,30"));
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("could not be encoded"));
+    }
+
+    #[test]
+    fn test_convert_unknown_target() {
+        let output = csvp()
+            .arg("convert")
+            .arg(fixtures_path("basic.csv"))
+            .arg("--to")
+            .arg("xlsx")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(!output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("Unknown convert target"));
+    }
+}
+
+mod schema_command {
+    use super::*;
+
+    #[test]
+    fn test_sample_values_are_deterministic() {
+        let run = || {
+            let output = csvp()
+                .arg("schema")
+                .arg(fixtures_path("mixed_types.csv"))
+                .arg("-f")
+                .arg("json")
+                .output()
+                .expect("Failed to execute command");
+            assert!(output.status.success());
+            output.stdout
+        };
+
+        let first = run();
+        for _ in 0..5 {
+            assert_eq!(run(), first);
+        }
+
+        // Samples are the first distinct values, in file order
+        let json = parse_json(&first);
+        assert_eq!(
+            json[1]["sample_values"],
+            serde_json::json!(["100", "200.5", "text", "300", "400.25"])
+        );
+        assert_eq!(
+            json[3]["sample_values"],
+            serde_json::json!(["true", "false"])
+        );
+    }
+
+    #[test]
+    fn test_explain_types() {
+        let run = |extra: &[&str]| {
+            let output = csvp()
+                .arg("schema")
+                .arg(fixtures_path("mixed_types.csv"))
+                .args(["-c", "value"])
+                .args(extra)
+                .output()
+                .expect("Failed to execute command");
+            assert!(output.status.success());
+            String::from_utf8_lossy(&output.stdout).to_string()
+        };
+
+        let table = run(&["--explain-types"]);
+        assert!(table.contains("counts"));
+        assert!(table.contains("integer:2 float:2 boolean:0 string:1 null:0"));
+
+        let json = parse_json(run(&["--explain-types", "-f", "json"]).as_bytes());
+        assert_eq!(json[0]["inferred_type"], "string");
+        assert_eq!(json[0]["type_counts"]["string"], 1);
+        assert_eq!(json[0]["type_counts"]["float"], 2);
+
+        // Off by default
+        assert!(!run(&[]).contains("counts"));
+        let json = parse_json(run(&["-f", "json"]).as_bytes());
+        assert!(json[0].get("type_counts").is_none());
+    }
+
+    #[test]
+    fn test_schema_basic() {
+        let output = csvp()
+            .arg("schema")
+            .arg(fixtures_path("basic.csv"))
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("columns: 5"));
+        assert!(stdout.contains("integer"));
+        assert!(stdout.contains("string"));
+        assert!(stdout.contains("boolean"));
+    }
+
+    #[test]
+    fn test_schema_leading_zeros() {
+        let output = csvp()
+            .arg("schema")
+            .arg(fixtures_path("zip_codes.csv"))
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("Note: 'zip' has values with leading zeros"));
+
+        let output = csvp()
+            .arg("schema")
+            .arg(fixtures_path("zip_codes.csv"))
+            .arg("--preserve-leading-zeros")
+            .arg("-f")
+            .arg("json")
+            .output()
+            .expect("Failed to execute command");
+
+        let json = parse_json(&output.stdout);
+        assert_eq!(json[0]["inferred_type"], "string");
+        assert_eq!(json[0]["leading_zeros"], true);
+        assert_eq!(json[1]["inferred_type"], "integer");
+        assert_eq!(json[1]["leading_zeros"], false);
+
+        let output = csvp()
+            .arg(fixtures_path("zip_codes.csv"))
+            .arg("-c")
+            .arg("zip")
+            .arg("--preserve-leading-zeros")
+            .arg("-f")
+            .arg("csv")
+            .output()
+            .expect("Failed to execute command");
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("\nzip,string,3,0,0.00,3,00501,98765,"));
+    }
+
+    #[test]
+    fn test_schema_where() {
+        let output = csvp()
+            .arg("schema")
+            .arg(fixtures_path("mixed_types.csv"))
+            .arg("-f")
+            .arg("csv")
+            .output()
+            .expect("Failed to execute command");
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("\nvalue,string,"));
+
+        let output = csvp()
+            .arg("schema")
+            .arg(fixtures_path("mixed_types.csv"))
+            .arg("-w")
+            .arg("id >= 4")
+            .arg("-f")
+            .arg("csv")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("\nvalue,float,0,2,"));
+
+        let output = csvp()
+            .arg("schema")
+            .arg(fixtures_path("mixed_types.csv"))
+            .arg("-w")
+            .arg("id >= 4")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(String::from_utf8_lossy(&output.stdout).contains("filter: id >= 4"));
+    }
+
+    #[test]
+    fn test_schema_cols() {
+        let output = csvp()
+            .arg("schema")
+            .arg(fixtures_path("basic.csv"))
+            .arg("-c")
+            .arg("salary,1")
+            .arg("-f")
+            .arg("csv")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let columns: Vec<&str> = stdout
+            .lines()
+            .skip(1)
+            .map(|l| l.split(',').next().unwrap())
+            .collect();
+        assert_eq!(columns, vec!["salary", "name"]);
+    }
+
+    #[test]
+    fn test_schema_json_format() {
+        let output = csvp()
+            .arg("schema")
+            .arg(fixtures_path("basic.csv"))
+            .arg("-f")
+            .arg("json")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("["));
+        assert!(stdout.contains("\"inferred_type\""));
+    }
+
+    #[test]
+    fn test_schema_as_json_schema() {
+        let output = csvp()
+            .arg("schema")
+            .arg(fixtures_path("with_nulls.csv"))
+            .arg("--as")
+            .arg("json-schema")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let json = parse_json(&output.stdout);
+        assert_eq!(
+            json["$schema"],
+            "https://json-schema.org/draft/2020-12/schema"
+        );
+        assert_eq!(json["type"], "object");
+        assert_eq!(json["properties"]["id"]["type"], "integer");
+        assert_eq!(
+            json["properties"]["age"]["type"],
+            serde_json::json!(["integer", "null"])
+        );
+        assert_eq!(json["required"].as_array().unwrap().len(), 5);
+        // Properties follow the file's column order
+        assert!(stdout.find("\"id\"").unwrap() < stdout.find("\"score\"").unwrap());
+        assert!(stdout.find("\"name\"").unwrap() < stdout.find("\"age\"").unwrap());
+
+        let output = csvp()
+            .arg("schema")
+            .arg(fixtures_path("with_nulls.csv"))
+            .arg("--as")
+            .arg("avro")
+            .output()
+            .expect("Failed to execute command");
+        assert!(!output.status.success());
+    }
+
+    #[test]
+    fn test_schema_sample_size() {
+        let output = csvp()
+            .arg("schema")
+            .arg(fixtures_path("mixed_types.csv"))
+            .arg("--sample-size")
+            .arg("2")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("sampled: first 2 rows"));
+        // "text" in row 3 would make `value` a string column
+        assert!(stdout.contains("| value       | float"));
+    }
+
+    #[test]
+    fn test_schema_sample_size_covers_file() {
+        let output = csvp()
+            .arg("schema")
+            .arg(fixtures_path("basic.csv"))
+            .arg("--sample-size")
+            .arg("5")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(!stdout.contains("sampled:"));
+    }
+
+    #[test]
+    fn test_schema_with_sample_values() {
+        let output = csvp()
+            .arg("schema")
+            .arg(fixtures_path("basic.csv"))
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        // Should show sample values like Alice, Bob
+        assert!(stdout.contains("samples"));
+        assert!(stdout.contains("Alice, Bob, Charlie, Diana, Eve"));
+    }
+
+    #[test]
+    fn test_schema_sample_values_skip_nulls_and_duplicates() {
+        let output = csvp()
+            .arg("schema")
+            .arg(fixtures_path("with_nulls.csv"))
+            .arg("-f")
+            .arg("csv")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("age,integer,2,6,33.33,25; 35; 32; 28"));
+        assert!(stdout.contains("city,string,3,6,50.00,Tokyo; Osaka; Nagoya"));
+    }
+}
+
+mod delimiter_options {
+    use super::*;
+
+    #[test]
+    fn test_escape_character() {
+        let samples = |extra: &[&str]| {
+            let output = csvp()
+                .arg("schema")
+                .arg(fixtures_path("backslash_escaped.csv"))
+                .args(["-c", "quote", "-f", "json"])
+                .args(extra)
+                .output()
+                .expect("Failed to execute command");
+            assert!(output.status.success());
+            let json = parse_json(&output.stdout);
+            json[0]["sample_values"].clone()
+        };
+
+        assert_eq!(
+            samples(&["--escape", "\\"]),
+            serde_json::json!(["he said \"hi\"", "a\"b,c"])
+        );
+        // Without it the quote after \ ends the field
+        assert_ne!(samples(&[]), samples(&["--escape", "\\"]));
+
+        let output = csvp()
+            .arg(fixtures_path("backslash_escaped.csv"))
+            .args(["--escape", "\\\\"])
+            .output()
+            .expect("Failed to execute command");
+        assert!(!output.status.success());
+        assert!(String::from_utf8_lossy(&output.stderr).contains("single ASCII character"));
+    }
+
+    #[test]
+    fn test_multi_char_delimiter() {
+        let json = summary_json(&["schema", &fixtures_path("double_colon.csv"), "-d", "::"]);
+        assert_eq!(column_names(&json), ["id", "name", "note"]);
+        assert_eq!(json[0]["inferred_type"], "integer");
+        // A single colon is data; quotes are kept as written
+        assert_eq!(
+            json[2]["sample_values"],
+            serde_json::json!(["say \"hi\"", "a:b"])
+        );
+        assert_eq!(json[2]["null_count"], 1);
+    }
+
+    #[test]
+    fn test_tab_delimiter() {
+        let output = csvp()
+            .arg("-d")
+            .arg("tab")
+            .arg(fixtures_path("tab_separated.tsv"))
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("product"));
+        assert!(stdout.contains("quantity"));
+    }
+
+    #[test]
+    fn test_tab_delimiter_backslash() {
+        let output = csvp()
+            .arg("-d")
+            .arg("\\t")
+            .arg(fixtures_path("tab_separated.tsv"))
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+    }
+
+    #[test]
+    fn test_hex_escape_delimiter() {
+        let output = csvp()
+            .arg("-d")
+            .arg("\\x1f")
+            .arg(fixtures_path("unit_separator.csv"))
+            .arg("-f")
+            .arg("csv")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("\na,integer,1"));
+        assert!(stdout.contains("\nb,integer,1"));
+    }
+
+    #[test]
+    fn test_non_ascii_escape_delimiter_rejected() {
+        let output = csvp()
+            .arg("-d")
+            .arg("\\u{e9}")
+            .arg(fixtures_path("unit_separator.csv"))
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(!output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("ASCII"));
+    }
+}
+
+mod encoding_option {
+    use super::*;
+
+    #[test]
+    fn test_bom_first_column_by_name() {
+        let output = csvp()
+            .arg(fixtures_path("bom.csv"))
+            .arg("--cols")
+            .arg("id")
+            .arg("-w")
+            .arg("id > 1")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("matched: 1"));
+        assert!(!stdout.contains('\u{FEFF}'));
+    }
+
+    #[test]
+    fn test_bom_with_explicit_encoding() {
+        for encoding in ["utf-8", "latin1"] {
+            let output = csvp()
+                .arg(fixtures_path("bom.csv"))
+                .arg("-e")
+                .arg(encoding)
+                .arg("--cols")
+                .arg("id")
+                .output()
+                .expect("Failed to execute command");
+
+            assert!(output.status.success());
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            assert!(!stdout.contains('\u{FEFF}'));
+        }
+    }
+
+    #[test]
+    fn test_lossy_decode_warning_counts() {
+        let output = csvp()
+            .arg(fixtures_path("shift_jis.csv"))
+            .args(["-e", "utf-8"])
+            .output()
+            .expect("Failed to execute command");
+        assert!(output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(
+            stderr.contains("Warning: 12 character(s) could not be decoded with encoding 'utf-8'")
+        );
+
+        // A BOM picks the encoding without -e; invalid bytes after it still count
+        let path = &temp_path("lossy_bom.csv");
+        std::fs::write(path, b"\xEF\xBB\xBFid,name\n1,\xFF\xFEx\n").unwrap();
+        let output = csvp()
+            .arg(path)
+            .output()
+            .expect("Failed to execute command");
+        assert!(output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("2 character(s) could not be decoded as UTF-8"));
+        std::fs::remove_file(path).ok();
+
+        let output = csvp()
+            .arg(fixtures_path("shift_jis.csv"))
+            .output()
+            .expect("Failed to execute command");
+        assert!(!String::from_utf8_lossy(&output.stderr).contains("Warning"));
+    }
+
+    #[test]
+    fn test_streaming_input() {
+        // Valid UTF-8 for the first 64 KiB, so it streams; the Latin-1 byte
+        // near the end is only seen while parsing
+        let path = &temp_path("streaming.csv");
+        let mut content = String::from("id,name\n");
+        for i in 0..10_000 {
+            content.push_str(&format!("{i},名前{i}\n"));
+        }
+        let mut bytes = content.into_bytes();
+        bytes.extend_from_slice(b"10000,caf\xE9\n");
+        std::fs::write(path, &bytes).unwrap();
+
+        for args in [vec!["count"], vec!["count", "--fast-count"]] {
+            let output = csvp()
+                .arg(path)
+                .args(&args)
+                .args(["--input-buffer-size", "100"])
+                .output()
+                .expect("Failed to execute command");
+            assert!(output.status.success());
+            assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "10001");
+        }
+
+        let output = csvp()
+            .arg(path)
+            .args(["--input-buffer-size", "7", "-f", "json"])
+            .output()
+            .expect("Failed to execute command");
+        assert!(output.status.success());
+        let json = parse_json(&output.stdout);
+        assert_eq!(json[1]["unique_count"], 10001);
+        assert_eq!(json[1]["last_value"], "caf\u{FFFD}");
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("Warning: 1 character(s) could not be decoded as UTF-8"));
+
+        // Reported by the command once the input is read, in --error-format
+        let output = csvp()
+            .arg(path)
+            .args(["--input-buffer-size", "7", "--error-format", "json"])
+            .output()
+            .expect("Failed to execute command");
+        assert!(output.status.success());
+        let warning = parse_json(&output.stderr);
+        assert_eq!(warning["warning"], "Undecodable");
+        assert_eq!(warning["count"], 1);
+
+        // Not on a run that fails for another reason
+        let output = csvp()
+            .arg(path)
+            .args(["--input-buffer-size", "7", "-c", "nope"])
+            .output()
+            .expect("Failed to execute command");
+        assert!(!output.status.success());
+        assert!(!String::from_utf8_lossy(&output.stderr).contains("could not be decoded"));
+
+        let output = csvp()
+            .arg(path)
+            .args(["--input-buffer-size", "0"])
+            .output()
+            .expect("Failed to execute command");
+        assert!(!output.status.success());
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_single_byte_encodings_stream() {
+        let path = &temp_path("single_byte.csv");
+        // "café" in windows-1252, "мир" in KOI8-R
+        std::fs::write(path, b"id,name\n1,caf\xE9\n2,\xCD\xC9\xD2\n").unwrap();
+
+        for (encoding, expected) in [("cp1252", "café"), ("koi8-r", "мир")] {
+            let output = csvp()
+                .arg(path)
+                .args(["-e", encoding, "--input-buffer-size", "3", "-f", "json"])
+                .output()
+                .expect("Failed to execute command");
+            assert!(output.status.success());
+            assert!(output.stderr.is_empty());
+            let json = parse_json(&output.stdout);
+            let values = [&json[1]["first_value"], &json[1]["last_value"]];
+            assert!(
+                values.iter().any(|v| *v == expected),
+                "{encoding}: {values:?}"
+            );
+        }
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_detect_bytes() {
+        // An ASCII header, then Shift_JIS names
+        let path = &temp_path("detect_bytes.csv");
+        let mut bytes = b"id,name\n".to_vec();
+        bytes.extend_from_slice(&std::fs::read(fixtures_path("shift_jis.csv")).unwrap());
+        std::fs::write(path, &bytes).unwrap();
+
+        // The header alone looks like UTF-8
+        let output = csvp()
+            .arg(path)
+            .args(["--detect-bytes", "8"])
+            .output()
+            .expect("Failed to execute command");
+        assert!(output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("as UTF-8, detected from the first 8 bytes"));
+
+        for detect_bytes in ["0", "1000"] {
+            let output = csvp()
+                .arg(path)
+                .args(["--detect-bytes", detect_bytes])
+                .output()
+                .expect("Failed to execute command");
+            assert!(output.status.success());
+            assert!(!String::from_utf8_lossy(&output.stderr).contains("Warning"));
+            assert!(String::from_utf8_lossy(&output.stdout).contains("山田"));
+        }
+        std::fs::remove_file(path).ok();
+
+        // The BOM is checked even when the sample is smaller than it
+        let output = csvp()
+            .arg(fixtures_path("bom.csv"))
+            .args(["--detect-bytes", "1", "-c", "id", "-f", "json"])
+            .output()
+            .expect("Failed to execute command");
+        assert!(output.status.success());
+        let json = parse_json(&output.stdout);
+        assert_eq!(json[0]["name"], "id");
+    }
+
+    #[test]
+    fn test_encoding_report() {
+        let output = csvp()
+            .arg(fixtures_path("shift_jis.csv"))
+            .args(["--encoding-report", "-f", "json"])
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        let json = parse_json(&output.stdout);
+        assert_eq!(json["detected"], "Shift_JIS");
+        assert!(json["bom"].is_null());
+        let candidates = json["candidates"].as_array().unwrap();
+        assert_eq!(candidates[0]["encoding"], "Shift_JIS");
+        assert_eq!(candidates[0]["errors"], 0);
+        let utf8 = candidates
+            .iter()
+            .find(|c| c["encoding"] == "UTF-8")
+            .unwrap();
+        assert!(utf8["errors"].as_u64().unwrap() > 0);
+
+        let output = csvp()
+            .arg(fixtures_path("bom.csv"))
+            .arg("--encoding-report")
+            .output()
+            .expect("Failed to execute command");
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("BOM: UTF-8"));
+        assert!(stdout.contains("UTF-8 *"));
+    }
+}
+
+mod terminator_option {
+    use super::*;
+
+    #[test]
+    fn test_custom_terminator() {
+        let output = csvp()
+            .arg("-d")
+            .arg(";")
+            .arg("--terminator")
+            .arg("~")
+            .arg(fixtures_path("tilde_terminated.csv"))
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("rows: 3"));
+        // Quoted terminators are field data
+        assert!(stdout.contains("x~y"));
+    }
+
+    #[test]
+    fn test_invalid_terminator() {
+        let output = csvp()
+            .arg("--terminator")
+            .arg("~~")
+            .arg(fixtures_path("basic.csv"))
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(!output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("single ASCII character"));
+    }
+}
+
+mod byte_window_option {
+    use super::*;
+
+    fn rows_in_window(start: &str, end: &str) -> String {
+        let output = csvp()
+            .arg(fixtures_path("basic.csv"))
+            .arg("--byte-start")
+            .arg(start)
+            .arg("--byte-end")
+            .arg(end)
+            .arg("-c")
+            .arg("name")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        String::from_utf8_lossy(&output.stdout).to_string()
+    }
+
+    #[test]
+    fn test_byte_window_snaps_to_records() {
+        // Offset 30 is inside Alice's row and 80 is inside Charlie's row
+        let stdout = rows_in_window("30", "80");
+        assert!(stdout.contains("rows: 2"));
+        assert!(stdout.contains("Bob"));
+        assert!(stdout.contains("Charlie"));
+        assert!(!stdout.contains("Alice"));
+    }
+
+    #[test]
+    fn test_adjacent_windows_cover_every_record() {
+        let first = rows_in_window("0", "80");
+        let second = rows_in_window("80", "1000");
+        assert!(first.contains("rows: 3"));
+        assert!(second.contains("rows: 2"));
+        assert!(second.contains("Diana"));
+        assert!(second.contains("Eve"));
+    }
+
+    #[test]
+    fn test_byte_window_invalid_range() {
+        let output = csvp()
+            .arg(fixtures_path("basic.csv"))
+            .arg("--byte-start")
+            .arg("80")
+            .arg("--byte-end")
+            .arg("30")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(!output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("must be less than"));
+    }
+}
+
+mod header_trimming {
+    use super::*;
+
+    #[test]
+    fn test_padded_headers_are_trimmed() {
+        let output = csvp()
+            .arg(fixtures_path("padded_headers.csv"))
+            .arg("-c")
+            .arg("name")
+            .arg("-w")
+            .arg("id > 1")
+            .arg("-f")
+            .arg("csv")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("\nname,string,1,"));
+    }
+
+    #[test]
+    fn test_no_trim_headers() {
+        let output = csvp()
+            .arg("schema")
+            .arg(fixtures_path("padded_headers.csv"))
+            .arg("--no-trim-headers")
+            .arg("-f")
+            .arg("csv")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("\n name ,string"));
+    }
+}
+
+mod duplicate_headers {
+    use super::*;
+
+    #[test]
+    fn test_duplicate_headers_are_disambiguated() {
+        let output = csvp()
+            .arg("schema")
+            .arg(fixtures_path("duplicate_headers.csv"))
+            .arg("-f")
+            .arg("csv")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("\nname,string,0,2,0.00,A; B"));
+        // name_2 already exists, so the repeated column becomes name_3
+        assert!(stdout.contains("\nname_3,string,0,2,0.00,X; Y"));
+        assert!(stdout.contains("\nname_2,string,0,2,0.00,p; q"));
+    }
+
+    #[test]
+    fn test_filter_on_disambiguated_column() {
+        let output = csvp()
+            .arg(fixtures_path("duplicate_headers.csv"))
+            .arg("-c")
+            .arg("name,name_3")
+            .arg("-w")
+            .arg("name_3 == Y")
+            .arg("-f")
+            .arg("json")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        let json = parse_json(&output.stdout);
+        let columns = json.as_array().unwrap();
+        assert_eq!(columns.len(), 2);
+        assert_eq!(columns[0]["min"], "B");
+        assert_eq!(columns[1]["min"], "Y");
+    }
+}
+
+mod rename_option {
+    use super::*;
+
+    #[test]
+    fn test_rename_with_no_header() {
+        let output = csvp()
+            .arg(fixtures_path("no_header.csv"))
+            .arg("--no-header")
+            .arg("--rename")
+            .arg("col0=id,1=fruit")
+            .arg("-c")
+            .arg("id,fruit")
+            .arg("-f")
+            .arg("csv")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("\nid,integer,5,"));
+        assert!(stdout.contains("\nfruit,string,5,"));
+    }
+
+    #[test]
+    fn test_rename_accepts_original_names() {
+        let output = csvp()
+            .arg("schema")
+            .arg(fixtures_path("basic.csv"))
+            .arg("--rename")
+            .arg("salary=pay")
+            .arg("-c")
+            .arg("salary")
+            .arg("-w")
+            .arg("salary > 55000 && pay < 70000")
+            .arg("-f")
+            .arg("csv")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("\npay,float,0,2,"));
+    }
+
+    #[test]
+    fn test_rename_onto_existing_column() {
+        let output = csvp()
+            .arg(fixtures_path("basic.csv"))
+            .arg("--rename")
+            .arg("salary=age")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(!output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("Cannot rename to 'age'"));
+    }
+}
+
+mod no_header_option {
+    use super::*;
+
+    #[test]
+    fn test_no_header() {
+        let output = csvp()
+            .arg("--no-header")
+            .arg(fixtures_path("no_header.csv"))
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        // Should generate col0, col1, col2, col3
+        assert!(stdout.contains("col0"));
+        assert!(stdout.contains("col1"));
+        assert!(stdout.contains("col2"));
+        assert!(stdout.contains("col3"));
+    }
+
+    #[test]
+    fn test_no_header_keeps_first_row() {
+        let output = csvp()
+            .arg("--no-header")
+            .arg(fixtures_path("no_header.csv"))
+            .args(["-c", "col0", "-f", "json"])
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        let json = parse_json(&output.stdout);
+        assert_eq!(json[0]["count"], 5);
+        assert_eq!(json[0]["min"], "1");
+    }
+
+    #[test]
+    fn test_header_option() {
+        let output = csvp()
+            .arg(fixtures_path("no_header.csv"))
+            .arg("--header")
+            .arg("id, fruit, stock, price")
+            .arg("-c")
+            .arg("fruit,stock")
+            .arg("-w")
+            .arg("stock >= 120")
+            .arg("-f")
+            .arg("json")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        let json = parse_json(&output.stdout);
+        assert_eq!(json[0]["name"], "fruit");
+        // The first line is data, not a header: 200, 150 and 120 match
+        assert_eq!(json[1]["count"], 3);
+        assert_eq!(json[1]["max"], "200");
+
+        let output = csvp()
+            .arg("count")
+            .arg(fixtures_path("no_header.csv"))
+            .arg("--header")
+            .arg("id,fruit,stock,price")
+            .output()
+            .expect("Failed to execute command");
+        assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "5");
+
+        let output = csvp()
+            .arg(fixtures_path("no_header.csv"))
+            .arg("--header")
+            .arg("id,,stock")
+            .output()
+            .expect("Failed to execute command");
+        assert!(!output.status.success());
+    }
+}
+
+mod filter_expressions {
+    use super::*;
+
+    fn csv_output(args: &[&str]) -> String {
+        let output = csvp()
+            .args(args)
+            .arg("-f")
+            .arg("csv")
+            .output()
+            .expect("Failed to execute command");
+        assert!(output.status.success());
+        String::from_utf8_lossy(&output.stdout).into_owned()
+    }
+
+    #[test]
+    fn test_null_comparisons() {
+        let count = |args: &[&str]| {
+            let output = csvp()
+                .arg("count")
+                .arg(fixtures_path("with_nulls.csv"))
+                .args(args)
+                .output()
+                .expect("Failed to execute command");
+            assert!(output.status.success());
+            String::from_utf8_lossy(&output.stdout).trim().to_string()
+        };
+
+        // Two of the six ages are null: in neither == nor != by default
+        assert_eq!(count(&["-w", "age == 30"]), "0");
+        assert_eq!(count(&["-w", "age != 30"]), "4");
+        assert_eq!(count(&["-w", "!(age == 30)"]), "4");
+        assert_eq!(count(&["-w", "age != 30 || is_null(age)"]), "6");
+
+        assert_eq!(count(&["-w", "age != 30", "--null-matches", "ne"]), "6");
+        assert_eq!(count(&["-w", "age > 30", "--null-matches", "ne"]), "2");
+    }
+
+    #[test]
+    fn test_short_rows_in_filter() {
+        let count = |args: &[&str]| {
+            csvp()
+                .arg("count")
+                .arg(fixtures_path("ragged.csv"))
+                .args(args)
+                .output()
+                .expect("Failed to execute command")
+        };
+
+        // The missing city of Bob's row reads as null
+        let output = count(&["-w", "is_null(city)"]);
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "1");
+
+        let output = count(&["-w", "is_null(city)", "--strict-rows"]);
+        assert!(!output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("Row at line 3 has 3 fields"));
+        assert!(stderr.contains("column 'city'"));
+
+        // Only the columns the filter reads have to be there
+        let output = count(&["-w", "age > 26", "--strict-rows"]);
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "2");
+
+        let output = count(&[
+            "-w",
+            "city == \"Tokyo\"",
+            "--strict-rows",
+            "--error-format",
+            "json",
+        ]);
+        let json = parse_json(&output.stderr);
+        assert_eq!(json["error"], "ShortRow");
+        assert_eq!(json["line"], 3);
+    }
+
+    #[test]
+    fn test_date_range() {
+        let count = |args: &[&str]| {
+            csvp()
+                .arg("count")
+                .arg(fixtures_path("dates.csv"))
+                .args(["--date-col", "created_at"])
+                .args(args)
+                .output()
+                .expect("Failed to execute command")
+        };
+
+        // Both ends included; timestamps count by their day
+        let output = count(&["--since", "2024-01-01", "--until", "2024-12-31"]);
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "4");
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("2 row(s) excluded because 'created_at' isn't a date"));
+
+        // Chronological, not lexical: 2024/02/29 is after 2024-02-01
+        let output = count(&["--since", "2024-02-01", "-w", "amount < 50"]);
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "2");
+
+        let output = count(&["--until", "2024-02-30"]);
+        assert!(!output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("--until 2024-02-30 is not a date"));
+
+        let output = csvp()
+            .arg(fixtures_path("dates.csv"))
+            .args(["--since", "2024-01-01"])
+            .output()
+            .expect("Failed to execute command");
+        assert!(!output.status.success());
+        assert!(String::from_utf8_lossy(&output.stderr).contains("--date-col"));
+
+        let output = count(&[]);
+        assert!(!output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("--date-col needs --since or --until"));
+
+        let output = count(&["--since", "2024-01-01", "--error-format", "json"]);
+        assert!(output.status.success());
+        let warning = parse_json(&output.stderr);
+        assert_eq!(warning["warning"], "NotADate");
+        assert_eq!(warning["count"], 2);
+        assert_eq!(warning["column"], "created_at");
+    }
+
+    #[test]
+    fn test_filter_on_column_outside_cols() {
+        let stdout = csv_output(&[
+            &fixtures_path("basic.csv"),
+            "-c",
+            "age",
+            "-w",
+            "name == \"Alice\"",
+        ]);
+        assert!(stdout.contains("\nage,integer,1,0,0.00,1,25,25,"));
+
+        // Selecting by index doesn't shift the filter's column lookup
+        let stdout = csv_output(&[&fixtures_path("basic.csv"), "-c", "2", "-w", "id == 3"]);
+        assert!(stdout.contains("\nage,integer,1,0,0.00,1,35,35,"));
+    }
+
+    #[test]
+    fn test_filter_on_generated_header_outside_cols() {
+        let stdout = csv_output(&[
+            &fixtures_path("no_header.csv"),
+            "--no-header",
+            "-c",
+            "col2",
+            "-w",
+            "col1 == \"Banana\"",
+        ]);
+        assert!(stdout.contains("\ncol2,integer,1,0,0.00,1,200,200,"));
+    }
+
+    #[test]
+    fn test_filter_outside_cols_in_other_commands() {
+        let output = csvp()
+            .arg("convert")
+            .arg(fixtures_path("basic.csv"))
+            .arg("-c")
+            .arg("age")
+            .arg("-w")
+            .arg("name == \"Charlie\"")
+            .arg("--to")
+            .arg("csv")
+            .output()
+            .expect("Failed to execute command");
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "age\n35\n");
+
+        let stdout = csv_output(&[
+            "schema",
+            &fixtures_path("mixed_types.csv"),
+            "-c",
+            "value",
+            "-w",
+            "id >= 4",
+        ]);
+        assert!(stdout.contains("\nvalue,float,0,2,"));
+    }
+
+    #[test]
+    fn test_filter_equals() {
+        let output = csvp()
+            .arg("summary")
+            .arg(fixtures_path("basic.csv"))
+            .arg("--where")
+            .arg("name == \"Alice\"")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("matched: 1"));
+    }
+
+    #[test]
+    fn test_filter_greater_than() {
+        let output = csvp()
+            .arg("summary")
+            .arg(fixtures_path("basic.csv"))
+            .arg("--where")
+            .arg("age >= 30")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("matched: 3"));
+    }
+
+    #[test]
+    fn test_filter_contains() {
+        let output = csvp()
+            .arg("summary")
+            .arg(fixtures_path("basic.csv"))
+            .arg("--where")
+            .arg("contains(name, \"a\")")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        // Charlie, Diana have 'a'
+        assert!(stdout.contains("matched:"));
+    }
+
+    #[test]
+    fn test_filter_in() {
+        let output = csvp()
+            .arg("summary")
+            .arg(fixtures_path("basic.csv"))
+            .arg("--where")
+            .arg("in(name, [\"Alice\", \"Bob\"])")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("matched: 2"));
+    }
+
+    #[test]
+    fn test_filter_is_null() {
+        let output = csvp()
+            .arg("summary")
+            .arg(fixtures_path("with_nulls.csv"))
+            .arg("--where")
+            .arg("is_null(age)")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        // Bob and Diana have null ages
+        assert!(stdout.contains("matched:"));
+    }
+
+    #[test]
+    fn test_filter_logical_and() {
+        let output = csvp()
+            .arg("summary")
+            .arg(fixtures_path("basic.csv"))
+            .arg("--where")
+            .arg("age > 25 && active == true")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+    }
+
+    #[test]
+    fn test_filter_matches_regex() {
+        let output = csvp()
+            .arg("summary")
+            .arg(fixtures_path("basic.csv"))
+            .arg("--where")
+            .arg("matches(name, \"^[AB]\")")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        // Alice and Bob start with A or B
+        assert!(stdout.contains("matched: 2"));
+    }
+}
+
+mod error_handling {
+    use super::*;
+
+    #[test]
+    fn test_file_not_found() {
+        let output = csvp()
+            .arg("nonexistent.csv")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(!output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("not found") || stderr.contains("No such file"));
+    }
+
+    #[test]
+    fn test_invalid_column() {
+        let output = csvp()
+            .arg("summary")
+            .arg(fixtures_path("basic.csv"))
+            .arg("--cols")
+            .arg("nonexistent")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(!output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("not found") || stderr.contains("Column"));
+    }
+
+    #[test]
+    fn test_column_index_out_of_range() {
+        let output = csvp()
+            .arg("summary")
+            .arg(fixtures_path("basic.csv"))
+            .arg("--cols")
+            .arg("999")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(!output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("out of range") || stderr.contains("index"));
+    }
+
+    #[test]
+    fn test_invalid_filter() {
+        let output = csvp()
+            .arg("summary")
+            .arg(fixtures_path("basic.csv"))
+            .arg("--where")
+            .arg("invalid_column > 5")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(!output.status.success());
+    }
+
+    #[test]
+    fn test_error_format_json_column_suggestion() {
+        let output = csvp()
+            .arg(fixtures_path("basic.csv"))
+            .arg("-c")
+            .arg("nmae")
+            .arg("--error-format")
+            .arg("json")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(!output.status.success());
+        let json = parse_json(&output.stderr);
+        assert_eq!(json["error"], "ColumnNotFound");
+        assert_eq!(json["column"], "nmae");
+        assert_eq!(json["suggestion"], "name");
+    }
+
+    #[test]
+    fn test_filter_column_suggestion() {
+        let output = csvp()
+            .arg(fixtures_path("basic.csv"))
+            .arg("-w")
+            .arg("nmae == \"Alice\"")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(!output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("did you mean \"name\"?"));
+    }
+
+    #[test]
+    fn test_subcommand_typo_suggestion() {
+        let output = csvp()
+            .arg("summry")
+            .arg(fixtures_path("basic.csv"))
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(!output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("a similar subcommand exists: 'summary'"));
+    }
+
+    #[test]
+    fn test_option_values_are_not_subcommand_typos() {
+        // "amount" is close to "count", but it is the value of -c
+        let output = csvp()
+            .arg("-c")
+            .arg("amount")
+            .arg(fixtures_path("basic.csv"))
+            .output()
+            .expect("Failed to execute command");
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(!stderr.contains("similar subcommand"), "{stderr}");
+        assert!(stderr.contains("amount"), "{stderr}");
+
+        let output = csvp()
+            .arg("-w")
+            .arg("age > 1")
+            .arg(fixtures_path("basic.csv"))
+            .output()
+            .expect("Failed to execute command");
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("rows: 5"));
+    }
+
+    #[test]
+    fn test_format_typo_suggestion() {
+        let output = csvp()
+            .arg(fixtures_path("basic.csv"))
+            .arg("-f")
+            .arg("jso")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(!output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("Did you mean \"json\"?"));
+    }
+
+    #[test]
+    fn test_error_format_json_file_not_found() {
+        let output = csvp()
+            .arg("nonexistent.csv")
+            .arg("--error-format")
+            .arg("json")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(!output.status.success());
+        let json = parse_json(&output.stderr);
+        assert_eq!(json["error"], "FileNotFound");
+        assert_eq!(json["path"], "nonexistent.csv");
+    }
+
+    #[test]
+    fn test_error_format_rejects_unknown_value() {
+        let output = csvp()
+            .arg(fixtures_path("basic.csv"))
+            .arg("--error-format")
+            .arg("jsn")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(!output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("Unknown error format: jsn"));
+    }
+}
+
+mod check_option {
+    use super::*;
+
+    #[test]
+    fn test_check_ok() {
+        let output = csvp()
+            .arg(fixtures_path("basic.csv"))
+            .arg("-c")
+            .arg("name,2")
+            .arg("-w")
+            .arg("salary > 55000")
+            .arg("--check")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert_eq!(stdout, "columns: name, age\nOK\n");
+    }
+
+    #[test]
+    fn test_check_reports_bad_filter() {
+        let output = csvp()
+            .arg("schema")
+            .arg(fixtures_path("basic.csv"))
+            .arg("-w")
+            .arg("salery > 55000")
+            .arg("--check")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(!output.status.success());
+        assert!(output.stdout.is_empty());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("salery"));
+        assert!(stderr.contains("salary"));
+    }
+
+    #[test]
+    fn test_check_reports_unknown_column() {
+        let output = csvp()
+            .arg(fixtures_path("basic.csv"))
+            .arg("-c")
+            .arg("nmae")
+            .arg("--check")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(!output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("nmae"));
+    }
+
+    #[test]
+    fn test_require_columns() {
+        let run = |args: &[&str]| {
+            csvp()
+                .arg(fixtures_path("basic.csv"))
+                .args(args)
+                .output()
+                .expect("Failed to execute command")
+        };
+
+        let output = run(&["--require-columns", "id, name,age", "--check"]);
+        assert!(output.status.success());
+
+        // Every missing column is listed, with a hint for near misses
+        let output = run(&["schema", "--require-columns", "id,nmae,amount"]);
+        assert!(!output.status.success());
+        assert!(output.stdout.is_empty());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("Missing required columns: nmae (did you mean \"name\"?), amount"));
+
+        // A renamed column satisfies its old and its new name
+        let output = run(&[
+            "--rename",
+            "name=full_name",
+            "--require-columns",
+            "name,full_name",
+        ]);
+        assert!(output.status.success());
+
+        let output = run(&["--require-columns", "amount", "--error-format", "json"]);
+        let json = parse_json(&output.stderr);
+        assert_eq!(json["error"], "MissingColumns");
+        assert_eq!(json["columns"][0], "amount");
+    }
+}
+
+mod color_option {
+    use super::*;
+
+    fn table(args: &[&str]) -> std::process::Output {
+        csvp()
+            .arg(fixtures_path("basic.csv"))
+            .args(["-c", "id,name", "--color", "always"])
+            .args(args)
+            .output()
+            .expect("Failed to execute command")
+    }
+
+    #[test]
+    fn test_color_schemes() {
+        let output = table(&[]);
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("\x1b[34minteger"));
+        assert!(stdout.contains("\x1b[35mstring"));
+
+        let output = table(&["--color-scheme", "colorblind"]);
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("\x1b[37mstring"));
+        assert!(!stdout.contains("\x1b[35mstring"));
+
+        let output = table(&["--color-scheme", "mono"]);
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("\x1b[1minteger"));
+        assert!(!stdout.contains("\x1b[35mstring"));
+    }
+
+    #[test]
+    fn test_color_scheme_without_color() {
+        let output = csvp()
+            .arg(fixtures_path("basic.csv"))
+            .args(["--color", "never", "--color-scheme", "mono"])
+            .output()
+            .expect("Failed to execute command");
+        assert!(output.status.success());
+        assert!(!String::from_utf8_lossy(&output.stdout).contains('\x1b'));
+
+        let output = table(&["--color-scheme", "neon"]);
+        assert!(!output.status.success());
+        assert!(String::from_utf8_lossy(&output.stderr).contains("Unknown color scheme: neon"));
+    }
+}
+
+mod default_command {
+    use super::*;
+
+    fn run(default: &str, args: &[&str]) -> std::process::Output {
+        csvp()
+            .env("CSVP_DEFAULT_COMMAND", default)
+            .args(args)
+            .arg(fixtures_path("basic.csv"))
+            .output()
+            .expect("Failed to execute command")
+    }
+
+    #[test]
+    fn test_env_sets_default_command() {
+        let output = run("schema", &[]);
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("samples"));
+        assert!(!stdout.contains("Top values:"));
+
+        let output = run("count", &[]);
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "5");
+    }
+
+    #[test]
+    fn test_subcommand_overrides_env() {
+        let output = run("count", &["summary"]);
+        assert!(output.status.success());
+        assert!(String::from_utf8_lossy(&output.stdout).contains("Top values:"));
+    }
+
+    #[test]
+    fn test_invalid_default_command() {
+        let output = run("pivot", &[]);
+        assert!(!output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("CSVP_DEFAULT_COMMAND"));
+        assert!(stderr.contains("summary, schema, profile, count"));
+    }
+}
+
+mod quiet_option {
+    use super::*;
+
+    #[test]
+    fn test_quiet_flag() {
+        let output = csvp()
+            .arg("-q")
+            .arg(fixtures_path("basic.csv"))
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        // Output should still contain results
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("file:"));
+    }
+}
+
+mod follow_option {
+    use super::*;
+    use std::io::Write;
+    use std::process::{Child, Stdio};
+    use std::sync::mpsc::{self, Receiver};
+    use std::time::Duration;
+
+    /// A `csvp FILE --follow -f json` run, handing over each summary as it
+    /// is printed
+    struct Follow {
+        child: Child,
+        summaries: Receiver<serde_json::Value>,
+    }
+
+    impl Follow {
+        fn spawn(path: &str, args: &[&str]) -> Self {
+            let mut child = csvp()
+                .arg(path)
+                .args(["--follow", "--follow-interval", "0.05", "-f", "json"])
+                .args(args)
+                .stdout(Stdio::piped())
+                .spawn()
+                .expect("Failed to execute command");
+            let stdout = child.stdout.take().unwrap();
+            let (sender, summaries) = mpsc::channel();
+            std::thread::spawn(move || {
+                let summaries = serde_json::Deserializer::from_reader(stdout).into_iter();
+                for summary in summaries.map_while(Result::ok) {
+                    if sender.send(summary).is_err() {
+                        break;
+                    }
+                }
+            });
+            Self { child, summaries }
+        }
+
+        /// The next summary, failing the test if none comes in time
+        fn next(&self) -> serde_json::Value {
+            self.summaries
+                .recv_timeout(Duration::from_secs(10))
+                .expect("No summary printed in time")
+        }
+
+        /// No summary for a few polls: nothing complete was appended
+        fn assert_quiet(&self) {
+            let extra = self.summaries.recv_timeout(Duration::from_millis(300));
+            assert!(extra.is_err(), "Unexpected summary: {extra:?}");
+        }
+    }
+
+    impl Drop for Follow {
+        fn drop(&mut self) {
+            self.child.kill().ok();
+            self.child.wait().ok();
+        }
+    }
+
+    fn append(path: &str, text: &str) {
+        let mut file = std::fs::OpenOptions::new().append(true).open(path).unwrap();
+        file.write_all(text.as_bytes()).unwrap();
+    }
+
+    #[test]
+    fn test_follow_redraws_on_appended_rows() {
+        let path = &temp_path("follow.csv");
+        std::fs::write(path, "id,name\n1,a\n2,b\n").unwrap();
+
+        // One summary per change, as a JSON array each
+        let follow = Follow::spawn(path, &["-c", "id"]);
+        assert_eq!(follow.next()[0]["count"], 2);
+        append(path, "3,c\n4,"); // The half-written row waits for its terminator
+        assert_eq!(follow.next()[0]["count"], 3);
+        follow.assert_quiet();
+        append(path, "d\n");
+        assert_eq!(follow.next()[0]["count"], 4);
+        drop(follow);
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_follow_waits_for_quoted_newline() {
+        let path = &temp_path("follow_quoted.csv");
+        std::fs::write(path, "a,b\n1,x\n2,y\n").unwrap();
+
+        let follow = Follow::spawn(path, &[]);
+        assert_eq!(follow.next()[0]["count"], 2);
+        append(path, "3,\"multi\n"); // The newline is inside the quoted field
+        follow.assert_quiet();
+        append(path, "line\"\n");
+        let last = follow.next();
+        assert_eq!(last[0]["count"], 3);
+        assert_eq!(last[0]["data_type"], "integer");
+        assert_eq!(last[1]["null_count"], 0);
+        assert_eq!(last[1]["max"], "y");
+        drop(follow);
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_follow_other_commands() {
+        let output = csvp()
+            .args(["schema", &fixtures_path("basic.csv"), "--follow"])
+            .output()
+            .expect("Failed to execute command");
+        assert!(!output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("--follow is not supported by schema"));
+    }
+
+    #[test]
+    fn test_follow_interval_must_be_positive() {
+        let output = csvp()
+            .arg(fixtures_path("basic.csv"))
+            .args(["--follow", "--follow-interval", "0"])
+            .output()
+            .expect("Failed to execute command");
+        assert!(!output.status.success());
+        assert!(String::from_utf8_lossy(&output.stderr).contains("--follow-interval"));
+    }
+}
+
+mod output_option {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_output_to_file() {
+        let output_file = &temp_path("output.txt");
+
+        let output = csvp()
+            .arg("-o")
+            .arg(output_file)
+            .arg(fixtures_path("basic.csv"))
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+
+        // Check file was created and has content
         let content = fs::read_to_string(output_file).expect("Failed to read output file");
         assert!(content.contains("file:"));
         assert!(content.contains("rows:"));
@@ -457,6 +3848,182 @@ mod output_option {
         // Cleanup
         fs::remove_file(output_file).ok();
     }
+
+    #[cfg(not(feature = "parquet"))]
+    #[test]
+    fn test_parquet_requires_feature() {
+        let output = csvp()
+            .arg(fixtures_path("basic.csv"))
+            .arg("-f")
+            .arg("parquet")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(!output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("--features parquet"));
+    }
+
+    #[cfg(feature = "parquet")]
+    #[test]
+    fn test_parquet_output() {
+        use parquet::file::reader::{FileReader, SerializedFileReader};
+
+        for (command, output_file) in [
+            ("summary", &temp_path("summary.parquet")),
+            ("schema", &temp_path("schema.parquet")),
+        ] {
+            let output = csvp()
+                .arg(command)
+                .arg(fixtures_path("basic.csv"))
+                .arg("-f")
+                .arg("parquet")
+                .arg("-o")
+                .arg(output_file)
+                .output()
+                .expect("Failed to execute command");
+
+            assert!(output.status.success());
+            let file = fs::File::open(output_file).expect("Failed to open output file");
+            let reader = SerializedFileReader::new(file).expect("Not a Parquet file");
+            // One row per CSV column
+            assert_eq!(reader.metadata().file_metadata().num_rows(), 5);
+
+            fs::remove_file(output_file).ok();
+        }
+    }
+
+    #[cfg(feature = "parquet")]
+    #[test]
+    fn test_parquet_requires_output_file() {
+        let output = csvp()
+            .arg(fixtures_path("basic.csv"))
+            .arg("-f")
+            .arg("parquet")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(!output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("-o/--output"));
+    }
+}
+
+mod url_input {
+    use super::*;
+
+    #[cfg(not(feature = "http"))]
+    #[test]
+    fn test_url_requires_feature() {
+        let output = csvp()
+            .arg("http://127.0.0.1:9/data.csv")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(!output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("--features http"));
+    }
+
+    /// Serve `requests` requests: /old redirects to /basic.csv, which returns
+    /// the fixture, /latin1.csv is UTF-8 declared as windows-1252, and
+    /// anything else is a 404
+    #[cfg(feature = "http")]
+    fn serve(requests: usize) -> String {
+        use std::io::{BufRead, BufReader, Write};
+        use std::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = format!("http://{}", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            for stream in listener.incoming().take(requests) {
+                let mut stream = stream.unwrap();
+                let mut request_line = String::new();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                reader.read_line(&mut request_line).unwrap();
+                let mut line = String::new();
+                while reader.read_line(&mut line).unwrap() > 2 {
+                    line.clear();
+                }
+
+                let path = request_line.split(' ').nth(1).unwrap_or("");
+                let response = match path {
+                    "/old" => "HTTP/1.1 301 Moved Permanently\r\nLocation: /basic.csv\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_string(),
+                    "/basic.csv" => {
+                        let body = std::fs::read_to_string(fixtures_path("basic.csv")).unwrap();
+                        format!(
+                            "HTTP/1.1 200 OK\r\nContent-Type: text/csv\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                            body.len()
+                        )
+                    }
+                    "/latin1.csv" => {
+                        let body = "name\ncafé\n";
+                        format!(
+                            "HTTP/1.1 200 OK\r\nContent-Type: text/csv; charset=\"windows-1252\"\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                            body.len()
+                        )
+                    }
+                    _ => "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_string(),
+                };
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+        address
+    }
+
+    #[cfg(feature = "http")]
+    #[test]
+    fn test_url_input_follows_redirects() {
+        let address = serve(2);
+        let output = csvp()
+            .arg(format!("{address}/old"))
+            .arg("-c")
+            .arg("age")
+            .arg("-f")
+            .arg("json")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        let json = parse_json(&output.stdout);
+        assert_eq!(json[0]["count"], 5);
+    }
+
+    #[cfg(feature = "http")]
+    #[test]
+    fn test_url_input_declared_charset() {
+        let address = serve(2);
+        let max = |extra: &[&str]| {
+            let output = csvp()
+                .arg(format!("{address}/latin1.csv"))
+                .arg("-f")
+                .arg("json")
+                .args(extra)
+                .output()
+                .expect("Failed to execute command");
+            assert!(output.status.success());
+            let json = parse_json(&output.stdout);
+            json[0]["max"].as_str().unwrap().to_string()
+        };
+
+        // The Content-Type charset wins over detection, and -e over both
+        assert_eq!(max(&[]), "cafÃ©");
+        assert_eq!(max(&["-e", "utf-8"]), "café");
+    }
+
+    #[cfg(feature = "http")]
+    #[test]
+    fn test_url_input_http_error() {
+        let address = serve(1);
+        let output = csvp()
+            .arg(format!("{address}/missing.csv"))
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(!output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("HTTP 404"));
+    }
 }
 
 mod edge_cases {
@@ -486,6 +4053,108 @@ mod edge_cases {
         assert!(stdout.contains("rows: 1"));
     }
 
+    #[test]
+    fn test_max_field_bytes() {
+        let output = csvp()
+            .arg(fixtures_path("special_chars.csv"))
+            .arg("--max-field-bytes")
+            .arg("16")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(!output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("Field too large at line 2, column 2"));
+    }
+
+    #[test]
+    fn test_max_field_bytes_stops_reading() {
+        use std::io::Write;
+
+        // A runaway quoted field from a pipe that is never closed: csvp must
+        // fail at the limit instead of buffering until the input ends
+        let path = &temp_path("runaway.fifo");
+        std::fs::remove_file(path).ok();
+        assert!(
+            std::process::Command::new("mkfifo")
+                .arg(path)
+                .status()
+                .unwrap()
+                .success()
+        );
+
+        let mut child = csvp()
+            .arg(path)
+            .args(["-e", "utf-8", "--max-field-bytes", "1000"])
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .spawn()
+            .expect("Failed to execute command");
+        let mut writer = std::fs::OpenOptions::new().write(true).open(path).unwrap();
+        writer.write_all(b"a,b\n1,2\n3,\"").unwrap();
+        writer.write_all(&[b'x'; 4096]).unwrap();
+        writer.flush().unwrap();
+
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+        let status = loop {
+            if let Some(status) = child.try_wait().unwrap() {
+                break Some(status);
+            }
+            if std::time::Instant::now() > deadline {
+                break None;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        };
+        if status.is_none() {
+            child.kill().unwrap();
+        }
+        drop(writer);
+        let output = child.wait_with_output().unwrap();
+        std::fs::remove_file(path).ok();
+
+        assert!(status.is_some(), "still reading with the pipe open");
+        assert!(!output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(
+            stderr.contains("Field too large at line 3, column 1"),
+            "{stderr}"
+        );
+    }
+
+    #[test]
+    fn test_skip_errors() {
+        let run = |extra: &[&str]| {
+            csvp()
+                .arg(fixtures_path("oversized_field.csv"))
+                .args(["--max-field-bytes", "100", "-f", "json"])
+                .args(extra)
+                .output()
+                .expect("Failed to execute command")
+        };
+
+        assert!(!run(&[]).status.success());
+
+        let output = run(&["--skip-errors"]);
+        assert!(output.status.success());
+        let json = parse_json(&output.stdout);
+        assert_eq!(json[0]["count"], 2);
+        assert_eq!(json[0]["max"], "3");
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("Field too large at line 3"));
+        assert!(stderr.contains("Warning: skipped 1 malformed row\n"));
+
+        let output = run(&["--skip-errors", "--error-format", "json"]);
+        assert!(output.status.success());
+        let warnings: Vec<serde_json::Value> = String::from_utf8_lossy(&output.stderr)
+            .lines()
+            .map(|line| parse_json(line.as_bytes()))
+            .collect();
+        assert_eq!(warnings.len(), 2);
+        assert_eq!(warnings[0]["warning"], "SkippedRow");
+        assert_eq!(warnings[1]["warning"], "SkippedRows");
+        assert_eq!(warnings[1]["count"], 1);
+    }
+
     #[test]
     fn test_special_chars_in_csv() {
         let output = csvp()
@@ -498,3 +4167,148 @@ mod edge_cases {
         assert!(stdout.contains("rows: 4"));
     }
 }
+
+mod examples_option {
+    use super::*;
+
+    #[test]
+    fn test_examples_use_file_columns() {
+        // --rows is not needed just to see examples
+        let output = csvp()
+            .arg("pivot")
+            .arg(fixtures_path("basic.csv"))
+            .arg("--examples")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.starts_with("EXAMPLES (pivot):"));
+        assert!(stdout.contains("--rows name -c active"));
+        assert!(stdout.contains("--value id --agg sum"));
+    }
+
+    #[test]
+    fn test_examples_without_file() {
+        let output = csvp()
+            .arg("--examples")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.starts_with("EXAMPLES (summary):"));
+        assert!(stdout.contains("csvp data.csv -w \"price > 0\""));
+    }
+}
+
+mod timings_option {
+    use super::*;
+
+    #[test]
+    fn test_timings_on_stderr() {
+        let output = csvp()
+            .arg(fixtures_path("basic.csv"))
+            .arg("--timings")
+            .arg("-f")
+            .arg("json")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        // stdout is still just the summary
+        parse_json(&output.stdout);
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.starts_with("Timings:"));
+        for phase in ["read/decode", "parse", "stats", "finalize", "total"] {
+            assert!(stderr.contains(&format!("  {phase} ")), "{stderr}");
+        }
+        let rows = stderr
+            .lines()
+            .find(|l| l.trim_start().starts_with("rows"))
+            .unwrap();
+        assert_eq!(rows.split_whitespace().nth(1), Some("5"));
+        assert!(stderr.contains("unique sets"));
+    }
+}
+
+mod fixed_width_option {
+    use super::*;
+
+    const REPORT: &str = "ACME CORP 001234  NY\n\
+                          WIDGETS\"  000017  \"CA\"\n\
+                          \n\
+                          BOB, INC  000900\n";
+
+    #[test]
+    fn test_fixed_width_slices_and_trims() {
+        let path = &temp_path("fixed_width.txt");
+        std::fs::write(path, REPORT).unwrap();
+
+        let output = csvp()
+            .arg("convert")
+            .arg(path)
+            .arg("--fixed-width")
+            .arg("0-10,10-18,18-")
+            .arg("--header")
+            .arg("company,amount,state")
+            .arg("--to")
+            .arg("csv")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        // Quotes and commas are data; a short line leaves the last field empty
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert_eq!(
+            stdout,
+            "company,amount,state\n\
+             ACME CORP,001234,NY\n\
+             \"WIDGETS\"\"\",000017,\"\"\"CA\"\"\"\n\
+             \"BOB, INC\",000900,\n"
+        );
+
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_fixed_width_generated_headers() {
+        let path = &temp_path("fixed_width_names.txt");
+        std::fs::write(path, REPORT).unwrap();
+
+        let output = csvp()
+            .arg("schema")
+            .arg(path)
+            .arg("--fixed-width")
+            .arg("0-10, 10-18")
+            .arg("-f")
+            .arg("json")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("\"col0\""));
+        assert!(stdout.contains("\"col1\""));
+        assert!(!stdout.contains("\"col2\""));
+
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_fixed_width_rejects_bad_ranges() {
+        for spec in ["0-10,10-5", "0-10,x", "0-10,,10-20"] {
+            let output = csvp()
+                .arg(fixtures_path("basic.csv"))
+                .arg("--fixed-width")
+                .arg(spec)
+                .output()
+                .expect("Failed to execute command");
+
+            assert!(!output.status.success(), "{spec}");
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            assert!(stderr.contains("--fixed-width"), "{stderr}");
+        }
+    }
+}